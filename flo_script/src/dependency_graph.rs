@@ -0,0 +1,274 @@
+use super::symbol_path::*;
+
+use std::fmt::Write;
+use std::collections::{HashMap, HashSet};
+
+///
+/// The ways that a dependency between two symbols can be discovered
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DependencyKind {
+    /// The dependency was found by analysing the source of a script
+    Static,
+
+    /// The dependency was read the last time a script was evaluated
+    Dynamic
+}
+
+///
+/// Indicates that one symbol depends on the value of another
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct DependencyEdge {
+    /// The symbol that reads the dependency
+    pub dependent: FloScriptPath,
+
+    /// The symbol that is read
+    pub dependency: FloScriptPath,
+
+    /// True if the dependency was found by analysing the script
+    pub is_static: bool,
+
+    /// True if the dependency was read the last time the script was evaluated
+    pub is_dynamic: bool
+}
+
+///
+/// The directed graph of the dependencies between the symbols in a notebook
+///
+/// Paths are relative to the notebook that the graph was generated for. Symbols and edges are kept in the order they were
+/// added, so the graph is the same every time it's generated for a notebook.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct DependencyGraph {
+    /// The symbols in the notebook
+    symbols: Vec<FloScriptPath>,
+
+    /// The dependencies between the symbols
+    edges: Vec<DependencyEdge>,
+
+    /// The symbols that are in the graph
+    symbol_set: HashSet<FloScriptPath>,
+
+    /// The index of the edge between each dependent and dependency
+    edge_index: HashMap<(FloScriptPath, FloScriptPath), usize>,
+
+    /// The edges leading from each symbol to the symbols that it depends upon
+    dependency_edges: HashMap<FloScriptPath, Vec<usize>>,
+
+    /// The edges leading to each symbol from the symbols that depend upon it
+    dependent_edges: HashMap<FloScriptPath, Vec<usize>>
+}
+
+impl DependencyGraph {
+    ///
+    /// Creates a new, empty, dependency graph
+    ///
+    pub fn new() -> DependencyGraph {
+        DependencyGraph {
+            symbols:            vec![],
+            edges:              vec![],
+            symbol_set:         HashSet::new(),
+            edge_index:         HashMap::new(),
+            dependency_edges:   HashMap::new(),
+            dependent_edges:    HashMap::new()
+        }
+    }
+
+    ///
+    /// The symbols in this graph
+    ///
+    pub fn symbols(&self) -> &[FloScriptPath] {
+        &self.symbols
+    }
+
+    ///
+    /// The dependencies between the symbols in this graph
+    ///
+    pub fn edges(&self) -> &[DependencyEdge] {
+        &self.edges
+    }
+
+    ///
+    /// True if a symbol is in this graph
+    ///
+    pub fn contains_symbol(&self, symbol: &FloScriptPath) -> bool {
+        self.symbol_set.contains(symbol)
+    }
+
+    ///
+    /// Adds a symbol to this graph
+    ///
+    pub fn add_symbol(&mut self, symbol: FloScriptPath) {
+        if self.symbol_set.insert(symbol.clone()) {
+            self.symbols.push(symbol);
+        }
+    }
+
+    ///
+    /// Records that one symbol depends on another (merging with any existing edge between the same symbols)
+    ///
+    pub fn add_dependency(&mut self, dependent: FloScriptPath, dependency: FloScriptPath, kind: DependencyKind) {
+        let key         = (dependent, dependency);
+
+        let index = if let Some(index) = self.edge_index.get(&key) {
+            *index
+        } else {
+            let index                   = self.edges.len();
+            let (dependent, dependency) = key.clone();
+
+            self.dependency_edges.entry(dependent.clone()).or_default().push(index);
+            self.dependent_edges.entry(dependency.clone()).or_default().push(index);
+            self.edges.push(DependencyEdge { dependent, dependency, is_static: false, is_dynamic: false });
+            self.edge_index.insert(key, index);
+
+            index
+        };
+
+        let edge = &mut self.edges[index];
+
+        match kind {
+            DependencyKind::Static  => edge.is_static = true,
+            DependencyKind::Dynamic => edge.is_dynamic = true
+        }
+    }
+
+    ///
+    /// Retrieves the symbols that a symbol depends upon
+    ///
+    pub fn dependencies_of(&self, symbol: &FloScriptPath) -> Vec<&FloScriptPath> {
+        self.dependency_edges.get(symbol).into_iter()
+            .flatten()
+            .map(|index| &self.edges[*index].dependency)
+            .collect()
+    }
+
+    ///
+    /// Retrieves the symbols that depend upon a symbol
+    ///
+    pub fn dependents_of(&self, symbol: &FloScriptPath) -> Vec<&FloScriptPath> {
+        self.dependent_edges.get(symbol).into_iter()
+            .flatten()
+            .map(|index| &self.edges[*index].dependent)
+            .collect()
+    }
+
+    ///
+    /// Finds a cycle of dependencies that leads from a symbol back to itself
+    ///
+    /// The result is the list of symbols in the cycle, starting with the specified symbol (so each symbol depends on the next one,
+    /// and the last symbol depends on the first one).
+    ///
+    pub fn find_cycle(&self, symbol: &FloScriptPath) -> Option<Vec<FloScriptPath>> {
+        let mut visited = HashSet::new();
+        let mut cycle   = vec![symbol.clone()];
+
+        if self.find_path_to(symbol, symbol, &mut cycle, &mut visited) {
+            Some(cycle)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Searches for a path from one symbol to a target symbol, following dependencies (adding the symbols along the way to `path`)
+    ///
+    fn find_path_to<'a>(&'a self, from: &FloScriptPath, target: &FloScriptPath, path: &mut Vec<FloScriptPath>, visited: &mut HashSet<&'a FloScriptPath>) -> bool {
+        for dependency in self.dependencies_of(from) {
+            if dependency == target {
+                return true;
+            }
+
+            if visited.insert(dependency) {
+                path.push(dependency.clone());
+
+                if self.find_path_to(dependency, target, path, visited) {
+                    return true;
+                }
+
+                path.pop();
+            }
+        }
+
+        false
+    }
+
+    ///
+    /// Lists the symbols that need to be evaluated again after some symbols have changed, in the order they should be evaluated
+    ///
+    /// Every symbol that depends (directly or indirectly) on a changed symbol is listed once, after all of the symbols that it
+    /// depends upon, so evaluating them in this order never mixes old and new values. Symbols in a dependency cycle are left
+    /// out, as they can't be evaluated until the cycle is broken.
+    ///
+    pub fn evaluation_order(&self, changed: &[FloScriptPath]) -> Vec<FloScriptPath> {
+        // Find all of the symbols affected by the change
+        let mut affected: HashSet<&FloScriptPath>   = HashSet::new();
+        let mut pending: Vec<&FloScriptPath>        = changed.iter().collect();
+
+        while let Some(symbol) = pending.pop() {
+            for dependent in self.dependents_of(symbol) {
+                if affected.insert(dependent) {
+                    pending.push(dependent);
+                }
+            }
+        }
+
+        // Repeatedly add the affected symbols whose affected dependencies are already in the order (symbols in a cycle never become ready)
+        let mut order: Vec<&FloScriptPath>          = vec![];
+        let mut ordered: HashSet<&FloScriptPath>    = HashSet::new();
+
+        loop {
+            let ready = self.symbols.iter()
+                .filter(|symbol| affected.contains(symbol) && !ordered.contains(symbol))
+                .filter(|symbol| self.dependencies_of(symbol).into_iter().all(|dependency| !affected.contains(dependency) || ordered.contains(dependency)))
+                .collect::<Vec<_>>();
+
+            if ready.is_empty() { break; }
+
+            ordered.extend(ready.iter().cloned());
+            order.extend(ready);
+        }
+
+        order.into_iter().cloned().collect()
+    }
+
+    ///
+    /// Generates a description of this graph in the Graphviz DOT language
+    ///
+    /// Edges point from a symbol to the symbols that it depends upon. Dependencies that were only seen while evaluating a
+    /// script are dashed, and dependencies that were both found in the script and seen while evaluating it are bold.
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph notebook {\n");
+
+        for symbol in self.symbols.iter() {
+            writeln!(dot, "    {};", dot_id(symbol)).ok();
+        }
+
+        for edge in self.edges.iter() {
+            let style = match (edge.is_static, edge.is_dynamic) {
+                (true, true)    => " [style=bold]",
+                (false, true)   => " [style=dashed]",
+                _               => ""
+            };
+
+            writeln!(dot, "    {} -> {}{};", dot_id(&edge.dependent), dot_id(&edge.dependency), style).ok();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+///
+/// Formats a path as a quoted DOT identifier
+///
+fn dot_id(path: &FloScriptPath) -> String {
+    format!("\"{}\"", path.to_string().replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Default for DependencyGraph {
+    fn default() -> DependencyGraph {
+        DependencyGraph::new()
+    }
+}
@@ -1,5 +1,12 @@
+use super::error::*;
 use super::symbol::*;
+use super::symbol_table::*;
+use super::symbol_path::*;
 use super::script_type_description::*;
+use super::script_type_registry::*;
+use super::script_value::*;
+use super::symbol_metadata::*;
+use super::notebook_definition::*;
 
 use futures::*;
 use futures::stream;
@@ -38,9 +45,85 @@ pub enum ScriptEdit {
     /// Nothing will be computed until the first value is 'pulled' from the resulting stream.
     SetComputingScript(FloScriptSymbol, String),
 
+    /// Specifies that a particular symbol is a computing script that produces values of the specified type
+    /// 
+    /// This is the same as `SetComputingScript`, except the script is compiled and checked against the declared type when
+    /// it's defined rather than when it's first read, and reading the symbol as any other type is an error.
+    SetTypedComputingScript(FloScriptSymbol, ScriptTypeDescription, String),
+
+    /// Specifies that a particular symbol has a constant value
+    /// 
+    /// Constants are read as states: every reader receives the current value, and will receive the new value if the constant
+    /// is redefined with a value of the same type (so scripts that depend on the constant will update).
+    SetConstant(FloScriptSymbol, ScriptValue),
+
+    /// Sets the metadata (label, description, units and so on) for a symbol that's already defined
+    /// 
+    /// Metadata is kept if the symbol is redefined or moved, and is removed along with the symbol.
+    SetMetadata(FloScriptSymbol, SymbolMetadata),
+
     /// Performs one or more edits in a namespace (names declared in this namespace are only visible from scripts that are
     /// also in that namespace)
-    WithNamespace(FloScriptSymbol, Vec<ScriptEdit>)
+    WithNamespace(FloScriptSymbol, Vec<ScriptEdit>),
+
+    /// Moves the symbol at the first path so that it's at the second path instead. Paths are the namespaces containing the
+    /// symbol followed by the symbol itself, relative to the namespace where the edit is performed.
+    /// 
+    /// This keeps the existing definition of the symbol, including any attached input stream and any streams that are reading
+    /// from it. Scripts that refer to the symbol are updated to use its new path wherever they can still reach it. Moving a
+    /// symbol to a path where a symbol is already defined is an error.
+    MoveSymbol(Vec<FloScriptSymbol>, Vec<FloScriptSymbol>)
+}
+
+///
+/// Reports the outcome of applying a single edit from a sequenced edit stream
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct EditAcknowledgement {
+    /// The position of this edit in the sequence of all of the edits applied to the notebook
+    pub sequence: u64,
+
+    /// Whether or not the edit was applied successfully
+    pub result: FloScriptResult<()>
+}
+
+///
+/// The results of checking a set of edits without applying them
+///
+/// Symbols are identified by their path: the namespaces containing the symbol followed by the symbol itself.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct EditValidation {
+    /// The errors that would occur if the edits were applied, along with the symbol that they're for
+    pub diagnostics: Vec<(Vec<FloScriptSymbol>, FloScriptError)>,
+
+    /// The inferred output type of each script defined by the edits, as a type in the scripting language
+    pub output_types: Vec<(Vec<FloScriptSymbol>, String)>
+}
+
+impl EditValidation {
+    ///
+    /// Creates a new validation result with no diagnostics
+    ///
+    pub fn new() -> EditValidation {
+        EditValidation {
+            diagnostics:    vec![],
+            output_types:   vec![]
+        }
+    }
+
+    ///
+    /// True if the edits that were validated would not produce any errors
+    ///
+    pub fn is_valid(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+impl Default for EditValidation {
+    fn default() -> EditValidation {
+        EditValidation::new()
+    }
 }
 
 ///
@@ -52,10 +135,35 @@ pub trait FloScriptEditor : Send+Sync {
     /// has been consumed.
     /// 
     /// Multiple edits can be sent at once to the script editor if needed: if this occurs, the streams are multiplexed and they are
-    /// performed in any order.
+    /// performed in any order. Use `send_sequenced_edits` to find out the order that the edits were applied in.
     ///
     fn send_edits<Edits: 'static+Send+Stream<Item=ScriptEdit, Error=()>>(&self, edits: Edits) -> Box<dyn Future<Item=(), Error=()>>;
 
+    ///
+    /// Performs edits from the specified stream in order, returning a stream that acknowledges each edit once it has been applied
+    ///
+    /// Every edit made to the notebook is assigned a sequence number, in the order that the edits were applied. If several edit
+    /// streams are sent at once, the edits from each stream are still applied in order, but edits from the other streams may be
+    /// applied in between: the sequence numbers in the acknowledgements will show where this happened. The returned stream
+    /// finishes once all of the edits have been acknowledged.
+    ///
+    /// The edits within a `WithNamespace` edit are applied in order until one of them fails. The edits before the one that
+    /// failed stay applied, and its error is the result in the acknowledgement.
+    ///
+    fn send_sequenced_edits<Edits: 'static+Send+Stream<Item=ScriptEdit, Error=()>>(&self, edits: Edits) -> Box<dyn Stream<Item=EditAcknowledgement, Error=()>+Send>;
+
+    ///
+    /// Checks a set of edits against the current state of the notebook without applying them
+    ///
+    /// Any scripts defined by the edits are compiled and type-checked, and every problem that's found is reported.
+    ///
+    fn validate(&self, edits: Vec<ScriptEdit>) -> EditValidation;
+
+    ///
+    /// Retrieves the symbol table of the host that this editor belongs to (which names the symbols that are loaded into it)
+    ///
+    fn symbols(&self) -> FloScriptSymbolTable;
+
     ///
     /// Sends a single edit to a script editor
     ///
@@ -90,4 +198,96 @@ pub trait FloScriptEditor : Send+Sync {
     /// Defines a state computing script which will produce an output stream on the specified symbol
     ///
     fn set_computing_script(&self, output_symbol: FloScriptSymbol, script: &str) { self.edit(ScriptEdit::SetComputingScript(output_symbol, String::from(script))); }
+
+    ///
+    /// Defines a state computing script which will produce an output stream of the specified type on the specified symbol
+    ///
+    fn set_typed_computing_script<OutputType: ScriptType>(&self, output_symbol: FloScriptSymbol, script: &str) { self.edit(ScriptEdit::SetTypedComputingScript(output_symbol, OutputType::description(), String::from(script))); }
+
+    ///
+    /// Defines a symbol as having a constant value
+    ///
+    fn set_constant<Value: Into<ScriptValue>>(&self, symbol: FloScriptSymbol, value: Value) { self.edit(ScriptEdit::SetConstant(symbol, value.into())); }
+
+    ///
+    /// Sets the metadata for a symbol
+    ///
+    fn set_metadata(&self, symbol: FloScriptSymbol, metadata: SymbolMetadata) { self.edit(ScriptEdit::SetMetadata(symbol, metadata)); }
+
+    ///
+    /// Performs an edit on the symbol at the end of a path, creating any namespaces along the path that don't already exist
+    ///
+    fn edit_path<EditFn: FnOnce(FloScriptSymbol) -> ScriptEdit>(&self, path: &FloScriptPath, edit: EditFn) { self.edit(path.edit(edit)); }
+
+    ///
+    /// Sets the type of the data sent to the input symbol at the end of a path
+    ///
+    fn set_input_type_at<InputType: ScriptType>(&self, path: &FloScriptPath) { self.edit_path(path, |symbol| ScriptEdit::SetInputType(symbol, InputType::description())); }
+
+    ///
+    /// Defines a streaming script for the symbol at the end of a path
+    ///
+    fn set_streaming_script_at(&self, path: &FloScriptPath, script: &str) { self.edit_path(path, |symbol| ScriptEdit::SetStreamingScript(symbol, String::from(script))); }
+
+    ///
+    /// Defines a state computing script for the symbol at the end of a path
+    ///
+    fn set_computing_script_at(&self, path: &FloScriptPath, script: &str) { self.edit_path(path, |symbol| ScriptEdit::SetComputingScript(symbol, String::from(script))); }
+
+    ///
+    /// Defines a state computing script that produces values of the specified type for the symbol at the end of a path
+    ///
+    fn set_typed_computing_script_at<OutputType: ScriptType>(&self, path: &FloScriptPath, script: &str) { self.edit_path(path, |symbol| ScriptEdit::SetTypedComputingScript(symbol, OutputType::description(), String::from(script))); }
+
+    ///
+    /// Defines the symbol at the end of a path as having a constant value
+    ///
+    fn set_constant_at<Value: Into<ScriptValue>>(&self, path: &FloScriptPath, value: Value) { self.edit_path(path, |symbol| ScriptEdit::SetConstant(symbol, value.into())); }
+
+    ///
+    /// Renames a symbol, updating any scripts in the same namespace that refer to it
+    ///
+    fn rename_symbol(&self, old_symbol: FloScriptSymbol, new_symbol: FloScriptSymbol) { self.edit(ScriptEdit::MoveSymbol(vec![old_symbol], vec![new_symbol])); }
+
+    ///
+    /// Moves a symbol to a new path (paths are the namespaces containing a symbol followed by the symbol itself)
+    ///
+    fn move_symbol(&self, from: Vec<FloScriptSymbol>, to: Vec<FloScriptSymbol>) { self.edit(ScriptEdit::MoveSymbol(from, to)); }
+
+    ///
+    /// Replaces the contents of this editor with the symbols from a saved notebook definition
+    ///
+    /// The types of any input symbols are looked up in the specified registry. Nothing is edited if any of them are
+    /// unknown. If any of the symbols can't be defined, the result is the first error that occurred. The symbols are
+    /// taken from the symbol table returned by `symbols()`.
+    ///
+    fn load_definition(&self, definition: &NotebookDefinition, types: &ScriptTypeRegistry) -> FloScriptResult<()> {
+        let symbols             = self.symbols();
+        let edits               = definition.edits(&symbols, types)?;
+        let num_edits           = edits.len() + 1;
+        let acknowledgements    = self.send_sequenced_edits(stream::iter_ok(Some(ScriptEdit::Clear).into_iter().chain(edits.clone())));
+        let result              = wait_for_loaded_edits(acknowledgements, num_edits);
+
+        // The notebook retains the symbols it has defined, so the edits no longer need to
+        release_edit_symbols(&symbols, &edits);
+
+        result
+    }
+}
+
+///
+/// Waits for the edits that load a notebook to be applied, returning the first error from their acknowledgements
+///
+/// It's an error if fewer than `num_edits` edits are acknowledged (which happens if the notebook is dropped while it's being loaded)
+///
+pub (crate) fn wait_for_loaded_edits<Acknowledgements: Stream<Item=EditAcknowledgement, Error=()>>(acknowledgements: Acknowledgements, num_edits: usize) -> FloScriptResult<()> {
+    let acknowledgements = executor::spawn(acknowledgements.collect()).wait_future()
+        .map_err(|_| FloScriptError::LoadError("The notebook stopped accepting edits while it was being loaded".to_string()))?;
+
+    if acknowledgements.len() < num_edits {
+        return Err(FloScriptError::LoadError("The notebook stopped accepting edits while it was being loaded".to_string()));
+    }
+
+    acknowledgements.into_iter()
+        .try_for_each(|acknowledgement| acknowledgement.result)
 }
@@ -1,6 +1,9 @@
 use super::symbol::*;
+use super::symbol_path::*;
+use super::script_type_description::*;
 
 use std::result::Result;
+use std::time::{Duration};
 
 ///
 /// Possible errors from a script call
@@ -16,18 +19,66 @@ pub enum FloScriptError {
     /// Tried to attach an input stream to a symbol that is not defined as an input symbol
     NotAnInputSymbol,
 
+    /// Tried to define a symbol where a symbol is already defined (for example, by moving a symbol on top of another one)
+    AlreadyDefined(FloScriptSymbol),
+
     /// Tried to perform an operation on an item that is not a namespace
     NotANamespace,
 
     /// Attempted to read from a namespace as if it were a stream
     CannotReadFromANamespace,
 
-    /// Requested an output or an input with the wrong type
-    IncorrectType,
+    /// Requested an output or an input with the wrong type (with descriptions of the type that was expected and the type that was requested)
+    IncorrectType { expected: String, requested: String },
+
+    /// A number could not be converted to another numeric type because it's outside of the range of that type (with the value and a description of the type)
+    OutOfRange { value: String, requested: String },
+
+    /// A symbol has a name that can't be used from a script (for example, because it contains spaces or is a keyword)
+    InvalidSymbolName(String),
+
+    /// A symbol would be referred to by the same name in scripts as another symbol in its namespace (for example, because its mangled name is the name of the other symbol)
+    DuplicateScriptName(String),
+
+    /// A script depends on itself, directly or via other scripts (the symbols involved in the cycle are listed in order)
+    DependencyCycle(Vec<FloScriptPath>),
+
+    /// A symbol path was not valid (for example, because part of the name was empty)
+    InvalidPath(String),
+
+    /// A type can't be used by a script (for example, because the Gluon module that defines it is not loaded)
+    UnsupportedType(String),
+
+    /// A type name (for example, in a saved notebook) does not correspond to any known type
+    UnknownTypeName(String),
+
+    /// A type has not been given a name in the type registry, so it can't be saved in a notebook (with the name of the Rust type)
+    UnregisteredType(String),
+
+    /// A notebook could not be loaded (with a description of why)
+    LoadError(String),
+
+    /// A script was stopped because it took longer than the time limit set for its namespace
+    TimeLimitExceeded(Duration),
+
+    /// A script was stopped because it took more than the number of steps allowed by its namespace
+    StepLimitExceeded(u64),
+
+    /// A script was stopped because it tried to allocate more than the number of bytes allowed by its namespace
+    MemoryLimitExceeded(usize),
 
     /// Indicates an error from the script
     ScriptError(String)
 }
 
+impl FloScriptError {
+    ///
+    /// Creates an error indicating that a symbol was used with the wrong type
+    ///
+    pub fn incorrect_type(expected: &ScriptTypeDescription, requested: &ScriptTypeDescription) -> FloScriptError {
+        FloScriptError::IncorrectType { expected: expected.to_string(), requested: requested.to_string() }
+    }
+}
+
 /// Result from a script operation
 pub type FloScriptResult<T> = Result<T, FloScriptError>;
@@ -1,15 +1,24 @@
 use super::derived_state::*;
+use super::execution_limits::*;
+use super::script_executor::*;
+use super::script_dependencies::*;
 use super::super::error::*;
+use super::super::symbol::*;
+use super::super::script_type_description::*;
 
 use gluon::{RootedThread, Compiler};
 use gluon::compiler_pipeline::{CompileValue, Executable};
-use gluon::vm::api::{VmType, Getable};
+use gluon::vm::api::{VmType, Getable, Function, OpaqueValue, Hole};
+use gluon::vm::thread::{RootedValue};
 use gluon::base::ast::{SpannedExpr};
 use gluon::base::symbol::{Symbol};
 use futures::*;
+use futures::future;
 
+use std::any::*;
 use std::mem;
 use std::sync::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::marker::PhantomData;
 
 ///
@@ -17,7 +26,10 @@ use std::marker::PhantomData;
 ///
 enum ComputingScriptState<Item> {
     /// Script is running and will produce a simple result
-    GeneratingResult(Box<dyn Future<Item=Item, Error=gluon::Error>+Send>),
+    GeneratingResult(Box<dyn Future<Item=Item, Error=FloScriptError>+Send>),
+
+    /// Script reads symbols from its namespace and is waiting for their values to change
+    WaitingForSymbols,
 
     /// Script has completed (has run and no longer depends on anything from the namespace)
     Finished
@@ -36,68 +48,297 @@ pub struct ComputingScriptStream<Item> {
     /// The compiler that created the script
     compiler: Arc<Mutex<Compiler>>,
 
+    /// The symbols read by the script (if it reads any)
+    symbols: Option<SymbolInputs>,
+
+    /// The limits that apply to each evaluation of the script
+    limits: ExecutionLimits,
+
+    /// The executor that evaluates the script (or `None` to evaluate it while this stream is polled)
+    executor: Option<Arc<dyn ScriptExecutor>>,
+
+    /// The thread that's evaluating the script (if it's running)
+    evaluating: Arc<Mutex<Option<RootedThread>>>,
+
+    /// Set once the script has been cancelled (so it's not evaluated again)
+    cancelled: Arc<AtomicBool>,
+
+    /// The paths of the symbols that were read the last time the script was evaluated
+    read_symbols: Arc<Mutex<Vec<Vec<FloScriptSymbol>>>>,
+
     /// We don't actually store any item of the specified data type
     item: PhantomData<Item>
 }
 
+///
+/// The symbols read by a computing script, and their most recent values
+///
+struct SymbolInputs {
+    /// The function that evaluates the script, given the values of its symbols
+    function: RootedValue<RootedThread>,
+
+    /// The symbols that the script reads
+    dependencies: Arc<ScriptDependencies>,
+
+    /// The streams of values for each symbol (`None` once a stream has finished)
+    inputs: Vec<Option<AnyValueStream>>,
+
+    /// The most recent value of each symbol
+    values: Vec<Option<Box<dyn Any+Send>>>,
+
+    /// True if any of the values have changed since the script was last evaluated
+    changed: bool
+}
+
+///
+/// The result of polling the symbols read by a script
+///
+enum SymbolPoll {
+    /// The values have changed and the script should be evaluated again
+    Changed(DependencyValues),
+
+    /// The values have not changed
+    Unchanged,
+
+    /// None of the values can change any more
+    Finished
+}
+
+
 impl<Item> ComputingScriptStream<Item> 
 where   for<'vm> DerivedState<'vm, Item>:   VmType,
-        Item:                               for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static {
+        Item:                               ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static {
     ///
     /// Creates a new computing thread that reads from the specified symbol
     ///
-    pub fn new(root_thread: Arc<RootedThread>, script: CompileValue<SpannedExpr<Symbol>>, compiler: Compiler) -> FloScriptResult<ComputingScriptStream<Item>> {
-        let symbol_type         = Item::make_type(&*root_thread);
-        let derived_state_type  = DerivedState::<Item>::make_type(&*root_thread);
-        let mut compiler        = compiler;
+    /// The script is evaluated on its own thread, which is stopped if it exceeds the specified limits. If an executor is supplied, the
+    /// script runs there and this stream only returns its result once it has finished.
+    ///
+    pub fn new(root_thread: Arc<RootedThread>, script: CompileValue<SpannedExpr<Symbol>>, compiler: Compiler, limits: ExecutionLimits, executor: Option<&Arc<dyn ScriptExecutor>>) -> FloScriptResult<ComputingScriptStream<Item>> {
+        let symbol_type         = make_gluon_type::<Item>(&*root_thread)?;
+        let derived_state_type  = make_gluon_type::<DerivedState<Item>>(&*root_thread)?;
+        let compiler            = Arc::new(Mutex::new(compiler));
 
-        let initial_state = if script.typ == symbol_type {
+        let evaluating          = Arc::new(Mutex::new(None));
+
+        let initial_state = if same_gluon_type(&script.typ, &symbol_type) {
             // Computed expression with no dependencies
             let root_copy       = Arc::clone(&root_thread);
             let thread          = root_thread.new_thread().expect("script thread");
-            let future_result   = script.run_expr(&mut compiler, thread, "", "", ())
-                .map(move |result| Item::from_value(&*root_copy, result.value.get_variant()));
+            let exceeded        = limits.apply_to_thread(&thread);
+            *evaluating.lock().unwrap() = Some(thread.clone());
+            let run_compiler    = Arc::clone(&compiler);
+
+            // Gluon evaluates the script as soon as `run_expr` is called, so defer that until the stream is polled (otherwise a long
+            // evaluation would block the namespace, and it could never be cancelled)
+            let future_result   = future::lazy(move || script.run_expr(&mut run_compiler.lock().unwrap(), thread, "", "", ()))
+                .map(move |result| Item::from_value(&*root_copy, result.value.get_variant()))
+                .map_err(move |error| limits.evaluation_error(&exceeded, &error));
 
-            ComputingScriptState::GeneratingResult(Box::new(future_result))
-        } else if script.typ == derived_state_type {
+            match executor {
+                Some(executor)  => ComputingScriptState::GeneratingResult(evaluate_on(executor, Box::new(future_result))),
+                None            => ComputingScriptState::GeneratingResult(Box::new(future_result))
+            }
+        } else if same_gluon_type(&script.typ, &derived_state_type) {
             // Computed expression with dependencies
             ComputingScriptState::Finished
         } else {
             // Not a valid type
-            return Err(FloScriptError::IncorrectType);
+            return Err(incorrect_script_type(&script.typ, &Item::description(), &root_thread));
         };
 
         Ok(ComputingScriptStream {
             root:           root_thread,
-            compiler:       Arc::new(Mutex::new(compiler)),
+            compiler,
+            symbols:        None,
+            limits,
+            executor:       executor.cloned(),
+            evaluating,
+            cancelled:      Arc::new(AtomicBool::new(false)),
+            read_symbols:   Arc::new(Mutex::new(vec![])),
             state:          initial_state,
             item:           PhantomData
         })
     }
+
+    ///
+    /// Creates a computing stream for a script that reads symbols from its namespace
+    ///
+    /// The script must have been compiled by `ScriptDependencies::compile()`, and the inputs are state streams of the values of
+    /// each of its symbols. The script is evaluated once every symbol has a value, then again whenever any of them change. The
+    /// stream finishes once none of the symbols can change any more.
+    ///
+    pub fn with_symbols(root_thread: Arc<RootedThread>, script: CompileValue<SpannedExpr<Symbol>>, dependencies: Arc<ScriptDependencies>, inputs: Vec<AnyValueStream>, compiler: Compiler, limits: ExecutionLimits, executor: Option<&Arc<dyn ScriptExecutor>>) -> FloScriptResult<ComputingScriptStream<Item>> {
+        let symbol_type     = make_gluon_type::<Item>(&*root_thread)?;
+        let script_type     = script.typ.as_function().map(|(_, output_type)| output_type.clone()).unwrap_or_else(|| script.typ.clone());
+        let mut compiler    = compiler;
+
+        if !same_gluon_type(&script_type, &symbol_type) {
+            return Err(incorrect_script_type(&script_type, &Item::description(), &root_thread));
+        }
+
+        // Running the compiled script produces the function that evaluates it
+        let thread          = root_thread.new_thread().map_err(|error| FloScriptError::ScriptError(error.to_string()))?;
+        let function        = script.run_expr(&mut compiler, thread, "", "", ()).wait()
+            .map_err(|error| FloScriptError::ScriptError(error.to_string()))?
+            .value;
+
+        let values          = inputs.iter().map(|_| None).collect();
+        let inputs          = inputs.into_iter().map(Some).collect();
+
+        Ok(ComputingScriptStream {
+            root:           root_thread,
+            compiler:       Arc::new(Mutex::new(compiler)),
+            symbols:        Some(SymbolInputs { function, dependencies, inputs, values, changed: false }),
+            limits,
+            executor:       executor.cloned(),
+            evaluating:     Arc::new(Mutex::new(None)),
+            cancelled:      Arc::new(AtomicBool::new(false)),
+            read_symbols:   Arc::new(Mutex::new(vec![])),
+            state:          ComputingScriptState::WaitingForSymbols,
+            item:           PhantomData
+        })
+    }
 }
 
-impl<'vm, Item> ComputingScriptStream<Item> 
-where   Item: for<'value> Getable<'vm, 'value> + VmType + Send + 'static {
+impl<Item> ComputingScriptStream<Item> {
+    ///
+    /// Returns the list of symbols that were read the last time this script was evaluated (which is updated as the script runs)
+    ///
+    /// Paths are relative to the namespace that the script is defined in. The list is empty until the script is first evaluated.
+    ///
+    pub fn read_symbols(&self) -> Arc<Mutex<Vec<Vec<FloScriptSymbol>>>> {
+        Arc::clone(&self.read_symbols)
+    }
+
+    ///
+    /// Returns a function that will stop the evaluation of this script (which then fails with an error)
+    ///
+    /// This can be called from any thread, which makes it possible to stop a script that's stuck in a loop. Scripts that read
+    /// symbols are not evaluated again once they've been stopped.
+    ///
+    pub fn canceller(&self) -> impl FnOnce()+Send {
+        let evaluating  = Arc::clone(&self.evaluating);
+        let cancelled   = Arc::clone(&self.cancelled);
+
+        move || {
+            cancelled.store(true, Ordering::SeqCst);
+
+            if let Some(evaluating) = evaluating.lock().unwrap().as_ref() {
+                evaluating.interrupt();
+            }
+        }
+    }
+}
+
+impl SymbolInputs {
+    ///
+    /// Reads any new values for the symbols
+    ///
+    fn poll(&mut self) -> Result<SymbolPoll, FloScriptError> {
+        for (input, value) in self.inputs.iter_mut().zip(self.values.iter_mut()) {
+            // Only the most recent value of each symbol matters
+            while let Some(stream) = input {
+                match stream.poll() {
+                    Ok(Async::Ready(Some(new_value)))   => { *value = Some(new_value); self.changed = true; },
+                    Ok(Async::Ready(None))              => { *input = None; },
+                    Ok(Async::NotReady)                 => break,
+                    Err(error)                          => return Err(error)
+                }
+            }
+        }
+
+        if self.changed && self.values.iter().all(|value| value.is_some()) {
+            // Evaluate the script with the latest values
+            let values      = self.values.iter().map(|value| &**value.as_ref().unwrap()).collect::<Vec<_>>();
+            self.changed    = false;
+
+            Ok(SymbolPoll::Changed(DependencyValues::new(&self.dependencies, &values)))
+        } else if self.inputs.iter().all(|input| input.is_none()) {
+            Ok(SymbolPoll::Finished)
+        } else {
+            Ok(SymbolPoll::Unchanged)
+        }
+    }
+}
+
+impl<Item> ComputingScriptStream<Item> 
+where   Item: for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static {
     ///
     /// Given a script in the 'GeneratingResult' state, 
     ///
-    fn poll_for_simple_result(mut future_result: Box<dyn Future<Item=Item, Error=gluon::Error>+Send>) -> (ComputingScriptState<Item>, Poll<Option<Item>, ()>) {
+    fn poll_for_simple_result(&self, mut future_result: Box<dyn Future<Item=Item, Error=FloScriptError>+Send>) -> (ComputingScriptState<Item>, Poll<Option<Item>, FloScriptError>) {
         use self::ComputingScriptState::*;
 
+        // Scripts that read symbols wait for them to change once they've been evaluated
+        let next_state = if self.symbols.is_some() { WaitingForSymbols } else { Finished };
+
         match future_result.poll() {
             Ok(Async::NotReady)         => (GeneratingResult(future_result), Ok(Async::NotReady)),
-            Ok(Async::Ready(result))    => (Finished, Ok(Async::Ready(Some(result)))),
-            Err(_err)                   => (Finished, Err(()))
+            Ok(Async::Ready(result))    => (next_state, Ok(Async::Ready(Some(result)))),
+            Err(err)                    => (Finished, Err(err))
+        }
+    }
+
+    ///
+    /// Given a script in the 'WaitingForSymbols' state, starts evaluating it if any of its symbols have changed
+    ///
+    fn poll_for_symbols(&mut self) -> (ComputingScriptState<Item>, Poll<Option<Item>, FloScriptError>) {
+        use self::ComputingScriptState::*;
+
+        let symbols = match self.symbols.as_mut() {
+            Some(symbols)   => symbols,
+            None            => return (Finished, Ok(Async::Ready(None)))
+        };
+
+        match symbols.poll() {
+            Ok(SymbolPoll::Unchanged)       => (WaitingForSymbols, Ok(Async::NotReady)),
+            Ok(SymbolPoll::Finished)        => (Finished, Ok(Async::Ready(None))),
+            Err(err)                        => (Finished, Err(err)),
+            Ok(SymbolPoll::Changed(values)) => {
+                if self.cancelled.load(Ordering::SeqCst) {
+                    return (Finished, Err(FloScriptError::Unavailable("Script was cancelled".to_string())));
+                }
+
+                // Each evaluation runs on a new thread, so the limits apply to each one separately
+                let thread = match self.root.new_thread() {
+                    Ok(thread)  => thread,
+                    Err(err)    => return (Finished, Err(FloScriptError::ScriptError(err.to_string())))
+                };
+
+                let exceeded                        = self.limits.apply_to_thread(&thread);
+                let limits                          = self.limits;
+                let root                            = Arc::clone(&self.root);
+                let mut function                    = Function::<RootedThread, fn(DependencyValues) -> OpaqueValue<RootedThread, Hole>>::from_value(&thread, symbols.function.get_variant());
+                *self.evaluating.lock().unwrap()    = Some(thread);
+
+                // The symbols that were read are known once the evaluation has finished
+                let reads                           = values.reads();
+                let dependencies                    = Arc::clone(&symbols.dependencies);
+                let read_symbols                    = Arc::clone(&self.read_symbols);
+
+                let future_result = future::lazy(move || function.call_async(values))
+                    .then(move |result| { *read_symbols.lock().unwrap() = reads.symbols(&dependencies); result })
+                    .map(move |result| Item::from_value(&*root, result.get_variant()))
+                    .map_err(move |error| limits.evaluation_error(&exceeded, &gluon::Error::VM(error)));
+
+                let future_result: Box<dyn Future<Item=Item, Error=FloScriptError>+Send> = match &self.executor {
+                    Some(executor)  => evaluate_on(executor, Box::new(future_result)),
+                    None            => Box::new(future_result)
+                };
+
+                self.poll_for_simple_result(future_result)
+            }
         }
     }
 }
 
-impl<'vm, Item> Stream for ComputingScriptStream<Item>
-where   Item: for<'value> Getable<'vm, 'value> + VmType + Send + 'static {
+impl<Item> Stream for ComputingScriptStream<Item>
+where   Item: for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static {
     type Item = Item;
-    type Error = ();
+    type Error = FloScriptError;
 
-    fn poll(&mut self) -> Poll<Option<Item>, ()> {
+    fn poll(&mut self) -> Poll<Option<Item>, FloScriptError> {
         use self::ComputingScriptState::*;
 
         // Steal the current state of the stream (we'll wind up in the finished state if there's a panic or something)
@@ -106,7 +347,8 @@ where   Item: for<'value> Getable<'vm, 'value> + VmType + Send + 'static {
 
         // Dispatch the next action based on the current script state
         let (new_state, result) = match current_state {
-            GeneratingResult(future_result) => Self::poll_for_simple_result(future_result),
+            GeneratingResult(future_result) => self.poll_for_simple_result(future_result),
+            WaitingForSymbols               => self.poll_for_symbols(),
             Finished                        => (Finished, Ok(Async::Ready(None)))
         };
 
@@ -1,5 +1,7 @@
 use super::editor::*;
 use super::core_namespace::*;
+use super::super::error::*;
+use super::super::symbol::*;
 use super::super::editor::*;
 
 use desync::Desync;
@@ -14,6 +16,9 @@ use std::sync::*;
 pub struct GluonScriptHostCore {
     /// The root namespace
     root_namespace: Arc<Desync<GluonScriptNamespace>>,
+
+    /// The sequence number to assign to the next edit
+    next_sequence: u64
 }
 
 impl GluonScriptHostCore {
@@ -24,39 +29,149 @@ impl GluonScriptHostCore {
         let root_namespace = GluonScriptNamespace::new();
 
         GluonScriptHostCore { 
-            root_namespace: Arc::new(Desync::new(root_namespace))
+            root_namespace: Arc::new(Desync::new(root_namespace)),
+            next_sequence:  0
         }
     }
 
     ///
     /// Perform an edit on a namespace
     ///
-    fn edit_namespace(namespace: &mut GluonScriptNamespace, edit: GluonScriptEdit) {
+    /// Edits to a child namespace are applied in order until one of them fails: the result is the error from that edit,
+    /// and the edits after it are not applied.
+    ///
+    fn edit_namespace(namespace: &mut GluonScriptNamespace, edit: GluonScriptEdit) -> FloScriptResult<()> {
         use self::GluonScriptEdit::*;
         use self::ScriptEdit::*;
 
+        Self::check_names(namespace, &edit)?;
+
         match edit {
-            ScriptEdit(Clear)                                   => { namespace.clear(); }
-            ScriptEdit(UndefineSymbol(symbol))                  => { namespace.undefine_symbol(symbol); }
-            ScriptEdit(SetInputType(symbol, input_type))        => { namespace.define_input_symbol(symbol, input_type); }
-            ScriptEdit(SetStreamingScript(symbol, script_src))  => { namespace.set_streaming_script(symbol, script_src); }
-            ScriptEdit(SetComputingScript(symbol, script_src))  => { namespace.set_computing_script(symbol, script_src); }
-            SetRunIo(run_io)                                    => { namespace.set_run_io(run_io); }
-
-            ScriptEdit(WithNamespace(symbol, edits))            => {
+            ScriptEdit(Clear)                                   => { namespace.clear(); Ok(()) }
+            ScriptEdit(UndefineSymbol(symbol))                  => { namespace.undefine_symbol(symbol) }
+            ScriptEdit(SetInputType(symbol, input_type))        => { namespace.define_input_symbol(symbol, input_type); Ok(()) }
+            ScriptEdit(SetStreamingScript(symbol, script_src))  => { namespace.set_streaming_script(symbol, script_src) }
+            ScriptEdit(SetComputingScript(symbol, script_src))  => { namespace.set_computing_script(symbol, script_src) }
+            ScriptEdit(SetTypedComputingScript(symbol, output_type, script_src)) => { namespace.set_typed_computing_script(symbol, output_type, script_src) }
+            ScriptEdit(SetConstant(symbol, value))              => { namespace.set_constant(symbol, value) }
+            ScriptEdit(SetMetadata(symbol, metadata))           => { namespace.set_metadata(symbol, metadata) }
+            ScriptEdit(MoveSymbol(from, to))                    => { namespace.move_symbol(&from, &to) }
+            SetRunIo(run_io)                                    => { namespace.set_run_io(run_io); Ok(()) }
+            SetNamePolicy(name_policy)                          => { namespace.set_name_policy(name_policy) }
+            SetExecutionLimits(limits)                          => { namespace.set_execution_limits(limits); Ok(()) }
+
+            ScriptEdit(self::ScriptEdit::WithNamespace(symbol, edits)) => {
+                Self::edit_namespace(namespace, GluonScriptEdit::WithNamespace(symbol, edits.into_iter().map(ScriptEdit).collect()))
+            }
+
+            GluonScriptEdit::WithNamespace(symbol, edits)       => {
                 namespace.get_or_create_namespace(symbol)
-                    .map(|namespace| {
-                        namespace.sync(move |namespace| {
-                            edits.into_iter().for_each(|edit| {
-                                Self::edit_namespace(namespace, ScriptEdit(edit))
-                            });
-                        });
+                    .and_then(|namespace| {
+                        namespace.sync(move |namespace| edits.into_iter().try_for_each(|edit| Self::edit_namespace(namespace, edit)))
                     })
-                    .ok();
             }
         }
     }
 
+    ///
+    /// Checks that the names of any symbols defined by an edit can be used in the namespace
+    ///
+    fn check_names(namespace: &GluonScriptNamespace, edit: &GluonScriptEdit) -> FloScriptResult<()> {
+        use self::GluonScriptEdit::*;
+        use self::ScriptEdit::*;
+
+        match edit {
+            ScriptEdit(SetInputType(symbol, _))                     |
+            ScriptEdit(SetStreamingScript(symbol, _))               |
+            ScriptEdit(SetComputingScript(symbol, _))               |
+            ScriptEdit(SetTypedComputingScript(symbol, _, _))       |
+            ScriptEdit(SetConstant(symbol, _))                      |
+            ScriptEdit(self::ScriptEdit::WithNamespace(symbol, _))  |
+            GluonScriptEdit::WithNamespace(symbol, _)               => namespace.check_symbol_name(*symbol),
+
+            // Namespaces along the path use the policy of this namespace too (as they inherit it when they're created)
+            ScriptEdit(MoveSymbol(_, to))                           => to.iter().try_for_each(|symbol| namespace.check_symbol_name(*symbol)),
+
+            ScriptEdit(Clear)                                       |
+            ScriptEdit(UndefineSymbol(_))                           |
+            ScriptEdit(SetMetadata(_, _))                           |
+            SetRunIo(_)                                             |
+            SetNamePolicy(_)                                        |
+            SetExecutionLimits(_)                                   => Ok(())
+        }
+    }
+
+    ///
+    /// Applies edits to a namespace, type-checking any scripts that they define and storing the results in a validation
+    ///
+    fn validate_namespace(namespace: &mut GluonScriptNamespace, path: &[FloScriptSymbol], edits: Vec<ScriptEdit>, validation: &mut EditValidation) {
+        use self::ScriptEdit::*;
+
+        for edit in edits {
+            match edit {
+                WithNamespace(symbol, edits)                => {
+                    let namespace_path = path.iter().cloned().chain(Some(symbol)).collect::<Vec<_>>();
+
+                    match namespace.check_symbol_name(symbol).and_then(|_| namespace.get_or_create_namespace(symbol)) {
+                        Ok(child_namespace) => child_namespace.sync(|child_namespace| Self::validate_namespace(child_namespace, &namespace_path, edits, validation)),
+                        Err(error)          => validation.diagnostics.push((namespace_path, error))
+                    }
+                }
+
+                SetStreamingScript(symbol, ref script_src)              |
+                SetComputingScript(symbol, ref script_src)              |
+                SetTypedComputingScript(symbol, _, ref script_src)      => {
+                    let symbol_path = path.iter().cloned().chain(Some(symbol)).collect::<Vec<_>>();
+
+                    if let Err(error) = namespace.check_symbol_name(symbol) {
+                        validation.diagnostics.push((symbol_path, error));
+                        continue;
+                    }
+
+                    let typecheck_error = match namespace.typecheck_script(symbol, script_src) {
+                        Ok(output_type) => { validation.output_types.push((symbol_path.clone(), output_type)); None },
+                        Err(error)      => { validation.diagnostics.push((symbol_path.clone(), error.clone())); Some(error) }
+                    };
+
+                    // Defining the script can still fail (for instance, if it depends on itself or doesn't produce its declared type)
+                    if let Err(error) = Self::edit_namespace(namespace, GluonScriptEdit::ScriptEdit(edit)) {
+                        // Scripts with a declared type are compiled when they're defined, so don't report the same error twice
+                        if Some(&error) != typecheck_error.as_ref() {
+                            validation.diagnostics.push((symbol_path, error));
+                        }
+                    }
+                }
+
+                other_edit                                  => {
+                    let edit_path = match other_edit {
+                        UndefineSymbol(symbol)  |
+                        SetInputType(symbol, _) |
+                        SetConstant(symbol, _)  |
+                        SetMetadata(symbol, _)  => path.iter().cloned().chain(Some(symbol)).collect::<Vec<_>>(),
+                        _                       => path.to_vec()
+                    };
+
+                    if let Err(error) = Self::edit_namespace(namespace, GluonScriptEdit::ScriptEdit(other_edit)) {
+                        validation.diagnostics.push((edit_path, error));
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Checks what would happen if a set of edits were applied to this core, without changing it
+    ///
+    pub fn validate(&self, edits: Vec<ScriptEdit>) -> EditValidation {
+        // Edits are applied to a copy of the root namespace
+        let mut namespace       = self.root_namespace.sync(|root_namespace| root_namespace.detached_copy());
+        let mut validation      = EditValidation::new();
+
+        Self::validate_namespace(&mut namespace, &[], edits, &mut validation);
+
+        validation
+    }
+
     ///
     /// Retrieves the root namespace for this core
     ///
@@ -65,9 +180,16 @@ impl GluonScriptHostCore {
     }
 
     ///
-    /// Performs an edit action on this core
+    /// Performs an edit action on this core, returning the sequence number assigned to the edit and its result
+    ///
+    /// Every edit performed on the core is assigned a sequence number, in the order that the edits are applied.
     ///
-    pub fn edit(&mut self, edit: GluonScriptEdit) {
-        self.root_namespace.sync(|root_namespace| Self::edit_namespace(root_namespace, edit));
+    pub fn edit(&mut self, edit: GluonScriptEdit) -> (u64, FloScriptResult<()>) {
+        let sequence        = self.next_sequence;
+        self.next_sequence  += 1;
+
+        let result          = self.root_namespace.sync(|root_namespace| Self::edit_namespace(root_namespace, edit));
+
+        (sequence, result)
     }
 }
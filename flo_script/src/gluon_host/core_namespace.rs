@@ -1,23 +1,44 @@
 use super::computing_script::*;
-use super::derived_state;
+use super::script_dependencies::*;
+use super::script_rewrite::*;
+use super::symbol_names::*;
+use super::symbol_status::*;
+use super::execution_limits::*;
+use super::script_executor::*;
+use super::vm_sharing::*;
+use super::script_type_declaration::*;
 use super::derived_state::{DerivedStateData};
 use super::super::script_type_description::*;
 use super::super::streams::*;
 use super::super::symbol::*;
+use super::super::symbol_table::{retain_symbol, release_symbol};
 use super::super::error::*;
+use super::super::notebook_definition::*;
+use super::super::script_type_registry::*;
+use super::super::script_value::*;
+use super::super::input_value::*;
+use super::super::symbol_metadata::*;
+use super::super::notebook::*;
+use super::super::notebook_symbol::*;
+use super::super::symbol_path::*;
+use super::super::dependency_graph::*;
 
 use desync::Desync;
 use gluon::*;
-use gluon::compiler_pipeline::{Compileable};
+use gluon::compiler_pipeline::{Compileable, CompileValue};
+use gluon::base::ast::{SpannedExpr};
+use gluon::base::symbol::{Symbol as GluonSymbol};
 use gluon::vm::{ExternModule};
+use gluon::base::types::{ArcType, Type};
 use gluon::vm::api::{VmType, Getable};
 use futures::*;
 use futures::future;
 use futures::sync::oneshot;
+use futures::sync::mpsc;
 
 use std::any::*;
 use std::sync::*;
-use std::collections::{HashMap};
+use std::collections::{HashMap, HashSet};
 use std::result::{Result};
 
 ///
@@ -28,27 +49,64 @@ enum SymbolDefinition {
     /// Symbol is an input stream
     Input(InputStreamSource),
 
-    /// An instantiated script acting as an input source
-    ActiveScript(InputStreamSource),
+    /// An instantiated computing script (with its source) acting as an input source
+    ActiveScript(Arc<String>, InputStreamSource),
 
-    /// Symbol represents a script that couldn't be compiled
-    ScriptError(String),
+    /// Symbol represents a script that couldn't be compiled (with its source and the error)
+    ScriptError(Arc<String>, String),
 
     /// Compiled computing expression
     Computing(Arc<String>),
 
+    /// Streaming script that has not been started
+    Streaming(Arc<String>),
+
+    /// Symbol is a constant (read from an input stream that never finishes)
+    Constant(ScriptValue, InputStreamSource),
+
     /// Symbol is a namespace
     Namespace(Arc<Desync<GluonScriptNamespace>>)
 }
 
+impl SymbolDefinition {
+    ///
+    /// The source of the script that defines the symbol, if it's defined by a script
+    ///
+    fn script(&self) -> Option<&Arc<String>> {
+        use self::SymbolDefinition::*;
+
+        match self {
+            ActiveScript(script, _)     |
+            ScriptError(script, _)      |
+            Computing(script)           |
+            Streaming(script)           => Some(script),
+
+            Input(_)                    |
+            Constant(_, _)              |
+            Namespace(_)                => None
+        }
+    }
+}
+
+/// A symbol definition that has been removed from a namespace, along with its metadata, status and declared output type
+type TakenDefinition = (SymbolDefinition, Option<SymbolMetadata>, Option<SymbolStatusCell>, Option<ScriptTypeDescription>);
+
 ///
 /// Represents a script namespace
 ///
-#[derive(Clone)]
+/// Namespaces hold a usage of the name of each symbol that they define, which is released when the namespace is dropped:
+/// use `detached_copy()` to create a copy of a namespace with its own usages.
+///
 pub struct GluonScriptNamespace {
     /// The definitions for the symbols in this namespace
     symbols: HashMap<FloScriptSymbol, SymbolDefinition>,
 
+    /// The symbols in this namespace, indexed by the name that scripts use to refer to them
+    script_names: HashMap<String, FloScriptSymbol>,
+
+    /// The references made by the script defining each symbol (kept so each script is only parsed when it changes)
+    references: HashMap<FloScriptSymbol, (Arc<String>, Arc<Vec<ScriptReference>>)>,
+
     /// The current thread for generating streaming scripts (or none if it hasn't been created yet)
     streaming: Option<RootedThread>,
 
@@ -56,7 +114,34 @@ pub struct GluonScriptNamespace {
     computing: Option<Arc<RootedThread>>,
 
     /// Whether or not we'll run I/O operations in this namespace or not
-    run_io: bool
+    run_io: bool,
+
+    /// How symbols with names that can't be used in a script are treated
+    name_policy: SymbolNamePolicy,
+
+    /// The limits on the resources used by each evaluation of a script in this namespace
+    execution_limits: ExecutionLimits,
+
+    /// The executor that evaluates the scripts in this namespace (or `None` to evaluate them on the thread reading their output)
+    executor: Option<Arc<dyn ScriptExecutor>>,
+
+    /// Creates the VMs used by this namespace (shared with its child namespaces)
+    vms: Arc<ScriptVms>,
+
+    /// The metadata attached to the symbols in this namespace
+    metadata: HashMap<FloScriptSymbol, SymbolMetadata>,
+
+    /// The paths of the symbols that each script read the last time it was evaluated (updated by the script as it runs)
+    dynamic_dependencies: HashMap<FloScriptSymbol, Arc<Mutex<Vec<Vec<FloScriptSymbol>>>>>,
+
+    /// The output types declared for the computing scripts in this namespace (scripts without a declared type are checked when they're read)
+    declared_types: HashMap<FloScriptSymbol, ScriptTypeDescription>,
+
+    /// The status of each symbol in this namespace
+    statuses: HashMap<FloScriptSymbol, SymbolStatusCell>,
+
+    /// The streams that are receiving updates from this namespace
+    update_senders: Vec<mpsc::UnboundedSender<NotebookUpdate>>
 }
 
 impl GluonScriptNamespace {
@@ -65,10 +150,21 @@ impl GluonScriptNamespace {
     ///
     pub fn new() -> GluonScriptNamespace {
         GluonScriptNamespace {
-            symbols:    HashMap::new(),
-            streaming:  None,
-            computing:  None,
-            run_io:     false
+            symbols:                HashMap::new(),
+            script_names:           HashMap::new(),
+            references:             HashMap::new(),
+            streaming:              None,
+            computing:              None,
+            run_io:                 false,
+            name_policy:            SymbolNamePolicy::default(),
+            execution_limits:       ExecutionLimits::unlimited(),
+            executor:               None,
+            vms:                    Arc::new(ScriptVms::new(VmSharing::default())),
+            metadata:               HashMap::new(),
+            dynamic_dependencies:   HashMap::new(),
+            declared_types:         HashMap::new(),
+            statuses:               HashMap::new(),
+            update_senders:         vec![]
         }
     }
 
@@ -76,9 +172,207 @@ impl GluonScriptNamespace {
     /// Clears this namespace
     ///
     pub fn clear(&mut self) {
-        self.symbols.clear();
+        let symbols = self.symbols.drain().map(|(symbol, _)| symbol).collect::<Vec<_>>();
+
+        self.script_names.clear();
+        self.references.clear();
+        self.metadata.clear();
+        self.dynamic_dependencies.clear();
+        self.declared_types.clear();
+        self.statuses.drain().for_each(|(_, status)| status.close());
         self.streaming  = None;
         self.computing  = None;
+
+        for symbol in symbols {
+            self.notify(NotebookUpdate::UndefinedSymbol(symbol));
+            release_symbol(symbol);
+        }
+    }
+
+    ///
+    /// Sends an update to everything that's watching this namespace
+    ///
+    fn notify(&mut self, update: NotebookUpdate) {
+        self.update_senders.retain(|sender| sender.unbounded_send(update.clone()).is_ok());
+    }
+
+    ///
+    /// Sets the status for a new definition of a symbol, returning the status cell and the generation that streams producing
+    /// values for the definition should use
+    ///
+    fn redefine_status(&mut self, symbol: FloScriptSymbol, status: SymbolStatus) -> (SymbolStatusCell, usize) {
+        let cell        = self.statuses.entry(symbol).or_insert_with(|| SymbolStatusCell::new(SymbolStatus::NotStarted)).clone();
+        let generation  = cell.redefine(status);
+
+        (cell, generation)
+    }
+
+    ///
+    /// Indicates that a symbol has been given a new definition, cancelling any evaluation of its previous definition
+    ///
+    fn replace_definition(&mut self, symbol: FloScriptSymbol) {
+        self.statuses.entry(symbol)
+            .or_insert_with(|| SymbolStatusCell::new(SymbolStatus::NotStarted))
+            .replace_definition();
+
+        // Scripts that read the symbol need to read its new definition
+        self.restart_dependents(symbol);
+    }
+
+    ///
+    /// Restarts the scripts in this namespace that read a symbol (or the contents of a namespace symbol)
+    ///
+    fn restart_dependents(&mut self, symbol: FloScriptSymbol) {
+        let name = match self.script_name(symbol) {
+            Some(name)  => name,
+            None        => return
+        };
+
+        let mut dependents = self.references.iter()
+            .filter(|(dependent, (_, references))| **dependent != symbol && references.iter().any(|reference| reference.names.first() == Some(&name)))
+            .map(|(dependent, _)| *dependent)
+            .collect::<Vec<_>>();
+        dependents.sort_by_key(|dependent| dependent.id());
+
+        for dependent in dependents {
+            self.restart_script(dependent);
+        }
+    }
+
+    ///
+    /// Sets the definition of a symbol, returning the definition it replaces
+    ///
+    fn insert_definition(&mut self, symbol: FloScriptSymbol, definition: SymbolDefinition) -> Option<SymbolDefinition> {
+        if let Some(name) = self.script_name(symbol) {
+            self.script_names.insert(name, symbol);
+        }
+
+        match definition.script() {
+            Some(script)    => {
+                // Scripts are only parsed again if they've changed
+                let unchanged = self.references.get(&symbol).map(|(old_script, _)| Arc::ptr_eq(old_script, script)).unwrap_or(false);
+
+                if !unchanged {
+                    self.references.insert(symbol, (Arc::clone(script), Arc::new(script_references(script))));
+                }
+            }
+
+            None            => { self.references.remove(&symbol); }
+        }
+
+        let old_definition = self.symbols.insert(symbol, definition);
+
+        // Symbols keep their names for as long as they're defined
+        if old_definition.is_none() {
+            retain_symbol(symbol);
+        }
+
+        old_definition
+    }
+
+    ///
+    /// Removes the definition of a symbol, returning it if it was defined
+    ///
+    fn remove_definition(&mut self, symbol: FloScriptSymbol) -> Option<SymbolDefinition> {
+        if let Some(name) = self.script_name(symbol) {
+            if self.script_names.get(&name) == Some(&symbol) {
+                self.script_names.remove(&name);
+            }
+        }
+
+        self.references.remove(&symbol);
+
+        let old_definition = self.symbols.remove(&symbol);
+
+        if old_definition.is_some() {
+            release_symbol(symbol);
+        }
+
+        old_definition
+    }
+
+    ///
+    /// Retrieves a token representing the current definition of a symbol (which can be used to tell when it's replaced)
+    ///
+    pub fn definition_token(&mut self, symbol: FloScriptSymbol) -> DefinitionToken {
+        self.statuses.entry(symbol)
+            .or_insert_with(|| SymbolStatusCell::new(SymbolStatus::NotStarted))
+            .definition_token()
+    }
+
+    ///
+    /// Retrieves the current status of a symbol
+    ///
+    pub fn status(&self, symbol: FloScriptSymbol) -> Option<SymbolStatus> {
+        self.statuses.get(&symbol).map(|status| status.status())
+    }
+
+    ///
+    /// Creates a state stream that returns the status of a symbol whenever it changes (finishing when the symbol is removed)
+    ///
+    pub fn watch_status(&self, symbol: FloScriptSymbol) -> FloScriptResult<SymbolStatusStream> {
+        self.statuses.get(&symbol)
+            .map(|status| status.watch())
+            .ok_or(FloScriptError::UndefinedSymbol(symbol))
+    }
+
+    ///
+    /// Returns the updates that describe how a symbol is currently defined (including its metadata)
+    ///
+    fn definition_updates(&self, symbol: FloScriptSymbol) -> Vec<NotebookUpdate> {
+        use self::SymbolDefinition::*;
+
+        let definition = match self.symbols.get(&symbol) {
+            Some(Input(input_source))               => Some(NotebookUpdate::DefinedInputSymbol(symbol, input_source.input_type().clone())),
+            Some(ActiveScript(_, input_source))     => Some(NotebookUpdate::DefinedOutputSymbol(symbol, input_source.input_type().clone())),
+            Some(Constant(value, _))                => Some(NotebookUpdate::DefinedOutputSymbol(symbol, value.value_type())),
+            Some(ScriptError(_, description))       => Some(NotebookUpdate::OutputSymbolError(symbol, FloScriptError::ScriptError(description.clone()))),
+            Some(Namespace(_))                      => Some(NotebookUpdate::DefinedNamespaceSymbol(symbol)),
+
+            // The output type of a script isn't known until it has been compiled, unless it was declared
+            Some(Computing(_))                      => self.declared_types.get(&symbol).map(|output_type| NotebookUpdate::DefinedOutputSymbol(symbol, output_type.clone())),
+
+            Some(Streaming(_))                      |
+            None                                    => None
+        };
+
+        let metadata = if definition.is_some() {
+            self.metadata.get(&symbol).map(|metadata| NotebookUpdate::SetMetadata(symbol, metadata.clone()))
+        } else {
+            None
+        };
+
+        definition.into_iter().chain(metadata).collect()
+    }
+
+    ///
+    /// Notifies anything watching this namespace that a symbol has been defined
+    ///
+    fn notify_defined(&mut self, symbol: FloScriptSymbol) {
+        for update in self.definition_updates(symbol) {
+            self.notify(update);
+        }
+    }
+
+    ///
+    /// Creates a stream of updates for this namespace, starting with the symbols that are currently defined
+    ///
+    pub fn updates(&mut self) -> impl Stream<Item=NotebookUpdate, Error=()>+Send {
+        let (sender, receiver)  = mpsc::unbounded();
+
+        // Describe the current state of the namespace (in the order the symbols were created, so the updates are always the same)
+        let mut symbols         = self.symbols.keys().cloned().collect::<Vec<_>>();
+        symbols.sort_by_key(|symbol| symbol.id());
+
+        for symbol in symbols {
+            for update in self.definition_updates(symbol) {
+                sender.unbounded_send(update).ok();
+            }
+        }
+
+        self.update_senders.push(sender);
+
+        receiver
     }
 
     ///
@@ -87,7 +381,33 @@ impl GluonScriptNamespace {
     pub fn define_input_symbol(&mut self, symbol: FloScriptSymbol, input_stream_type: ScriptTypeDescription) {
         let source = InputStreamSource::new(input_stream_type);
 
-        self.symbols.insert(symbol, SymbolDefinition::Input(source));
+        self.insert_definition(symbol, SymbolDefinition::Input(source));
+        self.dynamic_dependencies.remove(&symbol);
+        self.declared_types.remove(&symbol);
+        self.replace_definition(symbol);
+        self.redefine_status(symbol, SymbolStatus::WaitingForInput);
+        self.notify_defined(symbol);
+    }
+
+    ///
+    /// Sets the metadata for a symbol
+    ///
+    pub fn set_metadata(&mut self, symbol: FloScriptSymbol, metadata: SymbolMetadata) -> FloScriptResult<()> {
+        if !self.symbols.contains_key(&symbol) {
+            return Err(FloScriptError::UndefinedSymbol(symbol));
+        }
+
+        self.metadata.insert(symbol, metadata.clone());
+        self.notify(NotebookUpdate::SetMetadata(symbol, metadata));
+
+        Ok(())
+    }
+
+    ///
+    /// Retrieves the metadata for a symbol
+    ///
+    pub fn metadata(&self, symbol: FloScriptSymbol) -> Option<SymbolMetadata> {
+        self.metadata.get(&symbol).cloned()
     }
 
     ///
@@ -97,6 +417,16 @@ impl GluonScriptNamespace {
         unimplemented!()
     }
 
+    ///
+    /// Checks that a computing script can be read as the specified type (scripts with a declared type can only be read as that type)
+    ///
+    fn check_declared_read<Symbol: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<()> {
+        match self.declared_types.get(&symbol) {
+            Some(output_type) if !output_type.is::<Symbol>()    => Err(FloScriptError::incorrect_type(output_type, &Symbol::description())),
+            _                                                   => Ok(())
+        }
+    }
+
     ///
     /// Creates a stream to read from a particular symbol
     ///
@@ -107,10 +437,12 @@ impl GluonScriptNamespace {
 
         match self.symbols.get_mut(&symbol) {
             None                                => Err(FloScriptError::UndefinedSymbol(symbol)),
-            Some(ScriptError(description))      => Err(FloScriptError::ScriptError(description.clone())),
+            Some(ScriptError(_, description))   => Err(FloScriptError::ScriptError(description.clone())),
             Some(Input(input_source))           |
-            Some(ActiveScript(input_source))    => Ok(Box::new(input_source.read_as_stream()?)),
-            Some(Computing(expr))               => { let expr = Arc::clone(expr); Ok(Box::new(self.create_computing_stream(symbol, expr)?)) },
+            Some(ActiveScript(_, input_source)) => Ok(Box::new(input_source.read_as_stream()?)),
+            Some(Constant(_, input_source))     => Ok(Box::new(input_source.read_as_state_stream()?)),
+            Some(Computing(expr))               => { let expr = Arc::clone(expr); self.check_declared_read::<Symbol>(symbol)?; Ok(Box::new(self.create_computing_stream(symbol, expr)?)) },
+            Some(Streaming(_))                  => Err(FloScriptError::Unavailable("Streaming scripts are not supported yet".to_string())),
             Some(Namespace(_))                  => Err(FloScriptError::CannotReadFromANamespace)
         }
     }
@@ -125,29 +457,137 @@ impl GluonScriptNamespace {
 
         match self.symbols.get_mut(&symbol) {
             None                                => Err(FloScriptError::UndefinedSymbol(symbol)),
-            Some(ScriptError(description))      => Err(FloScriptError::ScriptError(description.clone())),
+            Some(ScriptError(_, description))   => Err(FloScriptError::ScriptError(description.clone())),
             Some(Input(input_source))           |
-            Some(ActiveScript(input_source))    => Ok(Box::new(input_source.read_as_state_stream()?)),
-            Some(Computing(expr))               => { let expr = Arc::clone(expr); Ok(Box::new(self.create_computing_stream(symbol, expr)?)) },
+            Some(ActiveScript(_, input_source)) => Ok(Box::new(input_source.read_as_state_stream()?)),
+            Some(Constant(_, input_source))     => Ok(Box::new(input_source.read_as_state_stream()?)),
+            Some(Computing(expr))               => { let expr = Arc::clone(expr); self.check_declared_read::<Symbol>(symbol)?; Ok(Box::new(self.create_computing_stream(symbol, expr)?)) },
+            Some(Streaming(_))                  => Err(FloScriptError::Unavailable("Streaming scripts are not supported yet".to_string())),
             Some(Namespace(_))                  => Err(FloScriptError::CannotReadFromANamespace)
         }
     }
 
+    ///
+    /// Creates a stream to read from a particular symbol, converting its values to the requested type if they have a different one
+    ///
+    pub fn read_coerced_stream<Symbol>(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<CoercedStream<Symbol>>
+    where   Symbol:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <Symbol as VmType>::Type:   Sized {
+        use self::SymbolDefinition::*;
+
+        match self.symbols.get_mut(&symbol) {
+            Some(Input(input_source))           |
+            Some(ActiveScript(_, input_source)) => input_source.read_as_coerced_stream(),
+            Some(Constant(_, input_source))     => input_source.read_as_coerced_state_stream(),
+            Some(Computing(_))                  => self.read_coerced_computing_stream(symbol),
+            _                                   => Ok(Box::new(self.read_stream(symbol)?.map(Ok)))
+        }
+    }
+
+    ///
+    /// Creates a stream to read from a particular symbol using the state stream semantics, converting its values to the requested type
+    ///
+    pub fn read_coerced_state_stream<Symbol>(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<CoercedStream<Symbol>>
+    where   Symbol:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <Symbol as VmType>::Type:   Sized {
+        use self::SymbolDefinition::*;
+
+        match self.symbols.get_mut(&symbol) {
+            Some(Input(input_source))           |
+            Some(ActiveScript(_, input_source)) |
+            Some(Constant(_, input_source))     => input_source.read_as_coerced_state_stream(),
+            Some(Computing(_))                  => self.read_coerced_computing_stream(symbol),
+            _                                   => Ok(Box::new(self.read_state_stream(symbol)?.map(Ok)))
+        }
+    }
+
+    ///
+    /// Starts a computing script, converting its values to the requested type if the script produces a different one
+    ///
+    /// Scripts without a declared type can be read directly as any type with the same Gluon type (so a script producing an
+    /// `Int` can be read as an `i32`). Other types are converted from the script's own type, following the rules described by
+    /// `ScriptTypeDescription::can_coerce_to()`.
+    ///
+    fn read_coerced_computing_stream<Symbol>(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<CoercedStream<Symbol>>
+    where   Symbol:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <Symbol as VmType>::Type:   Sized {
+        // Scripts whose type can't be described can still be read as the type they produce
+        let source_type         = match self.symbol_type(&[symbol]) {
+            Ok(source_type) => source_type,
+            Err(_)          => return Ok(Box::new(self.read_stream(symbol)?.map(Ok)))
+        };
+        let target_type         = Symbol::description();
+        let computing_thread    = self.get_computing_thread()?;
+
+        let read_directly       = source_type == target_type
+            || (!self.declared_types.contains_key(&symbol) && same_gluon_type(&source_type.vm_type(&computing_thread)?, &target_type.vm_type(&computing_thread)?));
+
+        if read_directly {
+            Ok(Box::new(self.read_stream(symbol)?.map(Ok)))
+        } else if !source_type.can_coerce_to(&target_type) {
+            Err(FloScriptError::incorrect_type(&source_type, &target_type))
+        } else {
+            let values = source_type.read_any_state_stream(self, symbol)?;
+
+            Ok(Box::new(values
+                .filter_map(move |value| source_type.to_script_value(&*value))
+                .map(move |value| value.coerce_into::<Symbol>())
+                .map_err(|_| ())))
+        }
+    }
+
     ///
     /// Creates a new computing stream from a script, storing the result as a new input stream associated with the specified symbol
     ///
     pub fn create_computing_stream<Item: 'static+ScriptType>(&mut self, symbol: FloScriptSymbol, expression: Arc<String>) -> FloScriptResult<impl Stream<Item=Item, Error=()>>
     where Item:             for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
     <Item as VmType>::Type: Sized {
-        let computing_thread    = self.get_computing_thread();
-        let mut compiler        = Compiler::default();
-        let expression          = (&*expression).compile(&mut compiler, &computing_thread, &symbol.name().unwrap_or("".to_string()), &*expression, None);
+        // Scripts that depend on themselves can't be evaluated
+        if let Err(cycle_error) = self.check_dependency_cycle(symbol) {
+            self.redefine_status(symbol, SymbolStatus::Failed(cycle_error.clone()));
+            return Err(cycle_error);
+        }
+
+        // Find the symbols that the script reads (which must be available before it's compiled)
+        let dependencies = match self.script_dependencies(symbol, &expression) {
+            Ok(dependencies)    => Arc::new(dependencies),
+            Err(error)          => {
+                self.redefine_status(symbol, SymbolStatus::Failed(error.clone()));
+                return Err(error);
+            }
+        };
+
+        let computing_thread    = self.get_computing_thread()?;
+        let mut compiled        = self.compile_script(&mut Compiler::default(), &computing_thread, symbol, &expression, &dependencies, None);
+
+        // Values such as `None` or tuples only have exactly the type they're being read as once they're compiled against it
+        if let (Ok((_, output_type)), Ok(item_type)) = (&compiled, make_gluon_type::<Item>(&computing_thread)) {
+            if !same_gluon_type(output_type, &item_type) {
+                if let Ok(typed) = self.compile_script(&mut Compiler::default(), &computing_thread, symbol, &expression, &dependencies, Some(&item_type)) {
+                    compiled = Ok(typed);
+                }
+            }
+        }
 
         // Report on the result
-        match expression {
-            Ok(compiled)        => {
-                // Create as an input stream
-                let stream = ComputingScriptStream::<Item>::new(computing_thread, compiled, Compiler::default())?;
+        match compiled {
+            Ok((compiled, _))   => {
+                // Create as an input stream (which updates the status of the symbol as it's evaluated)
+                let stream                  = if dependencies.is_empty() {
+                    ComputingScriptStream::<Item>::new(computing_thread, compiled, Compiler::default(), self.execution_limits, self.executor.as_ref())?
+                } else {
+                    let inputs = self.read_symbol_values(&dependencies)?;
+                    ComputingScriptStream::<Item>::with_symbols(computing_thread, compiled, Arc::clone(&dependencies), inputs, Compiler::default(), self.execution_limits, self.executor.as_ref())?
+                };
+
+                // The stream records the symbols that the script reads each time it's evaluated
+                self.dynamic_dependencies.insert(symbol, stream.read_symbols());
+
+                let (status, generation)    = self.redefine_status(symbol, SymbolStatus::Computing);
+
+                // The evaluation is stopped if the symbol is redefined before it finishes
+                status.definition_token().on_replaced(stream.canceller());
+                let stream                  = StatusTrackingStream::new(stream, status, generation);
 
                 // This will become the input stream for the specified symbol
                 let mut input_stream_source = InputStreamSource::new(Item::description());
@@ -157,127 +597,1229 @@ impl GluonScriptNamespace {
                 let result_stream = input_stream_source.read_as_state_stream()?;
 
                 // Update the symbol to be an active stream
-                self.symbols.insert(symbol, SymbolDefinition::ActiveScript(input_stream_source));
+                self.insert_definition(symbol, SymbolDefinition::ActiveScript(expression, input_stream_source));
+                self.notify_defined(symbol);
 
                 Ok(result_stream)
             },
-            Err(fail)           => {
-                // Generate a script error
-                let error_string = fail.emit_string(&compiler.code_map())
-                    .unwrap_or("<Error while compiling (could not convert to string)>".to_string());
-
+            Err(FloScriptError::ScriptError(error_string)) => {
                 // Don't try to run this script again
-                self.symbols.insert(symbol, SymbolDefinition::ScriptError(error_string.clone()));
+                self.insert_definition(symbol, SymbolDefinition::ScriptError(expression, error_string.clone()));
+                self.redefine_status(symbol, SymbolStatus::Failed(FloScriptError::ScriptError(error_string.clone())));
+                self.notify_defined(symbol);
 
                 // Return as the result
                 Err(FloScriptError::ScriptError(error_string))
+            },
+            Err(error)          => {
+                self.redefine_status(symbol, SymbolStatus::Failed(error.clone()));
+                Err(error)
             }
         }
     }
 
     ///
-    /// Attaches an input stream to a particular symbol
+    /// Compiles a script for a symbol in this namespace, which reads the specified symbols
     ///
-    pub fn attach_input<InputStream: 'static+Stream<Error=()>+Send>(&mut self, symbol: FloScriptSymbol, input: InputStream) -> FloScriptResult<()> 
-    where InputStream::Item: 'static+ScriptType {
-        use self::SymbolDefinition::*;
+    /// The result is the compiled script and the type of the values that it produces. If an output type is supplied, the script
+    /// is type-checked against it. Errors in the script itself are returned as `FloScriptError::ScriptError`.
+    ///
+    fn compile_script(&self, compiler: &mut Compiler, computing_thread: &RootedThread, symbol: FloScriptSymbol, script: &str, dependencies: &ScriptDependencies, output_type: Option<&ArcType>) -> FloScriptResult<(CompileValue<SpannedExpr<GluonSymbol>>, ArcType)> {
+        let file_name = symbol.name().unwrap_or("".to_string());
 
-        match self.symbols.get_mut(&symbol) {
-            None                        => Err(FloScriptError::UndefinedSymbol(symbol)),
-            Some(Input(input_source))   => input_source.attach(input),
-            _                           => Err(FloScriptError::NotAnInputSymbol)
+        let (mut compiled, script_type) = if dependencies.is_empty() {
+            let compiled = script.compile(compiler, computing_thread, &file_name, script, output_type).map_err(|fail| script_error(compiler, fail))?;
+            let typ      = compiled.typ.clone();
+            (compiled, typ)
+        } else {
+            dependencies.compile(compiler, computing_thread, &file_name, script, output_type)?
+        };
+
+        // Gluon reports the type that the script was inferred to have (which can be an alias declared by the script), but it has
+        // been checked against the output type so it produces values of that type
+        match output_type {
+            Some(output_type) => {
+                compiled.typ = match compiled.typ.as_function() {
+                    Some((argument, _)) if !dependencies.is_empty()    => Type::function(vec![argument.clone()], output_type.clone()),
+                    _                                                   => output_type.clone()
+                };
+
+                Ok((compiled, output_type.clone()))
+            },
+
+            None => Ok((compiled, script_type))
         }
     }
 
     ///
-    /// Removes the definition of a symbol from this namespace (if it exists)
+    /// Finds the symbols that a script for a symbol in this namespace reads, along with their types
     ///
-    pub fn undefine_symbol(&mut self, symbol: FloScriptSymbol) {
-        self.symbols.remove(&symbol);
+    fn script_dependencies(&mut self, symbol: FloScriptSymbol, script: &str) -> FloScriptResult<ScriptDependencies> {
+        // Use the references from the definition of the symbol if the script hasn't changed
+        let references = match self.references.get(&symbol) {
+            Some((defined_script, references)) if defined_script.as_str() == script => Arc::clone(references),
+            _                                                                       => Arc::new(script_references(script))
+        };
+
+        let mut dependencies = ScriptDependencies::new();
+
+        for reference in references.iter() {
+            if let Some(path) = self.resolve_reference(&reference.names) {
+                let names       = &reference.names[0..path.len()];
+                let symbol_type = self.symbol_type(&path).map_err(|error| match error {
+                    FloScriptError::ScriptError(_)  => FloScriptError::Unavailable(format!("Cannot read `{}` as its script has errors", names.join("."))),
+                    error                           => error
+                })?;
+
+                dependencies.add_symbol(names, path, symbol_type);
+            }
+        }
+
+        Ok(dependencies)
     }
 
     ///
-    /// Retrieves a sub-namespace within this namespace. The symbol must already be defined to be a namespace, or must be
-    /// undefined (in which case it will be assigned as a namespace)
+    /// Retrieves the type of the values produced by the symbol at a path (relative to this namespace), so that scripts can read it
     ///
-    pub fn get_or_create_namespace(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<Arc<Desync<GluonScriptNamespace>>> {
-        // Insert the namespace if it doesn't already exist
-        if self.symbols.get(&symbol).is_none() {
-            self.symbols.insert(symbol, SymbolDefinition::Namespace(Arc::new(Desync::new(GluonScriptNamespace::new()))));
-        }
+    /// Computing scripts without a declared type are type-checked to find the type they produce. Scripts producing `Bool`, `Int`,
+    /// `Float` or `String` values can be read this way: other scripts need a declared type before other scripts can read them.
+    ///
+    fn symbol_type(&mut self, path: &[FloScriptSymbol]) -> FloScriptResult<ScriptTypeDescription> {
+        use self::SymbolDefinition::*;
 
-        // Retrieve the namespace
-        self.symbols.get(&symbol)
-            .and_then(|symbol| if let SymbolDefinition::Namespace(symbol) = symbol {
-                Some(Arc::clone(symbol))
-            } else {
-                None
-            })
-            .ok_or(FloScriptError::NotANamespace)
+        match path {
+            []                          => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+            [symbol]                    => {
+                if let Some(declared_type) = self.declared_types.get(symbol) {
+                    return Ok(declared_type.clone());
+                }
+
+                match self.symbols.get(symbol) {
+                    None                                => Err(FloScriptError::UndefinedSymbol(*symbol)),
+                    Some(ScriptError(_, description))   => Err(FloScriptError::ScriptError(description.clone())),
+                    Some(Input(input_source))           |
+                    Some(ActiveScript(_, input_source)) |
+                    Some(Constant(_, input_source))     => Ok(input_source.input_type().clone()),
+                    Some(Computing(script))             => {
+                        let script = Arc::clone(script);
+                        self.check_dependency_cycle(*symbol)?;
+
+                        let script_type         = self.typecheck_script_type(*symbol, &script, None)?;
+                        let computing_thread    = self.get_computing_thread()?;
+                        default_type_description(&script_type, &computing_thread)
+                    },
+                    Some(Streaming(_))                  => Err(FloScriptError::Unavailable("Streaming scripts are not supported yet".to_string())),
+                    Some(Namespace(_))                  => Err(FloScriptError::CannotReadFromANamespace)
+                }
+            },
+            [namespace, path @ ..]      => {
+                let namespace = self.get_namespace(*namespace).ok_or(FloScriptError::NotANamespace)?;
+                namespace.sync(|namespace| namespace.symbol_type(path))
+            }
+        }
     }
 
     ///
-    /// Retrieves a sub-namespace, if it is defined
+    /// Reads the values of the symbols that a script depends on, in the order that the script expects them
     ///
-    pub fn get_namespace(&self, symbol: FloScriptSymbol) -> Option<Arc<Desync<GluonScriptNamespace>>> {
-        self.symbols.get(&symbol)
-            .and_then(|symbol| if let SymbolDefinition::Namespace(symbol) = symbol {
-                Some(Arc::clone(symbol))
-            } else {
-                None
+    fn read_symbol_values(&mut self, dependencies: &ScriptDependencies) -> FloScriptResult<Vec<AnyValueStream>> {
+        dependencies.symbols().iter()
+            .map(|(path, symbol_type)| match path.split_first() {
+                None                            => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+                Some((symbol, []))              => symbol_type.read_any_state_stream(self, *symbol),
+                Some((namespace, path))         => {
+                    // Symbols in child namespaces are followed when they're redefined (symbols in this namespace restart the script instead)
+                    let (symbol, namespaces)    = path.split_last().unwrap();
+                    let namespace               = self.get_namespace(*namespace).ok_or(FloScriptError::NotANamespace)?;
+                    let namespace               = if namespaces.is_empty() {
+                        namespace
+                    } else {
+                        namespace.sync(|namespace| namespace.get_nested_namespace(namespaces)).ok_or(FloScriptError::NotANamespace)?
+                    };
+
+                    symbol_type.follow_any_state_stream(&namespace, *symbol)
+                }
             })
+            .collect()
     }
 
     ///
-    /// Sets whether or not this namespace will run IO commands
+    /// Compiles and type-checks a script in this namespace without running it, returning the type of the value it produces
     ///
-    pub fn set_run_io(&mut self, run_io: bool) {
-        // This affects statements compiled after this is set
-        self.run_io = run_io;
+    pub fn typecheck_script(&mut self, symbol: FloScriptSymbol, script: &str) -> FloScriptResult<String> {
+        self.typecheck_script_type(symbol, script, None)
+            .map(|script_type| script_type.to_string())
     }
 
     ///
-    /// Loads a streaming script into this namespace
+    /// Type-checks a script as if it were defined for a symbol in this namespace, returning its Gluon type
+    ///
+    /// If an output type is supplied, the script is type-checked against it (so values such as `None` have that type rather than
+    /// a more general one).
     ///
-    pub fn set_streaming_script(&mut self, _symbol: FloScriptSymbol, script: String) {
+    fn typecheck_script_type(&mut self, symbol: FloScriptSymbol, script: &str, output_type: Option<&ArcType>) -> FloScriptResult<ArcType> {
+        let dependencies        = self.script_dependencies(symbol, script)?;
+        let computing_thread    = self.get_computing_thread()?;
+        let mut compiler        = Compiler::default();
 
+        if dependencies.is_empty() {
+            compiler.typecheck_str(&computing_thread, &symbol.name().unwrap_or("".to_string()), script, output_type)
+                .map(|(_expr, script_type)| script_type)
+                .map_err(|fail| script_error(&compiler, fail))
+        } else {
+            // Scripts that read symbols are compiled as functions of their values
+            dependencies.compile(&mut compiler, &computing_thread, &symbol.name().unwrap_or("".to_string()), script, output_type)
+                .map(|(_compiled, output_type)| output_type)
+        }
     }
 
     ///
-    /// Creates a new thread for this namespace
+    /// Creates a copy of this namespace that can be edited without affecting the original (including any child namespaces)
+    ///
+    /// Inputs in the copy don't have any streams attached to them, and scripts that were running in the original aren't
+    /// running in the copy: the copy has its own input sources, so editing it won't send values to anything reading the
+    /// original.
     ///
-    fn create_thread(&self) -> RootedThread {
-        // Create the thread as a new VM
-        let thread          = new_vm();
+    pub fn detached_copy(&self) -> GluonScriptNamespace {
+        use self::SymbolDefinition::*;
+
+        let symbols = self.symbols.iter()
+            .map(|(symbol, definition)| {
+                let definition = match definition {
+                    Input(input_source)                 => Input(InputStreamSource::new(input_source.input_type().clone())),
+                    ActiveScript(script, input_source)  => ActiveScript(Arc::clone(script), InputStreamSource::new(input_source.input_type().clone())),
+                    Constant(value, _)                  => {
+                        let mut input_source = InputStreamSource::new(value.value_type());
+                        attach_constant(&mut input_source, value).ok();
+                        Constant(value.clone(), input_source)
+                    },
+                    Namespace(namespace)                => Namespace(Arc::new(Desync::new(namespace.sync(|namespace| namespace.detached_copy())))),
+
+                    ScriptError(script, error)          => ScriptError(Arc::clone(script), error.clone()),
+                    Computing(script)                   => Computing(Arc::clone(script)),
+                    Streaming(script)                   => Streaming(Arc::clone(script))
+                };
+
+                // The copy keeps the names of the symbols it defines independently of the original
+                retain_symbol(*symbol);
 
-        // Import the standard modules
-        derived_state::load_flo_computed(&thread).expect("Load flo.computed module");
+                (*symbol, definition)
+            })
+            .collect();
 
-        // To make user data types available to Rust, we need to invoke the side-effects of the import! macro inside gluon
-        Compiler::default().run_expr::<()>(&thread, "import_flo_computed", "import! flo.computed\n()").unwrap();
+        GluonScriptNamespace {
+            symbols,
+            script_names:           self.script_names.clone(),
+            references:             self.references.clone(),
+            streaming:              self.streaming.clone(),
+            computing:              self.computing.clone(),
+            run_io:                 self.run_io,
+            name_policy:            self.name_policy,
+            execution_limits:       self.execution_limits,
+            executor:               self.executor.clone(),
+            vms:                    Arc::clone(&self.vms),
+            metadata:               self.metadata.clone(),
+            dynamic_dependencies:   self.dynamic_dependencies.iter().map(|(symbol, read_symbols)| (*symbol, Arc::new(Mutex::new(read_symbols.lock().unwrap().clone())))).collect(),
+            declared_types:         self.declared_types.clone(),
+            statuses:               self.statuses.iter().map(|(symbol, status)| (*symbol, status.detached_copy())).collect(),
 
-        thread
+            // Changes to the copy aren't reported to anything watching the original
+            update_senders:         vec![]
+        }
     }
 
     ///
-    /// Retrieves the computing thread for this namespace, if available
+    /// Attaches an input stream to a particular symbol
     ///
-    fn get_computing_thread(&mut self) -> Arc<RootedThread> {
-        let thread          = self.computing.clone();
+    pub fn attach_input<InputStream: 'static+Stream<Error=()>+Send>(&mut self, symbol: FloScriptSymbol, input: InputStream) -> FloScriptResult<()> 
+    where InputStream::Item: 'static+ScriptType {
+        use self::SymbolDefinition::*;
 
-        if let Some(thread) = thread {
-            thread
+        let input_type = match self.symbols.get(&symbol) {
+            None                        => return Err(FloScriptError::UndefinedSymbol(symbol)),
+            Some(Input(input_source))   => input_source.input_type().clone(),
+            _                           => return Err(FloScriptError::NotAnInputSymbol)
+        };
+
+        if !input_type.is::<InputStream::Item>() {
+            return Err(FloScriptError::incorrect_type(&input_type, &<InputStream::Item>::description()));
+        }
+
+        // The input is up to date until the stream finishes
+        let (status, generation) = self.redefine_status(symbol, SymbolStatus::UpToDate);
+
+        if let Some(Input(input_source)) = self.symbols.get_mut(&symbol) {
+            input_source.attach(StatusTrackingStream::new(input, status, generation))
         } else {
-            let thread      = Arc::new(self.create_thread());
-            self.computing  = Some(Arc::clone(&thread));
-            thread
+            Err(FloScriptError::NotAnInputSymbol)
         }
     }
 
     ///
-    /// Loads a computing script into this namespace
+    /// Sets the values of several input symbols as a single tick
+    ///
+    /// The inputs can be in this namespace or in any namespace within it. Every value is checked before any are applied, so either
+    /// all of the inputs change or none of them do. Scripts that have already been evaluated and depend on the inputs are restarted
+    /// once each, in dependency order, before the values are applied, so they're evaluated again against the new values when they're
+    /// next read.
     ///
-    pub fn set_computing_script(&mut self, symbol: FloScriptSymbol, script: String) {
-        self.symbols.insert(symbol, SymbolDefinition::Computing(Arc::new(script)));
+    pub fn send_input_tick(&mut self, values: Vec<InputValue>) -> FloScriptResult<()> {
+        // Check that every value can be applied to its input
+        for value in values.iter() {
+            let path        = value.path();
+            let value_type  = value.value_type();
+
+            if path.namespaces().is_empty() {
+                self.check_input_value(path.symbol(), value_type)?;
+            } else {
+                self.get_nested_namespace(path.namespaces())
+                    .ok_or(FloScriptError::UndefinedSymbol(path.symbol()))?
+                    .sync(|namespace| namespace.check_input_value(path.symbol(), value_type))?;
+            }
+        }
+
+        // Restart the scripts that depend on the inputs, dependencies first. This happens before the values are applied, so
+        // the old evaluations are cancelled before they can read a mix of old and new values
+        let mut graph   = DependencyGraph::new();
+        self.add_to_dependency_graph(&[], &mut graph);
+
+        let changed     = values.iter().map(|value| value.path().clone()).collect::<Vec<_>>();
+
+        for path in graph.evaluation_order(&changed) {
+            if path.namespaces().is_empty() {
+                self.restart_script(path.symbol());
+            } else if let Some(namespace) = self.get_nested_namespace(path.namespaces()) {
+                namespace.sync(|namespace| namespace.restart_script(path.symbol()));
+            }
+        }
+
+        // Apply all of the values before anything is evaluated again (readers of the restarted scripts wait for this namespace)
+        for value in values {
+            let path = value.path().clone();
+
+            if path.namespaces().is_empty() {
+                self.apply_input_value(path.symbol(), value)?;
+            } else if let Some(namespace) = self.get_nested_namespace(path.namespaces()) {
+                namespace.sync(move |namespace| namespace.apply_input_value(path.symbol(), value))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that a value of the specified type from an input tick can be applied to a symbol in this namespace
+    ///
+    fn check_input_value(&self, symbol: FloScriptSymbol, value_type: &ScriptTypeDescription) -> FloScriptResult<()> {
+        use self::SymbolDefinition::*;
+
+        match self.symbols.get(&symbol) {
+            None                                                                    => Err(FloScriptError::UndefinedSymbol(symbol)),
+            Some(Input(input_source)) if input_source.input_type() != value_type    => Err(FloScriptError::incorrect_type(input_source.input_type(), value_type)),
+            Some(Input(_))                                                          => Ok(()),
+            _                                                                       => Err(FloScriptError::NotAnInputSymbol)
+        }
+    }
+
+    ///
+    /// Makes a value from an input tick the current value of an input symbol in this namespace
+    ///
+    fn apply_input_value(&mut self, symbol: FloScriptSymbol, value: InputValue) -> FloScriptResult<()> {
+        use self::SymbolDefinition::*;
+
+        self.redefine_status(symbol, SymbolStatus::UpToDate);
+
+        if let Some(Input(input_source)) = self.symbols.get_mut(&symbol) {
+            value.attach_to(input_source)
+        } else {
+            Err(FloScriptError::NotAnInputSymbol)
+        }
+    }
+
+    ///
+    /// Discards the result of a computing script that has already been evaluated, so that its readers evaluate it again
+    ///
+    fn restart_script(&mut self, symbol: FloScriptSymbol) {
+        use self::SymbolDefinition::*;
+
+        // Scripts that failed to compile are also restarted, as they might have been reading a symbol that has now been defined
+        if let Some(ActiveScript(script, _)) | Some(ScriptError(script, _)) = self.symbols.get(&symbol) {
+            let script = Arc::clone(script);
+
+            // Readers follow the definition, so replacing it cancels the old evaluation and makes them read the script again
+            self.insert_definition(symbol, SymbolDefinition::Computing(script));
+            self.replace_definition(symbol);
+            self.redefine_status(symbol, SymbolStatus::NotStarted);
+        }
+    }
+
+    ///
+    /// Removes the definition of a symbol from this namespace
+    ///
+    pub fn undefine_symbol(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<()> {
+        self.remove_definition(symbol).ok_or(FloScriptError::UndefinedSymbol(symbol))?;
+        self.metadata.remove(&symbol);
+        self.dynamic_dependencies.remove(&symbol);
+        self.declared_types.remove(&symbol);
+        if let Some(status) = self.statuses.remove(&symbol) { status.close(); }
+        self.notify(NotebookUpdate::UndefinedSymbol(symbol));
+        self.restart_dependents(symbol);
+
+        Ok(())
+    }
+
+    ///
+    /// Removes the definition of the symbol at the specified path (relative to this namespace) and returns it, along with its metadata,
+    /// status and declared type
+    ///
+    fn take_definition(&mut self, path: &[FloScriptSymbol]) -> FloScriptResult<TakenDefinition> {
+        match path {
+            []                          => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+            [symbol]                    => {
+                let definition  = self.remove_definition(*symbol).ok_or(FloScriptError::UndefinedSymbol(*symbol))?;
+                let metadata    = self.metadata.remove(symbol);
+                let status      = self.statuses.remove(symbol);
+                let output_type = self.declared_types.remove(symbol);
+                self.dynamic_dependencies.remove(symbol);
+                self.notify(NotebookUpdate::UndefinedSymbol(*symbol));
+                self.restart_dependents(*symbol);
+
+                Ok((definition, metadata, status, output_type))
+            },
+            [namespace, path @ ..]      => {
+                let namespace = self.get_namespace(*namespace).ok_or(FloScriptError::NotANamespace)?;
+                namespace.sync(|namespace| namespace.take_definition(path))
+            }
+        }
+    }
+
+    ///
+    /// Stores a definition for the symbol at the specified path (relative to this namespace), creating any namespaces that
+    /// are needed along the way
+    ///
+    fn store_definition(&mut self, path: &[FloScriptSymbol], taken: TakenDefinition) -> FloScriptResult<()> {
+        match path {
+            []                          => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+            [symbol]                    => {
+                if self.symbols.contains_key(symbol) {
+                    return Err(FloScriptError::AlreadyDefined(*symbol));
+                }
+
+                let (definition, metadata, status, output_type) = taken;
+
+                self.insert_definition(*symbol, definition);
+                if let Some(metadata) = metadata { self.metadata.insert(*symbol, metadata); }
+                if let Some(old_status) = status.and_then(|status| self.statuses.insert(*symbol, status)) { old_status.close(); }
+                if let Some(output_type) = output_type { self.declared_types.insert(*symbol, output_type); }
+                self.notify_defined(*symbol);
+
+                Ok(())
+            },
+            [namespace, path @ ..]      => {
+                let namespace = self.get_or_create_namespace(*namespace)?;
+                namespace.sync(move |namespace| namespace.store_definition(path, taken))
+            }
+        }
+    }
+
+    ///
+    /// Checks that the symbol at the specified path (relative to this namespace) is defined
+    ///
+    fn check_defined(&self, path: &[FloScriptSymbol]) -> FloScriptResult<()> {
+        match path {
+            []                          => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+            [symbol]                    => if self.symbols.contains_key(symbol) { Ok(()) } else { Err(FloScriptError::UndefinedSymbol(*symbol)) },
+            [namespace, path @ ..]      => {
+                let namespace = self.get_namespace(*namespace).ok_or(FloScriptError::NotANamespace)?;
+                namespace.sync(|namespace| namespace.check_defined(path))
+            }
+        }
+    }
+
+    ///
+    /// Checks that `store_definition()` can store a definition at the specified path: the symbol must be free, and each of the
+    /// namespaces along the path must either be a namespace or be undefined (so that it can be created)
+    ///
+    fn check_can_store(&self, path: &[FloScriptSymbol]) -> FloScriptResult<()> {
+        match path {
+            []                          => Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string())),
+            [symbol]                    => if self.symbols.contains_key(symbol) { Err(FloScriptError::AlreadyDefined(*symbol)) } else { Ok(()) },
+            [namespace, path @ ..]      => {
+                match self.symbols.get(namespace) {
+                    None                                            => Ok(()),
+                    Some(SymbolDefinition::Namespace(namespace))    => namespace.sync(|namespace| namespace.check_can_store(path)),
+                    Some(_)                                         => Err(FloScriptError::NotANamespace)
+                }
+            }
+        }
+    }
+
+    ///
+    /// Changes the scripts in this namespace that refer to a symbol by one path so that they use another path instead
+    ///
+    fn rename_in_scripts(&mut self, old_path: &[String], new_path: &[String]) {
+        use self::SymbolDefinition::*;
+
+        let symbols = self.references.iter()
+            .filter(|(_, (_, references))| references.iter().any(|reference| reference.names.starts_with(old_path)))
+            .map(|(symbol, _)| *symbol)
+            .collect::<Vec<_>>();
+
+        for symbol in symbols {
+            let renamed = match self.symbols.get(&symbol) {
+                Some(ActiveScript(script, input))   => ActiveScript(Arc::new(rename_reference(script, old_path, new_path)), input.clone()),
+                Some(ScriptError(script, error))    => ScriptError(Arc::new(rename_reference(script, old_path, new_path)), error.clone()),
+                Some(Computing(script))             => Computing(Arc::new(rename_reference(script, old_path, new_path))),
+                Some(Streaming(script))             => Streaming(Arc::new(rename_reference(script, old_path, new_path))),
+
+                Some(Input(_))                      |
+                Some(Constant(_, _))                |
+                Some(Namespace(_))                  |
+                None                                => continue
+            };
+
+            self.insert_definition(symbol, renamed);
+        }
+    }
+
+    ///
+    /// Retrieves the names that scripts in this namespace use to refer to the symbol at a path
+    ///
+    fn script_path(&self, path: &[FloScriptSymbol]) -> Option<Vec<String>> {
+        match path {
+            []                          => Some(vec![]),
+            [symbol]                    => Some(vec![self.script_name(*symbol)?]),
+            [namespace, path @ ..]      => {
+                let mut names = self.get_namespace(*namespace)?.sync(|child| child.script_path(path))?;
+                names.insert(0, self.script_name(*namespace)?);
+                Some(names)
+            }
+        }
+    }
+
+    ///
+    /// Moves the symbol at the path `from` so that it's at the path `to` instead (both paths are relative to this namespace)
+    ///
+    /// The definition of the symbol is moved intact, so any attached input stream, existing readers or status streams are kept. The
+    /// scripts that refer to the symbol are updated to use its new path, as long as they can still reach it from their namespace.
+    /// The symbol can't be moved on top of a symbol that's already defined, and a move that fails leaves the namespace unchanged.
+    ///
+    pub fn move_symbol(&mut self, from: &[FloScriptSymbol], to: &[FloScriptSymbol]) -> FloScriptResult<()> {
+        let (from_namespace, to_namespace)  = match (from.split_last(), to.split_last()) {
+            (Some((_, from_namespace)), Some((_, to_namespace)))    => (from_namespace, to_namespace),
+            _                                                       => return Err(FloScriptError::Unavailable("Symbol paths cannot be empty".to_string()))
+        };
+
+        // The namespaces that contain both paths can refer to the symbol before and after it's moved
+        let num_shared  = from_namespace.iter().zip(to_namespace.iter()).take_while(|(from, to)| from == to).count();
+        let old_paths   = (0..=num_shared).map(|depth| self.nested_script_path(&from[0..depth], &from[depth..])).collect::<Vec<_>>();
+
+        // Check that the move can succeed before anything is changed (taking the definition notifies its readers and dependents)
+        self.check_defined(from)?;
+
+        if from == to {
+            return Ok(());
+        }
+
+        self.check_can_store(to)?;
+
+        let taken       = self.take_definition(from)?;
+        self.store_definition(to, taken)?;
+
+        // Update the scripts that refer to the symbol
+        for (depth, old_path) in old_paths.into_iter().enumerate() {
+            if let (Some(old_path), Some(new_path)) = (old_path, self.nested_script_path(&to[0..depth], &to[depth..])) {
+                self.rename_in_namespace(&from[0..depth], &old_path, &new_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Retrieves the names that scripts in the namespace at `namespace` use to refer to the symbol at `path` (relative to that namespace)
+    ///
+    fn nested_script_path(&self, namespace: &[FloScriptSymbol], path: &[FloScriptSymbol]) -> Option<Vec<String>> {
+        match namespace.split_first() {
+            None                        => self.script_path(path),
+            Some((child, namespace))    => self.get_namespace(*child)?.sync(|child| child.nested_script_path(namespace, path))
+        }
+    }
+
+    ///
+    /// Changes the references from one path to another in the scripts in the namespace at the specified path
+    ///
+    fn rename_in_namespace(&mut self, path: &[FloScriptSymbol], old_path: &[String], new_path: &[String]) {
+        match path.split_first() {
+            None                        => self.rename_in_scripts(old_path, new_path),
+            Some((namespace, path))     => {
+                if let Some(namespace) = self.get_namespace(*namespace) {
+                    namespace.sync(|namespace| namespace.rename_in_namespace(path, old_path, new_path));
+                }
+            }
+        }
+    }
+
+    ///
+    /// Retrieves a sub-namespace within this namespace. The symbol must already be defined to be a namespace, or must be
+    /// undefined (in which case it will be assigned as a namespace)
+    ///
+    pub fn get_or_create_namespace(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<Arc<Desync<GluonScriptNamespace>>> {
+        // Insert the namespace if it doesn't already exist
+        if self.symbols.get(&symbol).is_none() {
+            let namespace = self.new_child_namespace();
+
+            self.insert_definition(symbol, SymbolDefinition::Namespace(Arc::new(Desync::new(namespace))));
+            self.replace_definition(symbol);
+            self.redefine_status(symbol, SymbolStatus::UpToDate);
+            self.notify_defined(symbol);
+        }
+
+        // Retrieve the namespace
+        self.symbols.get(&symbol)
+            .and_then(|symbol| if let SymbolDefinition::Namespace(symbol) = symbol {
+                Some(Arc::clone(symbol))
+            } else {
+                None
+            })
+            .ok_or(FloScriptError::NotANamespace)
+    }
+
+    ///
+    /// Creates an empty namespace that inherits the settings of this one
+    ///
+    fn new_child_namespace(&self) -> GluonScriptNamespace {
+        let mut namespace           = GluonScriptNamespace::new();
+        namespace.name_policy       = self.name_policy;
+        namespace.execution_limits  = self.execution_limits;
+        namespace.executor          = self.executor.clone();
+        namespace.vms               = Arc::clone(&self.vms);
+
+        namespace
+    }
+
+    ///
+    /// Retrieves a sub-namespace, if it is defined
+    ///
+    pub fn get_namespace(&self, symbol: FloScriptSymbol) -> Option<Arc<Desync<GluonScriptNamespace>>> {
+        self.symbols.get(&symbol)
+            .and_then(|symbol| if let SymbolDefinition::Namespace(symbol) = symbol {
+                Some(Arc::clone(symbol))
+            } else {
+                None
+            })
+    }
+
+    ///
+    /// Retrieves a namespace nested within this one by following a list of namespace symbols (outermost first)
+    ///
+    /// This is used to resolve paths such as `layers.background.opacity`. The namespace must already exist.
+    ///
+    pub fn get_nested_namespace(&self, namespaces: &[FloScriptSymbol]) -> Option<Arc<Desync<GluonScriptNamespace>>> {
+        let (first, rest)   = namespaces.split_first()?;
+        let namespace       = self.get_namespace(*first)?;
+
+        if rest.is_empty() {
+            Some(namespace)
+        } else {
+            namespace.sync(|namespace| namespace.get_nested_namespace(rest))
+        }
+    }
+
+    ///
+    /// Sets whether or not this namespace will run IO commands
+    ///
+    pub fn set_run_io(&mut self, run_io: bool) {
+        // This affects statements compiled after this is set
+        self.run_io = run_io;
+    }
+
+    ///
+    /// Sets how symbols with names that can't be used in a script are treated, in this namespace and the namespaces within it
+    ///
+    /// The policy isn't changed in any namespace if it would give two existing symbols in one of them the same script name.
+    ///
+    pub fn set_name_policy(&mut self, name_policy: SymbolNamePolicy) -> FloScriptResult<()> {
+        self.check_name_policy(name_policy)?;
+        self.apply_name_policy(name_policy);
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that a name policy can be used by this namespace and the namespaces within it
+    ///
+    fn check_name_policy(&self, name_policy: SymbolNamePolicy) -> FloScriptResult<()> {
+        self.script_names_for_policy(name_policy)?;
+
+        self.child_namespaces().into_iter()
+            .try_for_each(|namespace| namespace.sync(|namespace| namespace.check_name_policy(name_policy)))
+    }
+
+    ///
+    /// Sets the name policy for this namespace and the namespaces within it (which must already have been checked with `check_name_policy()`)
+    ///
+    fn apply_name_policy(&mut self, name_policy: SymbolNamePolicy) {
+        // The names that scripts use for the existing symbols depend on the policy
+        if let Ok(script_names) = self.script_names_for_policy(name_policy) {
+            self.name_policy    = name_policy;
+            self.script_names   = script_names;
+        }
+
+        // Namespaces created after this is set will use the same policy
+        for namespace in self.child_namespaces() {
+            namespace.sync(|namespace| namespace.apply_name_policy(name_policy));
+        }
+    }
+
+    ///
+    /// Generates the names that scripts would use for the symbols in this namespace with a particular name policy
+    ///
+    /// The policy can't be used if it gives two existing symbols the same script name.
+    ///
+    fn script_names_for_policy(&self, name_policy: SymbolNamePolicy) -> FloScriptResult<HashMap<String, FloScriptSymbol>> {
+        let mut script_names = HashMap::new();
+
+        for symbol in self.symbols.keys() {
+            if let Some(name) = script_name(*symbol, name_policy) {
+                if let Some(other_symbol) = script_names.insert(name.clone(), *symbol) {
+                    if other_symbol.name() != symbol.name() {
+                        return Err(FloScriptError::DuplicateScriptName(name));
+                    }
+                }
+            }
+        }
+
+        Ok(script_names)
+    }
+
+    ///
+    /// Retrieves the namespaces defined within this namespace
+    ///
+    fn child_namespaces(&self) -> Vec<Arc<Desync<GluonScriptNamespace>>> {
+        self.symbols.values()
+            .filter_map(|definition| if let SymbolDefinition::Namespace(namespace) = definition { Some(Arc::clone(namespace)) } else { None })
+            .collect()
+    }
+
+    ///
+    /// Sets the limits on the resources that each evaluation of a script in this namespace can use
+    ///
+    pub fn set_execution_limits(&mut self, execution_limits: ExecutionLimits) {
+        // Scripts that are already running keep their limits, and namespaces created after this is set will use the same limits
+        self.execution_limits = execution_limits;
+    }
+
+    ///
+    /// Sets the executor that evaluates the scripts in this namespace
+    ///
+    pub fn set_executor(&mut self, executor: Option<Arc<dyn ScriptExecutor>>) {
+        // Namespaces created after this is set will use the same executor
+        self.executor = executor;
+    }
+
+    ///
+    /// Sets how this namespace and the namespaces created within it share VMs
+    ///
+    pub fn set_vm_sharing(&mut self, sharing: VmSharing) {
+        if self.vms.sharing() != sharing {
+            // The VM for this namespace is created again the next time it's needed (namespaces created after this is set will share the same VMs)
+            self.vms        = Arc::new(self.vms.with_sharing(sharing));
+            self.streaming  = None;
+            self.computing  = None;
+        }
+    }
+
+    ///
+    /// Declares a type by name in the VMs used by this namespace and the namespaces created within it
+    ///
+    pub fn declare_type<T: DeclaredScriptType>(&mut self) -> FloScriptResult<()>
+    where <T as VmType>::Type: Sized {
+        self.vms.declare_type::<T>()?;
+
+        // The threads that this namespace has already created won't have the new type
+        if let Some(computing) = &self.computing { declare_script_type::<T>(computing)?; }
+        if let Some(streaming) = &self.streaming { declare_script_type::<T>(streaming)?; }
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that a symbol can be defined in this namespace
+    ///
+    pub fn check_symbol_name(&self, symbol: FloScriptSymbol) -> FloScriptResult<()> {
+        check_symbol_name(symbol, self.name_policy)?;
+
+        // Symbols with different names can't share a script name (which can happen when names are mangled)
+        if let Some(script_name) = self.script_name(symbol) {
+            if let Some(other_symbol) = self.script_names.get(&script_name) {
+                if *other_symbol != symbol && other_symbol.name() != symbol.name() && self.symbols.contains_key(other_symbol) {
+                    return Err(FloScriptError::DuplicateScriptName(script_name));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Retrieves the name that scripts in this namespace use to refer to a symbol
+    ///
+    pub fn script_name(&self, symbol: FloScriptSymbol) -> Option<String> {
+        script_name(symbol, self.name_policy)
+    }
+
+    ///
+    /// Loads a streaming script into this namespace
+    ///
+    /// If the script depends on itself, it's still defined (so the cycle can be fixed by a later edit) but this returns an error
+    /// and the script won't be evaluated until the cycle is broken.
+    ///
+    pub fn set_streaming_script(&mut self, symbol: FloScriptSymbol, script: String) -> FloScriptResult<()> {
+        self.dynamic_dependencies.remove(&symbol);
+        self.declared_types.remove(&symbol);
+        self.replace_definition(symbol);
+
+        if self.insert_definition(symbol, SymbolDefinition::Streaming(Arc::new(script))).is_some() {
+            // The previous definition no longer applies (the script's output type is reported once it's compiled)
+            self.notify(NotebookUpdate::UndefinedSymbol(symbol));
+        }
+
+        self.check_new_script(symbol)
+    }
+
+    ///
+    /// Sets the status of a script that has just been defined, checking that it doesn't depend on itself
+    ///
+    fn check_new_script(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<()> {
+        match self.check_dependency_cycle(symbol) {
+            Ok(())              => { self.redefine_status(symbol, SymbolStatus::NotStarted); Ok(()) },
+            Err(cycle_error)    => { self.redefine_status(symbol, SymbolStatus::Failed(cycle_error.clone())); Err(cycle_error) }
+        }
+    }
+
+    ///
+    /// Retrieves the computing thread for this namespace, creating it if it doesn't exist yet
+    ///
+    fn get_computing_thread(&mut self) -> FloScriptResult<Arc<RootedThread>> {
+        let thread          = self.computing.clone();
+
+        if let Some(thread) = thread {
+            Ok(thread)
+        } else {
+            let thread      = Arc::new(self.vms.thread_for_namespace()?);
+            self.computing  = Some(Arc::clone(&thread));
+            Ok(thread)
+        }
+    }
+
+    ///
+    /// Sets the value of a constant symbol
+    ///
+    /// If the symbol is already a constant of the same type, the existing readers will receive the new value.
+    ///
+    pub fn set_constant(&mut self, symbol: FloScriptSymbol, value: ScriptValue) -> FloScriptResult<()> {
+        match self.symbols.get_mut(&symbol) {
+            Some(SymbolDefinition::Constant(existing_value, input_source)) if input_source.input_type() == &value.value_type() => {
+                // Update the existing constant
+                attach_constant(input_source, &value)?;
+                *existing_value = value;
+            }
+
+            _ => {
+                // Define a new constant
+                let mut input_source = InputStreamSource::new(value.value_type());
+                attach_constant(&mut input_source, &value)?;
+
+                self.insert_definition(symbol, SymbolDefinition::Constant(value, input_source));
+                self.dynamic_dependencies.remove(&symbol);
+                self.declared_types.remove(&symbol);
+                self.replace_definition(symbol);
+                self.notify_defined(symbol);
+            }
+        }
+
+        self.redefine_status(symbol, SymbolStatus::UpToDate);
+
+        Ok(())
+    }
+
+    ///
+    /// Loads a computing script into this namespace
+    ///
+    /// If the script depends on itself, it's still defined (so the cycle can be fixed by a later edit) but this returns an error
+    /// and the script won't be evaluated until the cycle is broken.
+    ///
+    pub fn set_computing_script(&mut self, symbol: FloScriptSymbol, script: String) -> FloScriptResult<()> {
+        self.dynamic_dependencies.remove(&symbol);
+        self.declared_types.remove(&symbol);
+        self.replace_definition(symbol);
+
+        if self.insert_definition(symbol, SymbolDefinition::Computing(Arc::new(script))).is_some() {
+            // The previous definition no longer applies (the script's output type is reported once it's compiled)
+            self.notify(NotebookUpdate::UndefinedSymbol(symbol));
+        }
+
+        self.check_new_script(symbol)
+    }
+
+    ///
+    /// Loads a computing script that produces values of a declared type into this namespace
+    ///
+    /// The script is compiled and checked against the declared type straight away (rather than when it's first read), and
+    /// the symbol can only be read as the declared type. The script is still defined if the check fails, but this returns
+    /// the error and the status of the symbol is set to failed.
+    ///
+    pub fn set_typed_computing_script(&mut self, symbol: FloScriptSymbol, output_type: ScriptTypeDescription, script: String) -> FloScriptResult<()> {
+        let defined = self.set_computing_script(symbol, script.clone());
+
+        // The output type of the symbol is known before the script is compiled
+        self.declared_types.insert(symbol, output_type.clone());
+        self.notify_defined(symbol);
+        defined?;
+
+        match self.check_declared_type(symbol, &script, &output_type) {
+            Ok(())                                  => Ok(()),
+            Err(FloScriptError::ScriptError(error)) => {
+                // Scripts that don't compile are treated the same way as when they're first read
+                self.insert_definition(symbol, SymbolDefinition::ScriptError(Arc::new(script), error.clone()));
+                self.redefine_status(symbol, SymbolStatus::Failed(FloScriptError::ScriptError(error.clone())));
+                Err(FloScriptError::ScriptError(error))
+            },
+            Err(type_error)                         => {
+                self.redefine_status(symbol, SymbolStatus::Failed(type_error.clone()));
+                Err(type_error)
+            }
+        }
+    }
+
+    ///
+    /// Checks that a script produces values of its declared type
+    ///
+    fn check_declared_type(&mut self, symbol: FloScriptSymbol, script: &str, output_type: &ScriptTypeDescription) -> FloScriptResult<()> {
+        let script_type         = self.typecheck_script_type(symbol, script, None)?;
+        let computing_thread    = self.get_computing_thread()?;
+
+        if output_type.computing_script_types(&computing_thread)?.iter().any(|output_type| same_gluon_type(output_type, &script_type)) {
+            Ok(())
+        } else if self.typecheck_script_type(symbol, script, Some(&output_type.vm_type(&computing_thread)?)).is_ok() {
+            // The script's type is more general than the declared type (for example, `None` is an option of any type)
+            Ok(())
+        } else {
+            // The declared type is the one that was expected: describe what the script produces as well as we can
+            match default_type_description(&script_type, &computing_thread) {
+                Ok(script_description)  => Err(FloScriptError::incorrect_type(output_type, &script_description)),
+                Err(_)                  => Err(FloScriptError::IncorrectType { expected: output_type.to_string(), requested: format!("script ({})", script_type) })
+            }
+        }
+    }
+
+    ///
+    /// Finds the symbol that a reference in a script (an identifier followed by any field names) refers to
+    ///
+    /// References to symbols in child namespaces are written as field accesses (for example, `layers.background.opacity`). The
+    /// result is the path to the symbol, or `None` if the reference isn't to a symbol in this namespace.
+    ///
+    fn resolve_reference(&self, names: &[String]) -> Option<Vec<FloScriptSymbol>> {
+        let (name, field_names) = names.split_first()?;
+        let symbol              = *self.script_names.get(name)?;
+
+        match self.symbols.get(&symbol) {
+            Some(SymbolDefinition::Namespace(namespace))    => {
+                // Namespaces can't be read directly, but their contents can
+                let mut path = namespace.sync(|namespace| namespace.resolve_reference(field_names))?;
+                path.insert(0, symbol);
+                Some(path)
+            }
+
+            // Any field names are fields of the value of this symbol
+            _                                               => Some(vec![symbol])
+        }
+    }
+
+    ///
+    /// Retrieves the paths of the symbols that the script for a symbol refers to (relative to this namespace)
+    ///
+    fn static_dependencies(&self, symbol: FloScriptSymbol) -> Vec<Vec<FloScriptSymbol>> {
+        let mut dependencies    = vec![];
+        let mut found           = HashSet::new();
+
+        if let Some((_, references)) = self.references.get(&symbol) {
+            for reference in references.iter() {
+                if let Some(path) = self.resolve_reference(&reference.names) {
+                    if found.insert(path.clone()) {
+                        dependencies.push(path);
+                    }
+                }
+            }
+        }
+
+        dependencies
+    }
+
+    ///
+    /// Retrieves the paths of the symbols that the symbol at a path depends upon (all paths are relative to this namespace)
+    ///
+    fn dependencies_of(&self, path: &[FloScriptSymbol]) -> Vec<Vec<FloScriptSymbol>> {
+        match path {
+            []                          => vec![],
+            [symbol]                    => {
+                let mut dependencies = self.static_dependencies(*symbol);
+
+                for dependency in self.read_symbols(*symbol) {
+                    if !dependencies.contains(&dependency) {
+                        dependencies.push(dependency);
+                    }
+                }
+
+                dependencies
+            },
+            [namespace, path @ ..]      => {
+                let dependencies = self.get_namespace(*namespace)
+                    .map(|child| child.sync(|child| child.dependencies_of(path)))
+                    .unwrap_or_default();
+
+                dependencies.into_iter()
+                    .map(|dependency| Some(*namespace).into_iter().chain(dependency).collect())
+                    .collect()
+            }
+        }
+    }
+
+    ///
+    /// Retrieves the paths of the symbols that a script read the last time it was evaluated
+    ///
+    fn read_symbols(&self, symbol: FloScriptSymbol) -> Vec<Vec<FloScriptSymbol>> {
+        self.dynamic_dependencies.get(&symbol)
+            .map(|read_symbols| read_symbols.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Adds the symbols in this namespace (which is at the specified path) and their dependencies to a dependency graph
+    ///
+    pub fn add_to_dependency_graph(&self, path: &[FloScriptSymbol], graph: &mut DependencyGraph) {
+        use self::SymbolDefinition::*;
+
+        let to_path = |symbol_path: &[FloScriptSymbol]| {
+            let full_path = path.iter().chain(symbol_path.iter()).cloned().collect::<Vec<_>>();
+            let (symbol, namespaces) = full_path.split_last().unwrap();
+
+            FloScriptPath::new(namespaces.to_vec(), *symbol)
+        };
+
+        // Order by ID so the graph is the same every time it's generated
+        let mut symbols = self.symbols.keys().cloned().collect::<Vec<_>>();
+        symbols.sort_by_key(|symbol| symbol.id());
+
+        for symbol in symbols {
+            let symbol_path = to_path(&[symbol]);
+            graph.add_symbol(symbol_path.clone());
+
+            match self.symbols.get(&symbol) {
+                Some(ActiveScript(_, _))        |
+                Some(ScriptError(_, _))         |
+                Some(Computing(_))              |
+                Some(Streaming(_))              => {
+                    for dependency in self.static_dependencies(symbol) {
+                        graph.add_dependency(symbol_path.clone(), to_path(&dependency), DependencyKind::Static);
+                    }
+                }
+
+                Some(Namespace(namespace))      => {
+                    let namespace_path = path.iter().cloned().chain(Some(symbol)).collect::<Vec<_>>();
+                    namespace.sync(|namespace| namespace.add_to_dependency_graph(&namespace_path, graph));
+                }
+
+                Some(Input(_))                  |
+                Some(Constant(_, _))            |
+                None                            => { }
+            }
+
+            for dependency in self.read_symbols(symbol) {
+                graph.add_dependency(symbol_path.clone(), to_path(&dependency), DependencyKind::Dynamic);
+            }
+        }
+    }
+
+    ///
+    /// Checks that a script doesn't depend on itself, reporting the cycle to anything watching the namespace if it does
+    ///
+    fn check_dependency_cycle(&mut self, symbol: FloScriptSymbol) -> FloScriptResult<()> {
+        if let Some(cycle) = self.find_cycle(symbol) {
+            self.notify(NotebookUpdate::DependencyCycle(cycle.clone()));
+            Err(FloScriptError::DependencyCycle(cycle))
+        } else {
+            Ok(())
+        }
+    }
+
+    ///
+    /// Finds a cycle of dependencies that leads from a symbol back to itself
+    ///
+    /// This only follows the dependencies that lead from the symbol, so it doesn't need to look at the rest of the namespace. As
+    /// with `DependencyGraph::find_cycle()`, the result starts with the symbol, and each symbol depends on the next one.
+    ///
+    fn find_cycle(&self, symbol: FloScriptSymbol) -> Option<Vec<FloScriptPath>> {
+        let start       = vec![symbol];
+        let mut visited = HashSet::new();
+        let mut cycle   = vec![start.clone()];
+
+        // The dependencies still to search at each point along the path (reversed so they're searched in order)
+        let mut pending = vec![self.dependencies_of(&start).into_iter().rev().collect::<Vec<_>>()];
+
+        while let Some(dependencies) = pending.last_mut() {
+            match dependencies.pop() {
+                None                                => { pending.pop(); cycle.pop(); }
+                Some(dependency)                    => {
+                    if dependency == start {
+                        return Some(cycle.into_iter().map(|path| {
+                            let (symbol, namespaces) = path.split_last().unwrap();
+                            FloScriptPath::new(namespaces.to_vec(), *symbol)
+                        }).collect());
+                    }
+
+                    if visited.insert(dependency.clone()) {
+                        pending.push(self.dependencies_of(&dependency).into_iter().rev().collect());
+                        cycle.push(dependency);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    ///
+    /// Describes the symbols defined in this namespace, ordered by name (anonymous symbols are listed last)
+    ///
+    /// Computing scripts without a declared type that haven't been read yet are type-checked to find the type of value that
+    /// they will produce.
+    ///
+    pub fn symbols(&mut self) -> Vec<NotebookSymbol> {
+        use self::SymbolDefinition::*;
+
+        let mut symbols = self.symbols.iter()
+            .map(|(symbol, definition)| {
+                let (kind, value_type, script) = match definition {
+                    Input(input_source)                 => (SymbolKind::Input, Some(input_source.input_type().clone()), None),
+                    Constant(value, _)                  => (SymbolKind::Constant, Some(value.value_type()), None),
+                    Computing(script)                   => (SymbolKind::ComputingScript, self.declared_types.get(symbol).cloned(), Some((**script).clone())),
+                    Streaming(script)                   => (SymbolKind::StreamingScript, None, Some((**script).clone())),
+                    ActiveScript(script, input_source)  => (SymbolKind::ComputingScript, Some(input_source.input_type().clone()), Some((**script).clone())),
+                    ScriptError(script, _)              => (SymbolKind::Error, None, Some((**script).clone())),
+                    Namespace(_)                        => (SymbolKind::Namespace, None, None)
+                };
+
+                NotebookSymbol {
+                    path:       FloScriptPath::from(*symbol),
+                    kind,
+                    value_type,
+                    script,
+                    status:     self.status(*symbol).unwrap_or(SymbolStatus::NotStarted),
+                    metadata:   self.metadata.get(symbol).cloned()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // The type produced by a script that hasn't been started is inferred from its source
+        for symbol in symbols.iter_mut() {
+            if symbol.kind == SymbolKind::ComputingScript && symbol.value_type.is_none() {
+                symbol.value_type = self.inferred_script_type(symbol.symbol());
+            }
+        }
+
+        symbols.sort_by_key(|symbol| (symbol.symbol().name().is_none(), symbol.symbol().name(), symbol.symbol().id()));
+
+        symbols
+    }
+
+    ///
+    /// Type-checks a computing script that hasn't been started to find the type of the values it will produce
+    ///
+    /// Unlike `symbol_type()`, this doesn't report dependency cycles: the cycle is reported when the script is read instead.
+    ///
+    fn inferred_script_type(&mut self, symbol: FloScriptSymbol) -> Option<ScriptTypeDescription> {
+        let script = match self.symbols.get(&symbol) {
+            Some(SymbolDefinition::Computing(script))   => Arc::clone(script),
+            _                                           => { return None; }
+        };
+
+        if self.find_cycle(symbol).is_some() {
+            return None;
+        }
+
+        let script_type         = self.typecheck_script_type(symbol, &script, None).ok()?;
+        let computing_thread    = self.get_computing_thread().ok()?;
+
+        default_type_description(&script_type, &computing_thread).ok()
+    }
+
+    ///
+    /// Creates a definition of the named symbols in this namespace, which can be saved and loaded again later on
+    ///
+    pub fn definition(&self, types: &ScriptTypeRegistry) -> FloScriptResult<NotebookDefinition> {
+        let mut symbols = self.symbols.iter()
+            .filter_map(|(symbol, definition)| {
+                // Only named symbols can be saved
+                let name = symbol.name()?;
+
+                Some(self.symbol_definition(*symbol, name, definition, types))
+            })
+            .collect::<FloScriptResult<Vec<_>>>()?;
+
+        // Order by name so that the same notebook always produces the same definition
+        symbols.sort_by(|a, b| a.name().cmp(b.name()));
+
+        Ok(NotebookDefinition {
+            run_io: self.run_io,
+            symbols
+        })
+    }
+
+    ///
+    /// Creates the saved definition of a named symbol
+    ///
+    fn symbol_definition(&self, symbol: FloScriptSymbol, name: String, definition: &SymbolDefinition, types: &ScriptTypeRegistry) -> FloScriptResult<NotebookSymbolDefinition> {
+        use self::SymbolDefinition::*;
+
+        Ok(match definition {
+            Input(input_source)         => NotebookSymbolDefinition::Input { name, type_name: types.registered_name(input_source.input_type())? },
+            ActiveScript(script, _)     |
+            ScriptError(script, _)      |
+            Computing(script)           => match self.declared_types.get(&symbol) {
+                Some(output_type)   => NotebookSymbolDefinition::TypedComputingScript { name, type_name: types.registered_name(output_type)?, script: (**script).clone() },
+                None                => NotebookSymbolDefinition::ComputingScript { name, script: (**script).clone() }
+            },
+            Streaming(script)           => NotebookSymbolDefinition::StreamingScript { name, script: (**script).clone() },
+            Constant(value, _)          => NotebookSymbolDefinition::Constant { name, value: value.clone() },
+            Namespace(namespace)        => NotebookSymbolDefinition::Namespace { name, definition: namespace.sync(|namespace| namespace.definition(types))? }
+        })
+    }
+}
+
+impl Drop for GluonScriptNamespace {
+    fn drop(&mut self) {
+        for symbol in self.symbols.keys() {
+            release_symbol(*symbol);
+        }
+    }
+}
+
+///
+/// Attaches the value of a constant to an input stream source
+///
+fn attach_constant(input_source: &mut InputStreamSource, value: &ScriptValue) -> FloScriptResult<()> {
+    use self::ScriptValue::*;
+
+    match value {
+        Bool(value)     => input_source.attach_constant(*value),
+        I32(value)      => input_source.attach_constant(*value),
+        U32(value)      => input_source.attach_constant(*value),
+        I64(value)      => input_source.attach_constant(*value),
+        U64(value)      => input_source.attach_constant(*value),
+        F32(value)      => input_source.attach_constant(*value),
+        F64(value)      => input_source.attach_constant(*value),
+        String(value)   => input_source.attach_constant(value.clone())
     }
 }
@@ -195,6 +195,7 @@ pub fn load_flo_computed(vm: &Thread) -> Result<()> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use super::super::std_io::*;
     use gluon::*;
     use gluon::vm::api::*;
     use gluon::vm::primitives;
@@ -205,7 +206,7 @@ mod test {
 
     #[test]
     fn make_type_from_derived_state() {
-        let vm = new_vm();
+        let vm = new_script_vm();
         load_flo_computed(&vm).unwrap();
 
         // Gluon only imports user data types if the corresponding module has previously been imported
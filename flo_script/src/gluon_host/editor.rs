@@ -1,10 +1,16 @@
 use super::core::*;
+use super::symbol_names::*;
+use super::execution_limits::*;
+use super::super::error::*;
 use super::super::symbol::*;
+use super::super::symbol_table::*;
 use super::super::editor::*;
+use super::super::script_type_registry::*;
+use super::super::notebook_definition::*;
 
 use desync::{Desync, pipe_in};
 use futures::*;
-use futures::sync::oneshot;
+use futures::sync::{oneshot, mpsc};
 
 use std::sync::*;
 
@@ -21,7 +27,16 @@ pub enum GluonScriptEdit {
     ScriptEdit(ScriptEdit),
 
     /// Sets whether or not I/O expressions are evaluated
-    SetRunIo(bool)
+    SetRunIo(bool),
+
+    /// Sets how symbols whose names can't be used in a script are treated in a namespace and the namespaces within it (namespaces created afterwards use the same policy). This fails if the policy would give two symbols in a namespace the same name in scripts
+    SetNamePolicy(SymbolNamePolicy),
+
+    /// Sets the limits on the time, steps and memory that each evaluation of a script can use (namespaces created afterwards use the same limits)
+    SetExecutionLimits(ExecutionLimits),
+
+    /// Performs one or more Gluon edits in a namespace
+    WithNamespace(FloScriptSymbol, Vec<GluonScriptEdit>)
 }
 
 ///
@@ -29,15 +44,18 @@ pub enum GluonScriptEdit {
 ///
 pub struct GluonScriptEditor {
     /// The core of the host that this editor represents
-    core: Arc<Desync<GluonScriptHostCore>>
+    core: Arc<Desync<GluonScriptHostCore>>,
+
+    /// The symbol table of the host that this editor represents
+    symbols: FloScriptSymbolTable
 }
 
 impl GluonScriptEditor {
     ///
     /// Creates a new script editor
     ///
-    pub (crate) fn new(core: Arc<Desync<GluonScriptHostCore>>) -> GluonScriptEditor {
-        GluonScriptEditor { core }
+    pub (crate) fn new(core: Arc<Desync<GluonScriptHostCore>>, symbols: FloScriptSymbolTable) -> GluonScriptEditor {
+        GluonScriptEditor { core, symbols }
     }
 
     ///
@@ -71,13 +89,96 @@ impl GluonScriptEditor {
         // Pipe the edits straight to the core
         pipe_in(core, edits, |core, edit| {
             if let Ok(edit) = edit {
-                core.edit(edit);
+                // Results are only reported for sequenced edits
+                let (_sequence, _result) = core.edit(edit);
             }
         });
 
         // Result is a future indicating when we've exhausted the stream (it'll signal cancelled if we stop polling the stream, which will happen if the host is dropped before it completes)
         finished
     }
+
+    ///
+    /// Receives edits from the specified stream, returning a stream that acknowledges each edit as it is applied
+    ///
+    /// The acknowledgement stream finishes once the edit stream is exhausted (or early if the script host is dropped).
+    ///
+    pub fn send_sequenced_gluon_edits<EditStream: 'static+Send+Stream<Item=GluonScriptEdit, Error=()>>(&self, edits: EditStream) -> impl Stream<Item=EditAcknowledgement, Error=()>+Send {
+        let core = Arc::clone(&self.core);
+
+        // Acknowledgements are sent via a channel: this will close when the pipe is finished with the processing function
+        let (send_acknowledgement, acknowledgements) = mpsc::unbounded();
+
+        pipe_in(core, edits, move |core, edit| {
+            if let Ok(edit) = edit {
+                let (sequence, result) = core.edit(edit);
+                send_acknowledgement.unbounded_send(EditAcknowledgement { sequence, result }).ok();
+            }
+        });
+
+        acknowledgements
+    }
+
+    ///
+    /// Generates the Gluon edits needed to define the symbols in a notebook definition (including the I/O flags for each namespace)
+    ///
+    /// As for `NotebookDefinition::edits()`, the symbols in the edits are retained until they're released.
+    ///
+    fn definition_edits(&self, definition: &NotebookDefinition, types: &ScriptTypeRegistry) -> FloScriptResult<Vec<GluonScriptEdit>> {
+        let mut edits = vec![GluonScriptEdit::SetRunIo(definition.run_io)];
+
+        for symbol_definition in definition.symbols.iter() {
+            let edit = if let NotebookSymbolDefinition::Namespace { name, definition } = symbol_definition {
+                self.definition_edits(definition, types).map(|namespace_edits| GluonScriptEdit::WithNamespace(self.symbols.symbol(name), namespace_edits))
+            } else {
+                symbol_definition.edit(&self.symbols, types).map(GluonScriptEdit::ScriptEdit)
+            };
+
+            match edit {
+                Ok(edit)    => edits.push(edit),
+                Err(err)    => {
+                    self.release_definition_edits(&edits);
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Replaces the contents of this editor with the symbols from a saved notebook definition
+    ///
+    /// Unlike `load_definition`, this will also restore whether or not each namespace runs I/O expressions. As with
+    /// `load_definition`, the result is the first error that occurred while defining the symbols.
+    ///
+    pub fn load_gluon_definition(&self, definition: &NotebookDefinition, types: &ScriptTypeRegistry) -> FloScriptResult<()> {
+        let edits               = self.definition_edits(definition, types)?;
+        let num_edits           = edits.len() + 1;
+        let acknowledgements    = self.send_sequenced_gluon_edits(stream::iter_ok(Some(GluonScriptEdit::ScriptEdit(ScriptEdit::Clear)).into_iter().chain(edits.clone())));
+        let result              = wait_for_loaded_edits(acknowledgements, num_edits);
+
+        // The notebook retains the symbols it has defined, so the edits no longer need to
+        self.release_definition_edits(&edits);
+
+        result
+    }
+
+    ///
+    /// Releases the symbols retained by the edits generated by `definition_edits()`
+    ///
+    fn release_definition_edits(&self, edits: &[GluonScriptEdit]) {
+        for edit in edits.iter() {
+            match edit {
+                GluonScriptEdit::ScriptEdit(edit)               => release_edit_symbols(&self.symbols, std::slice::from_ref(edit)),
+                GluonScriptEdit::WithNamespace(symbol, edits)   => {
+                    self.symbols.release(*symbol);
+                    self.release_definition_edits(edits);
+                }
+                _                                               => { }
+            }
+        }
+    }
 }
 
 impl FloScriptEditor for GluonScriptEditor {
@@ -100,4 +201,29 @@ impl FloScriptEditor for GluonScriptEditor {
 
         Box::new(finished_edits)
     }
+
+    ///
+    /// Performs edits from the specified stream in order, returning a stream that acknowledges each edit once it has been applied
+    ///
+    fn send_sequenced_edits<Edits: 'static+Send+Stream<Item=ScriptEdit, Error=()>>(&self, edits: Edits) -> Box<dyn Stream<Item=EditAcknowledgement, Error=()>+Send> {
+        let edits = edits.map(GluonScriptEdit::ScriptEdit);
+
+        Box::new(self.send_sequenced_gluon_edits(edits))
+    }
+
+    ///
+    /// Checks a set of edits against the current state of the notebook without applying them
+    ///
+    /// Any scripts defined by the edits are compiled and type-checked, and every problem that's found is reported.
+    ///
+    fn validate(&self, edits: Vec<ScriptEdit>) -> EditValidation {
+        self.core.sync(move |core| core.validate(edits))
+    }
+
+    ///
+    /// Retrieves the symbol table of the host that this editor belongs to
+    ///
+    fn symbols(&self) -> FloScriptSymbolTable {
+        self.symbols.clone()
+    }
 }
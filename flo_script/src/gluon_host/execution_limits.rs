@@ -0,0 +1,129 @@
+use super::super::error::*;
+
+use gluon;
+use gluon::vm;
+use gluon::vm::thread::{Thread, ThreadInternal, HookFlags};
+use futures::*;
+
+use std::sync::*;
+use std::time::{Duration, Instant};
+
+///
+/// Limits on the resources that a single evaluation of a script in a namespace can use
+///
+/// A script that exceeds a limit is stopped, and the status of its symbol is set to failed with an error describing the
+/// limit (other scripts in the namespace are unaffected). Limits that are `None` are not enforced.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ExecutionLimits {
+    /// The longest time that evaluating a script can take
+    pub time: Option<Duration>,
+
+    /// The maximum number of steps (function calls) that evaluating a script can make
+    pub steps: Option<u64>,
+
+    /// The maximum amount of memory (in bytes) that the VM can allocate while evaluating a script
+    pub memory: Option<usize>
+}
+
+impl ExecutionLimits {
+    ///
+    /// Creates a set of limits that doesn't restrict scripts at all
+    ///
+    pub fn unlimited() -> ExecutionLimits {
+        ExecutionLimits::default()
+    }
+
+    ///
+    /// Sets up a thread so that any script evaluated on it is stopped when it exceeds these limits
+    ///
+    /// The result records which limit was exceeded, so `evaluation_error()` can report it.
+    ///
+    pub (crate) fn apply_to_thread(&self, thread: &Thread) -> Arc<Mutex<Option<FloScriptError>>> {
+        let exceeded = Arc::new(Mutex::new(None));
+
+        if let Some(memory) = self.memory {
+            thread.set_memory_limit(memory);
+        }
+
+        if self.time.is_some() || self.steps.is_some() {
+            // Time and steps are checked whenever the script calls a function (loops in Gluon are written as recursive calls)
+            let time_limit      = self.time;
+            let step_limit      = self.steps;
+            let hook_exceeded   = Arc::clone(&exceeded);
+            let mut steps       = 0;
+            let mut started     = None;
+
+            let mut context     = thread.context();
+            context.set_hook(Some(Box::new(move |_thread, _debug_info| {
+                // The script starts running when its future is first polled, which might be some time after the thread was created
+                let started = *started.get_or_insert_with(Instant::now);
+                steps       += 1;
+
+                let limit_error = match (time_limit, step_limit) {
+                    (_, Some(step_limit)) if steps > step_limit                 => Some(FloScriptError::StepLimitExceeded(step_limit)),
+                    (Some(time_limit), _) if started.elapsed() > time_limit     => Some(FloScriptError::TimeLimitExceeded(time_limit)),
+                    _                                                           => None
+                };
+
+                if let Some(limit_error) = limit_error {
+                    let message = format!("{:?}", limit_error);
+                    *hook_exceeded.lock().unwrap() = Some(limit_error);
+
+                    Err(vm::Error::Message(message))
+                } else {
+                    Ok(Async::Ready(()))
+                }
+            })));
+            context.set_hook_mask(HookFlags::CALL_FLAG);
+        }
+
+        exceeded
+    }
+
+    ///
+    /// Converts an error from evaluating a script on a thread set up by `apply_to_thread()` into a script error
+    ///
+    pub (crate) fn evaluation_error(&self, exceeded: &Mutex<Option<FloScriptError>>, error: &gluon::Error) -> FloScriptError {
+        if let Some(limit_error) = exceeded.lock().unwrap().clone() {
+            return limit_error;
+        }
+
+        match (error, self.memory) {
+            (gluon::Error::VM(vm::Error::OutOfMemory { .. }), Some(memory)) => FloScriptError::MemoryLimitExceeded(memory),
+            (error, _)                                                      => FloScriptError::ScriptError(error.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn out_of_memory_is_memory_limit() {
+        let limits      = ExecutionLimits { memory: Some(1024), ..ExecutionLimits::unlimited() };
+        let exceeded    = Mutex::new(None);
+        let error       = gluon::Error::VM(vm::Error::OutOfMemory { limit: 1024, needed: 2048 });
+
+        assert!(limits.evaluation_error(&exceeded, &error) == FloScriptError::MemoryLimitExceeded(1024));
+    }
+
+    #[test]
+    fn recorded_limit_takes_priority() {
+        let limits      = ExecutionLimits { steps: Some(100), ..ExecutionLimits::unlimited() };
+        let exceeded    = Mutex::new(Some(FloScriptError::StepLimitExceeded(100)));
+        let error       = gluon::Error::VM(vm::Error::Message("Stopped".to_string()));
+
+        assert!(limits.evaluation_error(&exceeded, &error) == FloScriptError::StepLimitExceeded(100));
+    }
+
+    #[test]
+    fn other_errors_are_script_errors() {
+        let limits      = ExecutionLimits::unlimited();
+        let exceeded    = Mutex::new(None);
+        let error       = gluon::Error::VM(vm::Error::Message("Failed".to_string()));
+
+        assert!(limits.evaluation_error(&exceeded, &error) == FloScriptError::ScriptError(error.to_string()));
+    }
+}
@@ -0,0 +1,173 @@
+use super::core_namespace::*;
+use super::symbol_status::*;
+use super::super::error::*;
+use super::super::symbol::*;
+
+use desync::Desync;
+use futures::*;
+use futures::sync::oneshot;
+
+use std::mem;
+use std::sync::*;
+
+/// A stream that reads the values of a single definition of a symbol
+type DefinitionStream<Item> = Box<dyn Stream<Item=Item, Error=()>+Send>;
+
+/// Function that creates a stream to read from a symbol in a namespace
+type ReadFn<Item> = fn(&mut GluonScriptNamespace, FloScriptSymbol) -> FloScriptResult<DefinitionStream<Item>>;
+
+/// The result of reading the current definition of a symbol
+type ReadResult<Item> = FloScriptResult<(DefinitionStream<Item>, DefinitionToken)>;
+
+///
+/// The states of a following stream
+///
+enum FollowingState<Item> {
+    /// Reading values from the current definition of the symbol
+    Reading(DefinitionStream<Item>, DefinitionToken),
+
+    /// The current definition has stopped producing values and the stream is waiting for it to be replaced
+    WaitingForDefinition(DefinitionToken),
+
+    /// The definition has been replaced and the stream is waiting for the namespace to read the new one
+    Switching(Box<dyn Future<Item=ReadResult<Item>, Error=oneshot::Canceled>+Send>),
+
+    /// The symbol can no longer be read
+    Finished
+}
+
+///
+/// Stream that reads from a symbol, switching to the new definition whenever the symbol is redefined
+///
+/// Values from a definition that has been replaced are never returned. If the new definition can't be read (for instance,
+/// because the symbol was removed or now has a different type), the stream finishes. If the current definition fails while
+/// producing a value (for instance, because a script exceeded its execution limits), the stream returns the error that
+/// the status of the symbol reports.
+///
+pub struct FollowingStream<Item> {
+    /// The namespace containing the symbol
+    namespace: Arc<Desync<GluonScriptNamespace>>,
+
+    /// The symbol that's being read
+    symbol: FloScriptSymbol,
+
+    /// Reads the current definition of the symbol
+    read: ReadFn<Item>,
+
+    /// True if the stream waits for the symbol to be redefined when a definition stops producing values (rather than finishing)
+    wait_for_definition: bool,
+
+    /// What this stream is currently doing
+    state: FollowingState<Item>
+}
+
+impl<Item: 'static+Send> FollowingStream<Item> {
+    ///
+    /// Starts reading from a symbol, returning an error immediately if the current definition can't be read
+    ///
+    pub fn read(namespace: &Arc<Desync<GluonScriptNamespace>>, symbol: FloScriptSymbol, read: ReadFn<Item>) -> FloScriptResult<FollowingStream<Item>> {
+        let (stream, token) = namespace.sync(move |core| Self::read_definition(core, symbol, read))?;
+
+        Ok(FollowingStream {
+            namespace:              Arc::clone(namespace),
+            symbol,
+            read,
+            wait_for_definition:    false,
+            state:                  FollowingState::Reading(stream, token)
+        })
+    }
+
+    ///
+    /// Starts reading from a symbol, continuing with its next definition whenever its current definition stops producing values
+    ///
+    /// This is used by scripts that read symbols from other namespaces: a script that has been evaluated doesn't produce any more
+    /// values, but the scripts reading it need to be evaluated again if it's redefined.
+    ///
+    pub fn follow(namespace: &Arc<Desync<GluonScriptNamespace>>, symbol: FloScriptSymbol, read: ReadFn<Item>) -> FloScriptResult<FollowingStream<Item>> {
+        let mut stream              = Self::read(namespace, symbol, read)?;
+        stream.wait_for_definition  = true;
+
+        Ok(stream)
+    }
+
+    ///
+    /// Reads the current definition of a symbol along with the token that indicates when it's replaced
+    ///
+    fn read_definition(core: &mut GluonScriptNamespace, symbol: FloScriptSymbol, read: ReadFn<Item>) -> ReadResult<Item> {
+        let stream  = read(core, symbol)?;
+        let token   = core.definition_token(symbol);
+
+        Ok((stream, token))
+    }
+
+    ///
+    /// Starts reading the new definition of the symbol
+    ///
+    fn switch_definition(&self) -> FollowingState<Item> {
+        let symbol  = self.symbol;
+        let read    = self.read;
+
+        FollowingState::Switching(Box::new(self.namespace.future(move |core| Self::read_definition(core, symbol, read))))
+    }
+}
+
+impl<Item: 'static+Send> Stream for FollowingStream<Item> {
+    type Item   = Item;
+    type Error  = FloScriptError;
+
+    fn poll(&mut self) -> Poll<Option<Item>, FloScriptError> {
+        use self::FollowingState::*;
+
+        loop {
+            match mem::replace(&mut self.state, Finished) {
+                Finished                    => { return Ok(Async::Ready(None)); }
+
+                Switching(mut new_definition) => {
+                    match new_definition.poll() {
+                        Ok(Async::NotReady)                     => { self.state = Switching(new_definition); return Ok(Async::NotReady); }
+                        Ok(Async::Ready(Ok((stream, token))))   => { self.state = Reading(stream, token); }
+                        Ok(Async::Ready(Err(_))) | Err(_)       => { return Ok(Async::Ready(None)); }
+                    }
+                }
+
+                WaitingForDefinition(token) => {
+                    token.notify_when_replaced();
+
+                    if token.is_current() {
+                        self.state = WaitingForDefinition(token);
+                        return Ok(Async::NotReady);
+                    } else {
+                        self.state = self.switch_definition();
+                    }
+                }
+
+                Reading(mut stream, token)  => {
+                    // Wake up when the definition is replaced, even if the stream is waiting for a value that will never arrive
+                    token.notify_when_replaced();
+                    let result = stream.poll();
+
+                    if token.is_current() {
+                        return match result {
+                            Ok(Async::Ready(None)) | Err(()) => {
+                                // The definition has stopped producing values: report why if it failed (the task is woken if the symbol is redefined)
+                                match token.failure() {
+                                    Some(error)                         => Err(error),
+                                    None if self.wait_for_definition    => { self.state = WaitingForDefinition(token); Ok(Async::NotReady) },
+                                    None                                => Ok(Async::Ready(None))
+                                }
+                            }
+
+                            Ok(ready) => {
+                                self.state = Reading(stream, token);
+                                Ok(ready)
+                            }
+                        };
+                    } else {
+                        // Discard whatever the old definition produced and read the new one
+                        self.state = self.switch_definition();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use super::core::*;
 use super::editor::*;
 use super::notebook::*;
+use super::script_executor::*;
+use super::vm_sharing::*;
+use super::script_type_declaration::*;
 use super::super::host::*;
+use super::super::symbol_table::*;
+use super::super::error::*;
 
 use desync::Desync;
+use gluon::vm::api::{VmType};
 
 use std::sync::*;
 
@@ -14,7 +20,10 @@ use std::sync::*;
 ///
 pub struct GluonScriptHost {
     /// The core is used to execute the scripts asynchronously and process their results 
-    core: Arc<Desync<GluonScriptHostCore>>
+    core: Arc<Desync<GluonScriptHostCore>>,
+
+    /// The symbol table for this host
+    symbols: FloScriptSymbolTable
 }
 
 impl GluonScriptHost {
@@ -22,12 +31,67 @@ impl GluonScriptHost {
     /// Creates a new Gluon script host with no scripts running
     /// 
     pub fn new() -> GluonScriptHost {
+        Self::with_symbol_table(FloScriptSymbolTable::new())
+    }
+
+    ///
+    /// Creates a new Gluon script host that uses an existing symbol table (for example, one shared by all the hosts in a session)
+    ///
+    pub fn with_symbol_table(symbols: FloScriptSymbolTable) -> GluonScriptHost {
         let core = GluonScriptHostCore::new();
 
         GluonScriptHost {
-            core: Arc::new(Desync::new(core))
+            core:       Arc::new(Desync::new(core)),
+            symbols
         }
     }
+
+    ///
+    /// Creates a new Gluon script host that evaluates its scripts using an executor (such as a thread pool)
+    ///
+    /// Output streams then only deliver the results of scripts once they have finished, rather than evaluating them on the thread
+    /// that's polling the stream.
+    ///
+    pub fn with_executor<Exec: 'static+ScriptExecutor>(executor: Exec) -> GluonScriptHost {
+        Self::with_symbol_table_and_executor(FloScriptSymbolTable::new(), executor)
+    }
+
+    ///
+    /// Creates a new Gluon script host that uses an existing symbol table and evaluates its scripts using an executor
+    ///
+    pub fn with_symbol_table_and_executor<Exec: 'static+ScriptExecutor>(symbols: FloScriptSymbolTable, executor: Exec) -> GluonScriptHost {
+        let host                                = Self::with_symbol_table(symbols);
+        let executor: Arc<dyn ScriptExecutor>   = Arc::new(executor);
+
+        host.core.sync(move |core| core.root_namespace().sync(move |namespace| namespace.set_executor(Some(executor))));
+
+        host
+    }
+
+    ///
+    /// Sets how the namespaces in this host share Gluon VMs
+    ///
+    /// By default, every namespace creates its own VM. VMs are created when scripts are first evaluated, so this should be set
+    /// before any scripts are defined: namespaces that already exist keep the VMs they're using.
+    ///
+    pub fn set_vm_sharing(&self, sharing: VmSharing) {
+        self.core.sync(move |core| core.root_namespace().sync(move |namespace| namespace.set_vm_sharing(sharing)));
+    }
+
+    ///
+    /// Declares a type derived with `#[derive(ScriptType)]` by name, so that scripts can refer to it
+    ///
+    /// Types are declared in a VM automatically the first time they're used with it (for example, as the type of an input
+    /// symbol), so this is only needed for types that scripts refer to by name before then. As with `set_vm_sharing()`, this
+    /// should be called before any scripts are defined: it applies to the VMs that namespaces create from now on.
+    ///
+    /// Gluon refers to types by name, so this returns `FloScriptError::UnsupportedType` if a different type with the same name
+    /// has already been declared.
+    ///
+    pub fn declare_type<T: DeclaredScriptType>(&self) -> FloScriptResult<()>
+    where <T as VmType>::Type: Sized {
+        self.core.sync(move |core| core.root_namespace().sync(move |namespace| namespace.declare_type::<T>()))
+    }
 }
 
 impl FloScriptHost for GluonScriptHost {
@@ -51,6 +115,13 @@ impl FloScriptHost for GluonScriptHost {
     /// The editor can be used to define input symbols, scripts and namespaces
     /// 
     fn editor(&self) -> Self::Editor {
-        GluonScriptEditor::new(Arc::clone(&self.core))
+        GluonScriptEditor::new(Arc::clone(&self.core), self.symbols.clone())
+    }
+
+    ///
+    /// Retrieves the symbol table owned by this host
+    ///
+    fn symbols(&self) -> FloScriptSymbolTable {
+        self.symbols.clone()
     }
 }
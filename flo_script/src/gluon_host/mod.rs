@@ -5,8 +5,27 @@ mod editor;
 mod notebook;
 pub (crate) mod derived_state;
 mod computing_script;
+mod script_dependencies;
+mod script_rewrite;
+mod symbol_names;
+mod symbol_status;
+mod script_type_declaration;
 mod dynamic_record;
+mod execution_limits;
+mod following_stream;
+mod script_executor;
+mod vm_sharing;
+mod std_io;
 
 pub use self::host::*;
 pub use self::editor::*;
 pub use self::notebook::*;
+pub use self::symbol_names::*;
+pub use self::script_type_declaration::*;
+pub use self::execution_limits::*;
+pub use self::script_executor::*;
+pub use self::vm_sharing::*;
+pub use self::std_io::*;
+
+pub (crate) use self::core_namespace::GluonScriptNamespace;
+pub (crate) use self::following_stream::FollowingStream;
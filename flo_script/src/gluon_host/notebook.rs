@@ -1,8 +1,15 @@
 use super::core_namespace::*;
+use super::following_stream::*;
 use super::super::error::*;
 use super::super::symbol::*;
 use super::super::notebook::*;
+use super::super::notebook_definition::*;
+use super::super::script_type_registry::*;
 use super::super::script_type_description::*;
+use super::super::symbol_metadata::*;
+use super::super::notebook_symbol::*;
+use super::super::dependency_graph::*;
+use super::super::input_value::*;
 
 use gluon::vm::api::*;
 use desync::Desync;
@@ -13,6 +20,7 @@ use std::sync::*;
 ///
 /// Provides notebook functionality for a Gluon script host
 ///
+#[derive(Clone)]
 pub struct GluonScriptNotebook {
     /// The namespace that this notebook represents
     namespace: Arc<Desync<GluonScriptNamespace>>
@@ -25,15 +33,28 @@ impl GluonScriptNotebook {
     pub (crate) fn new(namespace: Arc<Desync<GluonScriptNamespace>>) -> GluonScriptNotebook {
         GluonScriptNotebook { namespace }
     }
+
+    ///
+    /// Retrieves a definition of the symbols in this notebook that can be saved and loaded again later on
+    ///
+    /// Types are saved using the names they have in the specified registry, so this fails if any input or declared
+    /// script type is not registered.
+    ///
+    pub fn definition(&self, types: &ScriptTypeRegistry) -> FloScriptResult<NotebookDefinition> {
+        self.namespace.sync(|core| core.definition(types))
+    }
 }
 
 impl FloScriptNotebook for GluonScriptNotebook {
     /// The type of the stream used to receive updates from this notebook
     type UpdateStream = Box<dyn Stream<Item=NotebookUpdate, Error=()>+Send>;
 
+    /// The type of the stream used to watch the status of a symbol
+    type StatusStream = Box<dyn Stream<Item=SymbolStatus, Error=()>+Send>;
+
     /// Retrieves a stream of updates for this notebook
     fn updates(&self) -> Self::UpdateStream {
-        unimplemented!()
+        Box::new(self.namespace.sync(|core| core.updates()))
     }
 
     /// Retrieves a notebook containing the symbols in the specified namespace
@@ -44,6 +65,44 @@ impl FloScriptNotebook for GluonScriptNotebook {
         .map(|namespace| GluonScriptNotebook::new(namespace))
     }
 
+    /// Retrieves the metadata for a symbol, if any has been set
+    fn metadata(&self, symbol: FloScriptSymbol) -> Option<SymbolMetadata> {
+        self.namespace.sync(move |core| core.metadata(symbol))
+    }
+
+    /// Retrieves the current status of a symbol, or `None` if it's not defined
+    fn status(&self, symbol: FloScriptSymbol) -> Option<SymbolStatus> {
+        self.namespace.sync(move |core| core.status(symbol))
+    }
+
+    /// Watches the status of a symbol
+    fn watch_status(&self, symbol: FloScriptSymbol) -> FloScriptResult<Self::StatusStream> {
+        self.namespace.sync(move |core| core.watch_status(symbol))
+            .map(|status_stream| -> Self::StatusStream { Box::new(status_stream) })
+    }
+
+    /// Lists the symbols defined in this notebook (not including the contents of any child namespaces)
+    fn symbols(&self) -> Vec<NotebookSymbol> {
+        self.namespace.sync(|core| core.symbols())
+    }
+
+    /// Retrieves the graph of the dependencies between the symbols in this notebook and its child namespaces
+    fn dependency_graph(&self) -> DependencyGraph {
+        self.namespace.sync(|core| {
+            let mut graph = DependencyGraph::new();
+            core.add_to_dependency_graph(&[], &mut graph);
+            graph
+        })
+    }
+
+    /// Retrieves the notebook for a namespace nested within this one
+    fn nested_namespace(&self, namespaces: &[FloScriptSymbol]) -> Option<Self> {
+        self.namespace.sync(move |core| {
+            core.get_nested_namespace(namespaces)
+        })
+        .map(GluonScriptNotebook::new)
+    }
+
     /// Attaches an input stream to an input symbol. This will replace any existing input stream for that symbol if there is one.
     fn attach_input<InputStream: 'static+Stream<Error=()>+Send>(&self, symbol: FloScriptSymbol, input: InputStream) -> FloScriptResult<()> 
     where InputStream::Item: ScriptType {
@@ -52,21 +111,44 @@ impl FloScriptNotebook for GluonScriptNotebook {
         })
     }
 
+    /// Sets the values of several input symbols at once, as a single 'tick'
+    fn send_input_tick(&self, values: Vec<InputValue>) -> FloScriptResult<()> {
+        self.namespace.sync(move |core| {
+            core.send_input_tick(values)
+        })
+    }
+
     /// Creates an output stream to receive the results from a script associated with the specified symbol
-    fn receive_output<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=()>+Send>>
+    fn receive_output<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
     where   OutputItem:             for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
     <OutputItem as VmType>::Type:   Sized {
-        self.namespace.sync(move |core| {
-            core.read_stream(symbol)
-        })
+        FollowingStream::read(&self.namespace, symbol, GluonScriptNamespace::read_stream::<OutputItem>)
+            .map(|stream| -> Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send> { Box::new(stream) })
     }
 
     /// Receives the output stream for the specified symbol as a state stream (which will only return the most recently available symbol when polled)
-    fn receive_output_state<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=()>+Send>>
+    fn receive_output_state<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
     where   OutputItem:             for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
     <OutputItem as VmType>::Type:   Sized {
-        self.namespace.sync(move |core| {
-            core.read_state_stream(symbol)
-        })
+        FollowingStream::read(&self.namespace, symbol, GluonScriptNamespace::read_state_stream::<OutputItem>)
+            .map(|stream| -> Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send> { Box::new(stream) })
+    }
+
+    /// Creates an output stream that converts the results from a symbol to the requested type
+    fn receive_coerced_output<OutputItem>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
+    where   OutputItem:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <OutputItem as VmType>::Type:   Sized {
+        // Values that can't be converted are returned as errors
+        FollowingStream::read(&self.namespace, symbol, GluonScriptNamespace::read_coerced_stream::<OutputItem>)
+            .map(|stream| -> Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send> { Box::new(stream.and_then(|value| value)) })
+    }
+
+    /// Receives the output stream for the specified symbol as a state stream, converting its values to the requested type
+    fn receive_coerced_output_state<OutputItem>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
+    where   OutputItem:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <OutputItem as VmType>::Type:   Sized {
+        // Values that can't be converted are returned as errors
+        FollowingStream::read(&self.namespace, symbol, GluonScriptNamespace::read_coerced_state_stream::<OutputItem>)
+            .map(|stream| -> Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send> { Box::new(stream.and_then(|value| value)) })
     }
 }
@@ -0,0 +1,387 @@
+// SymbolReads uses the Gluon derive macros, which implement their traits inside a constant (see std_io.rs)
+#![allow(non_local_definitions)]
+
+use super::script_rewrite::*;
+use super::super::error::*;
+use super::super::symbol::*;
+use super::super::script_type_description::*;
+
+use gluon::{Compiler};
+use gluon::compiler_pipeline::{Compileable, CompileValue};
+use gluon::base::ast::{Expr, Literal, SpannedExpr};
+use gluon::base::pos::{self, BytePos};
+use gluon::base::symbol::{Symbol};
+use gluon::base::types::{ArcType, Field, Type};
+use gluon::import;
+use gluon::vm;
+use gluon::vm::{ExternModule};
+use gluon::vm::types::{VmInt};
+use gluon::vm::api::{Pushable, Generic};
+use gluon::vm::api::generic::{A};
+use gluon::vm::thread::{Thread, ActiveThread};
+
+use std::any::*;
+use std::sync::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The name of the argument that passes the values of the symbols that a script reads
+const SYMBOLS_ARGUMENT: &str = "__flo_symbols";
+
+/// The name that stands for the script itself in the function that's generated around it
+const SCRIPT_PLACEHOLDER: &str = "__flo_script";
+
+/// The name of the field in the record of symbols that records which of them the script reads
+const READS_FIELD: &str = "__flo_reads";
+
+/// The name of the function that records that a script has read a symbol
+const READ_FUNCTION: &str = "__flo_read";
+
+/// The name that stands for the value that's being read in the expression that records a read
+const VALUE_PLACEHOLDER: &str = "__flo_value";
+
+///
+/// A field in the record of symbols passed to a script
+///
+#[derive(Clone)]
+enum DependencyField {
+    /// The value of a symbol (the index into the list of symbols)
+    Symbol(usize),
+
+    /// A namespace, containing the fields that the script reads from it
+    Namespace(BTreeMap<String, DependencyField>),
+
+    /// The record of the symbols that are read while the script is evaluated
+    Reads
+}
+
+///
+/// The symbols that a computing script reads from its namespace
+///
+/// Scripts read symbols by name, and read the symbols in child namespaces by field access (`layers.background.opacity`). A
+/// script that reads symbols is compiled as a function that takes their values as a record, with a nested record for each
+/// child namespace that contains just the symbols that the script reads.
+///
+#[derive(Clone)]
+pub struct ScriptDependencies {
+    /// The fields of the record passed to the script
+    fields: BTreeMap<String, DependencyField>,
+
+    /// The paths of the symbols that the script reads (relative to its namespace) and their types
+    symbols: Vec<(Vec<FloScriptSymbol>, ScriptTypeDescription)>
+}
+
+impl ScriptDependencies {
+    ///
+    /// Creates a set of dependencies for a script that doesn't read any symbols
+    ///
+    pub fn new() -> ScriptDependencies {
+        let mut fields = BTreeMap::new();
+        fields.insert(READS_FIELD.to_string(), DependencyField::Reads);
+
+        ScriptDependencies {
+            fields,
+            symbols:    vec![]
+        }
+    }
+
+    ///
+    /// True if the script doesn't read any symbols
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    ///
+    /// The paths of the symbols that the script reads and their types, in the order that their values are supplied
+    ///
+    pub fn symbols(&self) -> &[(Vec<FloScriptSymbol>, ScriptTypeDescription)] {
+        &self.symbols
+    }
+
+    ///
+    /// Adds a symbol that the script reads, given the names that the script uses for each part of its path
+    ///
+    pub fn add_symbol(&mut self, names: &[String], path: Vec<FloScriptSymbol>, symbol_type: ScriptTypeDescription) {
+        let (name, namespaces)  = match names.split_last() {
+            Some(names) => names,
+            None        => return
+        };
+
+        // Find the record containing the symbol
+        let mut fields          = &mut self.fields;
+
+        for namespace in namespaces {
+            let field = fields.entry(namespace.clone()).or_insert_with(|| DependencyField::Namespace(BTreeMap::new()));
+
+            fields = match field {
+                DependencyField::Namespace(namespace_fields)    => namespace_fields,
+                DependencyField::Symbol(_)                      |
+                DependencyField::Reads                          => return
+            };
+        }
+
+        // Symbols are only read once, however many times the script refers to them
+        if !fields.contains_key(name) {
+            fields.insert(name.clone(), DependencyField::Symbol(self.symbols.len()));
+            self.symbols.push((path, symbol_type));
+        }
+    }
+
+    ///
+    /// Generates the Gluon type of a record of symbols
+    ///
+    fn record_type(&self, fields: &BTreeMap<String, DependencyField>, vm: &Thread) -> FloScriptResult<ArcType> {
+        let fields = fields.iter()
+            .map(|(name, field)| {
+                let field_type = match field {
+                    DependencyField::Symbol(index)      => self.symbols[*index].1.vm_type(vm)?,
+                    DependencyField::Namespace(fields)  => self.record_type(fields, vm)?,
+                    DependencyField::Reads              => make_gluon_type::<SymbolReads>(vm)?
+                };
+
+                Ok(Field::new(Symbol::from(&name[..]), field_type))
+            })
+            .collect::<FloScriptResult<Vec<_>>>()?;
+
+        Ok(Type::record(vec![], fields))
+    }
+
+    ///
+    /// Finds the indexes of the symbols read by a reference from a script (all of the symbols in a namespace, if the reference is
+    /// to a whole namespace)
+    ///
+    fn reference_indexes(&self, names: &[String]) -> Vec<usize> {
+        let mut fields = &self.fields;
+
+        for name in names {
+            match fields.get(name) {
+                Some(DependencyField::Symbol(index))        => return vec![*index],
+                Some(DependencyField::Namespace(namespace)) => fields = namespace,
+                Some(DependencyField::Reads)                |
+                None                                        => return vec![]
+            }
+        }
+
+        let mut indexes = vec![];
+        field_indexes(fields, &mut indexes);
+        indexes
+    }
+
+    ///
+    /// Compiles a script that reads these symbols, returning the compiled script and the type of the values it produces
+    ///
+    /// The script is compiled as a function that takes the values of the symbols (see `DependencyValues`). Errors are reported
+    /// against the source of the script, as if it had been compiled on its own. If an output type is supplied, the script is
+    /// type-checked against it.
+    ///
+    /// Each place where the script refers to a symbol is changed so that it records that the symbol has been read when it's
+    /// evaluated, so the symbols that a script actually reads (which can depend on the branches it takes) are recorded in the
+    /// `SymbolReads` passed to it.
+    ///
+    pub fn compile(&self, compiler: &mut Compiler, vm: &Thread, file: &str, script: &str, output_type: Option<&ArcType>) -> FloScriptResult<(CompileValue<SpannedExpr<Symbol>>, ArcType)> {
+        let output_type     = output_type.cloned().unwrap_or_else(Type::hole);
+        let expected_type   = Type::function(vec![self.record_type(&self.fields, vm)?], output_type);
+
+        // Bind the symbols to their names, then evaluate the script
+        let names           = self.fields.keys().cloned().collect::<Vec<_>>().join(", ");
+        let wrapper         = format!("\\{} -> let {{ {} }} = {} in let {} = (import! flo.script.reads).read in {}", SYMBOLS_ARGUMENT, names, SYMBOLS_ARGUMENT, READ_FUNCTION, SCRIPT_PLACEHOLDER);
+        let read            = format!("{} {} 0 {}", READ_FUNCTION, READS_FIELD, VALUE_PLACEHOLDER);
+
+        let type_cache      = vm.global_env().type_cache();
+        let mut function    = compiler.parse_expr(type_cache, "flo.script.symbols", &wrapper).map_err(|error| script_error(compiler, error.into()))?;
+        let read_expr       = compiler.parse_expr(type_cache, "flo.script.read", &read).map_err(|error| script_error(compiler, error.into()))?;
+        let mut script_expr = compiler.parse_expr(type_cache, file, script).map_err(|error| script_error(compiler, error.into()))?;
+
+        // Record the reads made by each reference to a symbol
+        let script_start    = compiler.code_map().find_file(script_expr.span.start()).map(|file| file.span().start()).unwrap_or(BytePos::from(1));
+        replace_references(&mut script_expr, script, script_start, |reference, expr| {
+            for index in self.reference_indexes(&reference.names) {
+                let value   = std::mem::replace(expr, pos::spanned(expr.span, Expr::Error(None)));
+                *expr       = read_symbol_expr(&read_expr, index, value);
+            }
+        });
+
+        replace_placeholder(&mut function, script_expr);
+
+        let compiled        = function.compile(compiler, vm, file, script, Some(&expected_type)).map_err(|error| script_error(compiler, error))?;
+        let output_type     = compiled.typ.as_function().map(|(_, output_type)| output_type.clone()).unwrap_or_else(|| compiled.typ.clone());
+
+        Ok((compiled, output_type))
+    }
+}
+
+///
+/// Converts a Gluon error into a script error
+///
+pub fn script_error(compiler: &Compiler, error: gluon::Error) -> FloScriptError {
+    let error_string = error.emit_string(&compiler.code_map())
+        .unwrap_or("<Error while compiling (could not convert to string)>".to_string());
+
+    FloScriptError::ScriptError(error_string)
+}
+
+///
+/// Adds the indexes of all of the symbols in a record of symbols to a list
+///
+fn field_indexes(fields: &BTreeMap<String, DependencyField>, indexes: &mut Vec<usize>) {
+    for field in fields.values() {
+        match field {
+            DependencyField::Symbol(index)      => indexes.push(*index),
+            DependencyField::Namespace(fields)  => field_indexes(fields, indexes),
+            DependencyField::Reads              => { }
+        }
+    }
+}
+
+///
+/// Generates an expression that records that the symbol with the specified index is read, then evaluates to its value
+///
+fn read_symbol_expr(read_expr: &SpannedExpr<Symbol>, index: usize, value: SpannedExpr<Symbol>) -> SpannedExpr<Symbol> {
+    let span        = value.span;
+    let mut read    = read_expr.clone();
+    read.span       = span;
+
+    if let Expr::App { args, .. } = &mut read.value {
+        args[1] = pos::spanned(span, Expr::Literal(Literal::Int(index as i64)));
+        args[2] = value;
+    }
+
+    read
+}
+
+///
+/// Replaces the placeholder in the function generated around a script with the script itself
+///
+fn replace_placeholder(expr: &mut SpannedExpr<Symbol>, script: SpannedExpr<Symbol>) {
+    match &mut expr.value {
+        Expr::Lambda(lambda)            => replace_placeholder(&mut lambda.body, script),
+        Expr::LetBindings(_, body)      => replace_placeholder(body, script),
+        _                               => *expr = script
+    }
+}
+
+///
+/// The values of the symbols read by a script, which are passed to it as a record
+///
+pub struct DependencyValues {
+    /// The symbols that the values are for
+    dependencies: Arc<ScriptDependencies>,
+
+    /// The value of each symbol, in the same order as the symbols in the dependencies
+    values: Vec<Box<dyn Any+Send>>,
+
+    /// Records the symbols that the script reads
+    reads: SymbolReads
+}
+
+impl DependencyValues {
+    ///
+    /// Creates a copy of the values of the symbols read by a script
+    ///
+    pub fn new(dependencies: &Arc<ScriptDependencies>, values: &[&(dyn Any+Send)]) -> DependencyValues {
+        let values = dependencies.symbols.iter()
+            .zip(values.iter())
+            .map(|((_, value_type), value)| value_type.clone_any(*value))
+            .collect();
+
+        DependencyValues {
+            dependencies:   Arc::clone(dependencies),
+            values,
+            reads:          SymbolReads::new()
+        }
+    }
+
+    ///
+    /// Returns the record of the symbols that the script reads when it's evaluated with these values
+    ///
+    pub fn reads(&self) -> SymbolReads {
+        self.reads.clone()
+    }
+
+    ///
+    /// Pushes a record containing the specified fields
+    ///
+    fn push_record<'vm>(&self, fields: &BTreeMap<String, DependencyField>, context: &mut ActiveThread<'vm>) -> vm::Result<()> {
+        // Push the field values
+        for field in fields.values() {
+            match field {
+                DependencyField::Symbol(index)      => self.dependencies.symbols[*index].1.push_any(&*self.values[*index], context)?,
+                DependencyField::Namespace(fields)  => self.push_record(fields, context)?,
+                DependencyField::Reads              => self.reads.clone().push(context)?
+            }
+        }
+
+        // Turn into a record
+        let vm          = context.thread();
+        let field_names = fields.keys().map(|name| vm.global_env().intern(name)).collect::<vm::Result<Vec<_>>>()?;
+        context.context().push_new_record(vm, field_names.len(), &field_names)?;
+
+        Ok(())
+    }
+}
+
+impl<'vm> Pushable<'vm> for DependencyValues {
+    fn push(self, context: &mut ActiveThread<'vm>) -> vm::Result<()> {
+        self.push_record(&self.dependencies.fields, context)
+    }
+}
+
+///
+/// Records the symbols that a script reads while it's being evaluated
+///
+#[derive(Userdata, VmType, Trace, Clone, Debug)]
+#[gluon_trace(skip)]
+#[gluon(vm_type = "flo.script.reads.SymbolReads")]
+pub struct SymbolReads {
+    /// The indexes of the symbols that have been read (into `ScriptDependencies::symbols()`)
+    read: Arc<Mutex<BTreeSet<usize>>>
+}
+
+impl SymbolReads {
+    ///
+    /// Creates a record of symbol reads where no symbols have been read yet
+    ///
+    pub fn new() -> SymbolReads {
+        SymbolReads {
+            read: Arc::new(Mutex::new(BTreeSet::new()))
+        }
+    }
+
+    ///
+    /// The paths of the symbols that have been read, in the order that they appear in the dependencies
+    ///
+    pub fn symbols(&self, dependencies: &ScriptDependencies) -> Vec<Vec<FloScriptSymbol>> {
+        self.read.lock().unwrap().iter()
+            .filter_map(|index| dependencies.symbols.get(*index))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+///
+/// Records that a script has read the symbol with the specified index, and returns its value
+///
+fn read_symbol(reads: &SymbolReads, index: VmInt, value: Generic<A>) -> Generic<A> {
+    reads.read.lock().unwrap().insert(index as usize);
+
+    value
+}
+
+///
+/// Generates the flo.script.reads extern module for a Gluon VM
+///
+fn load_symbol_reads(vm: &Thread) -> vm::Result<ExternModule> {
+    vm.register_type::<SymbolReads>("flo.script.reads.SymbolReads", &[])?;
+
+    ExternModule::new(vm, record! {
+        type SymbolReads    => SymbolReads,
+        read                => primitive!(3, read_symbol)
+    })
+}
+
+///
+/// Adds the flo.script.reads module, used by scripts that read symbols, to a Gluon VM
+///
+pub fn load_flo_script_reads(vm: &Thread) {
+    import::add_extern_module(vm, "flo.script.reads", load_symbol_reads);
+}
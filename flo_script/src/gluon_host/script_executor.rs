@@ -0,0 +1,122 @@
+use super::super::error::*;
+
+use futures::*;
+use futures::future;
+use futures::future::Executor;
+use futures::sync::oneshot;
+
+use std::sync::*;
+
+/// A future that evaluates a script on a script executor
+pub type ScriptFuture = Box<dyn Future<Item=(), Error=()>+Send>;
+
+///
+/// Runs the futures that evaluate scripts
+///
+/// By default, scripts are evaluated on whatever thread polls their output stream. A host created with an executor runs them
+/// there instead, so expensive scripts don't hold up the thread that's reading their results. Anything that implements the
+/// futures `Executor` trait for `ScriptFuture` (such as a `CpuPool`) can be used as a script executor.
+///
+pub trait ScriptExecutor : Send+Sync {
+    ///
+    /// Starts running a future (which should be polled until it completes)
+    ///
+    fn spawn(&self, future: ScriptFuture);
+}
+
+impl<Exec> ScriptExecutor for Exec
+where Exec: Executor<ScriptFuture>+Send+Sync {
+    fn spawn(&self, future: ScriptFuture) {
+        // If the executor refuses the future, it's dropped and the reader is told that the evaluation stopped
+        self.execute(future).ok();
+    }
+}
+
+///
+/// Evaluates a script on an executor, returning a future that produces the result once it has finished
+///
+/// The evaluation stops if the returned future is dropped before it completes.
+///
+pub (crate) fn evaluate_on<Item: 'static+Send>(executor: &Arc<dyn ScriptExecutor>, evaluation: Box<dyn Future<Item=Item, Error=FloScriptError>+Send>) -> Box<dyn Future<Item=Item, Error=FloScriptError>+Send> {
+    let (sender, receiver)  = oneshot::channel();
+    let mut evaluation      = evaluation;
+    let mut sender          = Some(sender);
+
+    executor.spawn(Box::new(future::poll_fn(move || {
+        // Give up if nothing is waiting for the result any more
+        if let Some(Ok(Async::Ready(()))) = sender.as_mut().map(|sender| sender.poll_cancel()) {
+            return Ok(Async::Ready(()));
+        }
+
+        let result = match evaluation.poll() {
+            Ok(Async::NotReady)     => return Ok(Async::NotReady),
+            Ok(Async::Ready(item))  => Ok(item),
+            Err(error)              => Err(error)
+        };
+
+        if let Some(sender) = sender.take() {
+            sender.send(result).ok();
+        }
+
+        Ok(Async::Ready(()))
+    })));
+
+    Box::new(receiver.then(|result| match result {
+        Ok(result)  => result,
+        Err(_)      => Err(FloScriptError::Unavailable("The script executor stopped before the script finished".to_string()))
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use futures::executor;
+
+    use std::thread;
+
+    struct ThreadExecutor;
+
+    impl Executor<ScriptFuture> for ThreadExecutor {
+        fn execute(&self, future: ScriptFuture) -> Result<(), future::ExecuteError<ScriptFuture>> {
+            thread::spawn(move || { executor::spawn(future).wait_future().ok(); });
+            Ok(())
+        }
+    }
+
+    struct RefusingExecutor;
+
+    impl Executor<ScriptFuture> for RefusingExecutor {
+        fn execute(&self, future: ScriptFuture) -> Result<(), future::ExecuteError<ScriptFuture>> {
+            Err(future::ExecuteError::new(future::ExecuteErrorKind::Shutdown, future))
+        }
+    }
+
+    #[test]
+    fn evaluates_on_executor_thread() {
+        let executor: Arc<dyn ScriptExecutor>   = Arc::new(ThreadExecutor);
+        let reader_thread                       = thread::current().id();
+        let evaluation                          = future::lazy(move || Ok(thread::current().id() != reader_thread));
+
+        let result = executor::spawn(evaluate_on(&executor, Box::new(evaluation))).wait_future();
+        assert!(result == Ok(true));
+    }
+
+    #[test]
+    fn evaluation_errors_are_returned() {
+        let executor: Arc<dyn ScriptExecutor>   = Arc::new(ThreadExecutor);
+        let evaluation                          = future::err::<i32, _>(FloScriptError::ScriptError("Failed".to_string()));
+
+        let result = executor::spawn(evaluate_on(&executor, Box::new(evaluation))).wait_future();
+        assert!(result == Err(FloScriptError::ScriptError("Failed".to_string())));
+    }
+
+    #[test]
+    fn refused_evaluation_is_unavailable() {
+        let executor: Arc<dyn ScriptExecutor>   = Arc::new(RefusingExecutor);
+        let evaluation                          = future::ok::<_, FloScriptError>(42);
+
+        let result = executor::spawn(evaluate_on(&executor, Box::new(evaluation))).wait_future();
+        assert!(matches!(result, Err(FloScriptError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,433 @@
+use gluon::base::ast::{Expr, Pattern, SpannedExpr, SpannedPattern, TypedIdent};
+use gluon::base::pos::{self, BytePos, Span};
+use gluon::base::symbol::{Symbol, Symbols};
+use gluon::base::types::{ArcType, TypeCache};
+use gluon::parser::parse_partial_expr;
+
+use std::ops::Range;
+
+///
+/// Changes the references to a symbol in a Gluon script so that they use a new path
+///
+/// Paths are the names that the script uses to reach the symbol, so `["layers", "opacity"]` is written `layers.opacity`.
+/// Only references to values that the script doesn't define itself are changed: local variables, record fields, field
+/// accesses on other values, strings and comments are all left alone.
+///
+pub fn rename_reference<OldName: AsRef<str>, NewName: AsRef<str>>(script: &str, old_path: &[OldName], new_path: &[NewName]) -> String {
+    if old_path.is_empty() || new_path.is_empty() {
+        return script.to_string();
+    }
+
+    // Only the names after the part of the path that's the same are replaced
+    let unchanged       = old_path.iter().zip(new_path.iter())
+        .take_while(|(old_name, new_name)| old_name.as_ref() == new_name.as_ref())
+        .count()
+        .min(old_path.len()-1)
+        .min(new_path.len()-1);
+    let new_names       = new_path[unchanged..].iter().map(|name| name.as_ref()).collect::<Vec<_>>().join(".");
+
+    // Find the names to replace
+    let mut replacements = script_references(script).into_iter()
+        .filter(|reference| reference.names.len() >= old_path.len() && reference.names.iter().zip(old_path.iter()).all(|(name, old_name)| name == old_name.as_ref()))
+        .map(|reference| {
+            let span = reference.spans[unchanged].start..reference.spans[old_path.len()-1].end;
+
+            if reference.punned {
+                // `{ x }` is short for `{ x = x }`, and the field keeps its name
+                (span, format!("{} = {}", reference.names[0], new_names))
+            } else {
+                (span, new_names.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+    replacements.sort_by_key(|(span, _)| span.start);
+
+    // Generate the new script
+    let mut result  = String::with_capacity(script.len());
+    let mut pos     = 0;
+
+    for (span, replacement) in replacements {
+        result.push_str(&script[pos..span.start]);
+        result.push_str(&replacement);
+        pos = span.end;
+    }
+
+    result.push_str(&script[pos..]);
+    result
+}
+
+///
+/// A reference from a script to a value that the script doesn't define itself (an identifier that isn't bound by a `let`,
+/// lambda, pattern or `do`), along with any field names that follow it
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScriptReference {
+    /// The identifier followed by the field names, so `layers.background.opacity` is `["layers", "background", "opacity"]`
+    pub names: Vec<String>,
+
+    /// Where each of the names appears in the script (as byte ranges)
+    pub spans: Vec<Range<usize>>,
+
+    /// True if the reference is a record field written without a value (`{ x }`), which names both the field and the value
+    pub punned: bool
+}
+
+///
+/// Finds the references in a Gluon script to values that it doesn't define itself
+///
+/// References are found from the parsed script, so local variables, record field names, field accesses on local values,
+/// strings, characters and comments are never references. Constructors and the arguments to macros such as `import!` are
+/// also skipped. Scripts with syntax errors are analysed as far as they can be parsed.
+///
+pub fn script_references(script: &str) -> Vec<ScriptReference> {
+    let mut symbols     = Symbols::new();
+    let type_cache      = TypeCache::<Symbol, ArcType>::default();
+
+    let expr            = match parse_partial_expr(&mut symbols, &type_cache, script) {
+        Ok(expr)                    => expr,
+        Err((Some(partial), _))     => partial,
+        Err((None, _))              => return vec![]
+    };
+
+    let mut expr        = expr;
+    let mut references  = vec![];
+    replace_references(&mut expr, script, BytePos::from(1), |reference, _| references.push(reference.clone()));
+
+    references
+}
+
+///
+/// Finds the references in a parsed script to values that it doesn't define itself, and passes them to a function that can
+/// replace the expressions that make them
+///
+/// The script must have been parsed from `script`, starting at the position `start` (scripts parsed on their own start at 1).
+/// References written as record fields without a value (`{ x }`) are given a value before they're passed to the function, so
+/// the value can be replaced without renaming the field.
+///
+pub fn replace_references<ReplaceFn: FnMut(&ScriptReference, &mut SpannedExpr<Symbol>)>(expr: &mut SpannedExpr<Symbol>, script: &str, start: BytePos, replace: ReplaceFn) {
+    let mut finder = ReferenceFinder { script, start, bound: vec![], replace };
+    finder.expr(expr);
+}
+
+///
+/// Walks a parsed script, tracking the names that are bound at each point so the free references can be found
+///
+struct ReferenceFinder<'a, ReplaceFn> {
+    /// The source of the script
+    script: &'a str,
+
+    /// The position of the start of the script in the spans from the parser
+    start: BytePos,
+
+    /// The names bound by the script at the current point (innermost last)
+    bound: Vec<String>,
+
+    /// The function that's called with each reference that's found
+    replace: ReplaceFn
+}
+
+impl<'a, ReplaceFn: FnMut(&ScriptReference, &mut SpannedExpr<Symbol>)> ReferenceFinder<'a, ReplaceFn> {
+    ///
+    /// Converts a span from the parser into a byte range in the script
+    ///
+    fn range(&self, span: Span<BytePos>) -> Range<usize> {
+        (span.start().to_usize() - self.start.to_usize())..(span.end().to_usize() - self.start.to_usize())
+    }
+
+    ///
+    /// True if an identifier could refer to something outside of the script
+    ///
+    fn is_free(&self, name: &str) -> bool {
+        // Constructors start with an uppercase letter, and macros end with a '!'
+        !name.starts_with(|c: char| c.is_uppercase())
+            && !name.ends_with('!')
+            && !self.bound.iter().any(|bound| bound == name)
+    }
+
+    ///
+    /// Walks an expression with some extra names bound
+    ///
+    fn expr_with_bound(&mut self, names: Vec<String>, expr: &mut SpannedExpr<Symbol>) {
+        let num_bound = self.bound.len();
+
+        self.bound.extend(names);
+        self.expr(expr);
+        self.bound.truncate(num_bound);
+    }
+
+    ///
+    /// Finds the reference made by an identifier followed by any number of field accesses
+    ///
+    fn projection_reference(&self, expr: &SpannedExpr<Symbol>) -> Option<ScriptReference> {
+        match &expr.value {
+            Expr::Ident(ident) if self.is_free(ident.name.as_ref()) => {
+                Some(ScriptReference { names: vec![ident.name.as_ref().to_string()], spans: vec![self.range(expr.span)], punned: false })
+            }
+
+            Expr::Projection(value, field, _)               => {
+                let mut reference   = self.projection_reference(value)?;
+
+                // The field name is always at the end of the projection
+                let end             = self.range(expr.span).end;
+                let field_span      = end.checked_sub(field.as_ref().len())?..end;
+
+                if self.script.get(field_span.clone()) != Some(field.as_ref()) { return None; }
+
+                reference.names.push(field.as_ref().to_string());
+                reference.spans.push(field_span);
+                Some(reference)
+            }
+
+            _                                               => None
+        }
+    }
+
+    ///
+    /// Finds the references in an expression
+    ///
+    fn expr(&mut self, expr: &mut SpannedExpr<Symbol>) {
+        if let Some(reference) = self.projection_reference(expr) {
+            (self.replace)(&reference, expr);
+            return;
+        }
+
+        match &mut expr.value {
+            Expr::Ident(_)                                  => { }
+            Expr::Projection(value, _, _)                   => self.expr(value),
+
+            Expr::App { func, implicit_args, args }         => {
+                // The arguments to macros (such as the module name in `import! std.map`) aren't values
+                let is_macro = if let Expr::Ident(ident) = &func.value { ident.name.as_ref().ends_with('!') } else { false };
+
+                if !is_macro {
+                    self.expr(func);
+                    implicit_args.iter_mut().chain(args.iter_mut()).for_each(|arg| self.expr(arg));
+                }
+            }
+
+            Expr::Lambda(lambda)                            => {
+                let args = lambda.args.iter().map(|arg| arg.name.value.name.as_ref().to_string()).collect();
+                self.expr_with_bound(args, &mut lambda.body);
+            }
+
+            Expr::IfElse(condition, if_true, if_false)      => {
+                self.expr(condition);
+                self.expr(if_true);
+                self.expr(if_false);
+            }
+
+            Expr::Match(value, alternatives)                => {
+                self.expr(value);
+
+                for alternative in alternatives.iter_mut() {
+                    let mut names = vec![];
+                    pattern_names(&alternative.pattern, &mut names);
+                    self.expr_with_bound(names, &mut alternative.expr);
+                }
+            }
+
+            Expr::Infix { lhs, rhs, .. }                    => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+
+            Expr::Array(array)                              => array.exprs.iter_mut().for_each(|expr| self.expr(expr)),
+            Expr::Tuple { elems, .. }                       => elems.iter_mut().for_each(|expr| self.expr(expr)),
+            Expr::Block(exprs)                              => exprs.iter_mut().for_each(|expr| self.expr(expr)),
+
+            Expr::Record { exprs, base, .. }                => {
+                for field in exprs.iter_mut() {
+                    if field.value.is_none() && self.is_free(field.name.value.as_ref()) {
+                        // `{ x }` is short for `{ x = x }`
+                        let reference   = ScriptReference { names: vec![field.name.value.as_ref().to_string()], spans: vec![self.range(field.name.span)], punned: true };
+                        let mut value   = pos::spanned(field.name.span, Expr::Ident(TypedIdent::new(field.name.value.clone())));
+
+                        (self.replace)(&reference, &mut value);
+                        field.value     = Some(value);
+                    } else if let Some(value) = &mut field.value {
+                        self.expr(value);
+                    }
+                }
+
+                if let Some(base) = base { self.expr(base); }
+            }
+
+            Expr::LetBindings(bindings, body)               => {
+                let mut names = vec![];
+                bindings.iter().for_each(|binding| pattern_names(&binding.name, &mut names));
+
+                // Recursive bindings can refer to each other
+                let binding_names = if bindings.is_recursive() { names.clone() } else { vec![] };
+
+                for binding in bindings.iter_mut() {
+                    let args = binding.args.iter().map(|arg| arg.name.value.name.as_ref().to_string()).collect::<Vec<_>>();
+                    self.expr_with_bound(binding_names.iter().cloned().chain(args).collect(), &mut binding.expr);
+                }
+
+                self.expr_with_bound(names, body);
+            }
+
+            Expr::Do(do_expr)                               => {
+                let mut names = vec![];
+                if let Some(pattern) = &do_expr.id { pattern_names(pattern, &mut names); }
+
+                self.expr(&mut do_expr.bound);
+                self.expr_with_bound(names, &mut do_expr.body);
+            }
+
+            Expr::TypeBindings(_, body)                     => self.expr(body),
+            Expr::MacroExpansion { original, .. }           => self.expr(original),
+            Expr::Annotated(value, _)                       => self.expr(value),
+
+            Expr::Literal(_)                                |
+            Expr::Error(_)                                  => { }
+        }
+    }
+}
+
+///
+/// Adds the names bound by a pattern to a list
+///
+fn pattern_names(pattern: &SpannedPattern<Symbol>, names: &mut Vec<String>) {
+    match &pattern.value {
+        Pattern::Ident(ident)                           => names.push(ident.name.as_ref().to_string()),
+        Pattern::As(name, pattern)                      => { names.push(name.value.as_ref().to_string()); pattern_names(pattern, names); }
+        Pattern::Constructor(_, patterns)               => patterns.iter().for_each(|pattern| pattern_names(pattern, names)),
+        Pattern::Tuple { elems, .. }                    => elems.iter().for_each(|pattern| pattern_names(pattern, names)),
+
+        Pattern::Record { fields, implicit_import, .. } => {
+            for field in fields.iter() {
+                match &field.value {
+                    Some(pattern)   => pattern_names(pattern, names),
+                    None            => names.push(field.name.value.as_ref().to_string())
+                }
+            }
+
+            if let Some(implicit_import) = implicit_import { names.push(implicit_import.value.as_ref().to_string()); }
+        }
+
+        Pattern::Literal(_)                             |
+        Pattern::Error                                  => { }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rename_simple_identifier() {
+        assert!(rename_reference("x + 1", &["x"], &["y"]) == "y + 1");
+    }
+
+    #[test]
+    fn rename_into_namespace() {
+        assert!(rename_reference("x + 1", &["x"], &["ns", "x"]) == "ns.x + 1");
+        assert!(rename_reference("ns.x + 1", &["ns", "x"], &["x"]) == "x + 1");
+        assert!(rename_reference("{ x }", &["x"], &["ns", "x"]) == "{ x = ns.x }");
+    }
+
+    #[test]
+    fn do_not_rename_field_access() {
+        assert!(rename_reference("do x = state.x\nx + 1", &["x"], &["y"]) == "do x = state.x\nx + 1");
+    }
+
+    #[test]
+    fn do_not_rename_record_fields() {
+        assert!(rename_reference("let p = { x = 1, y = 2 } in p.x", &["x"], &["z"]) == "let p = { x = 1, y = 2 } in p.x");
+        assert!(rename_reference("{ x = x }", &["x"], &["z"]) == "{ x = z }");
+    }
+
+    #[test]
+    fn rename_punned_record_field() {
+        assert!(rename_reference("{ x, y = 1 }", &["x"], &["z"]) == "{ x = z, y = 1 }");
+    }
+
+    #[test]
+    fn do_not_rename_locals() {
+        assert!(rename_reference("let x = 1 in x", &["x"], &["y"]) == "let x = 1 in x");
+        assert!(rename_reference("(\\x -> x) x", &["x"], &["y"]) == "(\\x -> x) y");
+    }
+
+    #[test]
+    fn do_not_rename_partial_identifiers() {
+        assert!(rename_reference("xx + x_1 + ax", &["x"], &["y"]) == "xx + x_1 + ax");
+    }
+
+    #[test]
+    fn do_not_rename_in_strings_comments_or_characters() {
+        assert!(rename_reference("// x\n/* x */ \"x \\\" x\" ++ show '\"' ++ x", &["x"], &["y"]) == "// x\n/* x */ \"x \\\" x\" ++ show '\"' ++ y");
+    }
+
+    #[test]
+    fn rename_dotted_path() {
+        assert!(rename_reference("layers.opacity + opacity", &["layers", "opacity"], &["layers", "alpha"]) == "layers.alpha + opacity");
+        assert!(rename_reference("layers.background.opacity", &["layers", "background"], &["layers", "back"]) == "layers.back.opacity");
+    }
+
+    fn reference_names(script: &str) -> Vec<Vec<String>> {
+        script_references(script).into_iter().map(|reference| reference.names).collect()
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn find_simple_references() {
+        assert!(reference_names("x + y") == vec![names(&["x"]), names(&["y"])]);
+    }
+
+    #[test]
+    fn find_dotted_references() {
+        assert!(reference_names("layers.background.opacity * 2") == vec![names(&["layers", "background", "opacity"])]);
+    }
+
+    #[test]
+    fn reference_spans() {
+        let references = script_references("1 + layers.opacity");
+
+        assert!(references[0].spans == vec![4..10, 11..18]);
+    }
+
+    #[test]
+    fn skip_strings_comments_and_numbers() {
+        assert!(reference_names("// x\n/* y */ \"y\" ++ show 1.5 ++ show 'x' ++ z") == vec![names(&["show"]), names(&["show"]), names(&["z"])]);
+    }
+
+    #[test]
+    fn skip_shadowed_locals() {
+        assert!(reference_names("let y = 1 in y").is_empty());
+        assert!(reference_names("let y = x in y + z") == vec![names(&["x"]), names(&["z"])]);
+        assert!(reference_names("let f a = a + f b in f 1") == vec![names(&["b"])]);
+        assert!(reference_names("(\\x -> x + y) 1") == vec![names(&["y"])]);
+        assert!(reference_names("match v with\n| Some x -> x\n| None -> d") == vec![names(&["v"]), names(&["d"])]);
+        assert!(reference_names("do x = state.x\nx + 1") == vec![names(&["state", "x"])]);
+    }
+
+    #[test]
+    fn local_is_only_bound_inside_its_scope() {
+        assert!(reference_names("let a = (let x = 1 in x) in a + x") == vec![names(&["x"])]);
+    }
+
+    #[test]
+    fn skip_record_fields() {
+        assert!(reference_names("let p = { x = 1, y = 2 } in p.x") .is_empty());
+        assert!(reference_names("{ x = y }") == vec![names(&["y"])]);
+        assert!(reference_names("let { x } = r in x") == vec![names(&["r"])]);
+    }
+
+    #[test]
+    fn punned_field_is_reference() {
+        let references = script_references("{ x }");
+
+        assert!(references.len() == 1);
+        assert!(references[0].names == names(&["x"]));
+        assert!(references[0].punned);
+    }
+
+    #[test]
+    fn skip_constructors_and_imports() {
+        assert!(reference_names("let m = import! std.map in Some x") == vec![names(&["x"])]);
+    }
+}
@@ -0,0 +1,53 @@
+use super::super::error::*;
+
+use gluon::base::types::{Alias, ArcType};
+use gluon::base::symbol::{Symbol};
+use gluon::vm::api::{VmType};
+use gluon::vm::thread::{Thread};
+
+use std::any::*;
+
+/// Function that declares a type in a VM
+pub (crate) type DeclareTypeFn = fn(&Thread) -> FloScriptResult<ArcType>;
+
+///
+/// A Rust type with a Gluon record or variant definition, which is declared by name in the VMs that it is used with
+///
+/// This is usually implemented by `#[derive(ScriptType)]`, which also implements the Gluon traits needed for the type to
+/// be used as the type of an input or output symbol.
+///
+pub trait DeclaredScriptType : 'static+VmType
+where <Self as VmType>::Type: Sized {
+    ///
+    /// The name of the type in Gluon
+    ///
+    fn gluon_name() -> &'static str;
+
+    ///
+    /// Creates the Gluon record or variant type that corresponds to this type
+    ///
+    fn gluon_definition(vm: &Thread) -> ArcType;
+}
+
+///
+/// Declares a type by name in a VM, returning its definition
+///
+/// This is called by the `VmType` implementation generated by `#[derive(ScriptType)]`, so types are declared in a VM the first
+/// time they're used with it. `GluonScriptHost::declare_type()` declares a type in the VMs of the host's namespaces as they're
+/// created, for scripts that refer to a type by name before it's used as the type of a symbol.
+///
+/// This returns `FloScriptError::UnsupportedType` if another type has already been declared with the same name in the VM.
+///
+pub fn declare_script_type<T: DeclaredScriptType>(vm: &Thread) -> FloScriptResult<ArcType>
+where <T as VmType>::Type: Sized {
+    let definition = T::gluon_definition(vm);
+
+    if vm.get_type::<T>().is_none() {
+        // Scripts can refer to the type by name once it's registered
+        let name = Symbol::from(T::gluon_name());
+        vm.register_type_as(name.clone(), Alias::new(name, vec![], definition.clone()), TypeId::of::<T>())
+            .map_err(|error| FloScriptError::UnsupportedType(format!("`{}` cannot be declared: {}", T::gluon_name(), error)))?;
+    }
+
+    Ok(definition)
+}
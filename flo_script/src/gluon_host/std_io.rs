@@ -0,0 +1,291 @@
+// The Gluon derive macros implement their traits inside a constant, which newer versions of Rust warn about
+#![allow(non_local_definitions)]
+
+use gluon::{RootedThread, new_vm};
+use gluon::import;
+use gluon::vm;
+use gluon::vm::{ExternModule};
+use gluon::vm::api::{IO, RuntimeResult, TypedBytecode, OpaqueValue};
+use gluon::vm::api::generic::{A, B};
+use gluon::vm::thread::Thread;
+use gluon::vm::types::Instruction::*;
+
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Write};
+use std::sync::*;
+
+///
+/// Creates a new Gluon VM that can compile scripts
+///
+/// The `std.io.prim` module that comes with Gluon declares its types using a macro that no longer produces valid type names
+/// with current versions of Rust, which makes every script fail to compile (the prelude imports it). VMs created by this
+/// function replace that module with an equivalent one whose type names are spelled out.
+///
+pub fn new_script_vm() -> RootedThread {
+    let vm = new_vm();
+    import::add_extern_module(&vm, "std.io.prim", load_std_io);
+
+    vm
+}
+
+///
+/// The fields of the std.io.prim module
+///
+/// These are declared by hand rather than via `record!` so that the names of the types are exactly as Gluon expects them.
+///
+#[allow(non_camel_case_types)]
+mod _field {
+    use gluon::vm::api::record::Field;
+
+    macro_rules! io_field {
+        ($field: ident, $name: expr) => { io_field!($field, $name, &[]); };
+        ($field: ident, $name: expr, $args: expr) => {
+            #[derive(Default)]
+            pub struct $field;
+
+            impl Field for $field {
+                fn name() -> &'static str                   { $name }
+                fn args() -> &'static [&'static str]        { $args }
+            }
+        };
+    }
+
+    io_field!(File, "std::io::File");
+    io_field!(OpenOptions, "OpenOptions");
+    io_field!(IO, "std::io::IO", &["a"]);
+
+    io_field!(flat_map, "flat_map");
+    io_field!(wrap, "wrap");
+    io_field!(open_file_with, "open_file_with");
+    io_field!(read_file_to_string, "read_file_to_string");
+    io_field!(read_file_to_array, "read_file_to_array");
+    io_field!(read_file, "read_file");
+    io_field!(read_file_to_end, "read_file_to_end");
+    io_field!(write_slice_file, "write_slice_file");
+    io_field!(flush_file, "flush_file");
+    io_field!(close_file, "close_file");
+    io_field!(is_file_closed, "is_file_closed");
+    io_field!(read_char, "read_char");
+    io_field!(read_line, "read_line");
+    io_field!(print, "print");
+    io_field!(println, "println");
+    io_field!(flush_stdout, "flush_stdout");
+    io_field!(eprint, "eprint");
+    io_field!(eprintln, "eprintln");
+    io_field!(throw, "throw");
+    io_field!(default_buf_len, "default_buf_len");
+}
+
+///
+/// A file opened by a script
+///
+#[derive(Userdata, Trace, VmType)]
+#[gluon(vm_type = "std.io.File")]
+#[gluon_trace(skip)]
+struct ScriptFile(Mutex<Option<File>>);
+
+impl fmt::Debug for ScriptFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "File")
+    }
+}
+
+///
+/// The ways a file can be opened
+///
+#[derive(Getable, VmType, Clone, Copy)]
+enum OpenOptions {
+    Read,
+    Write,
+    Append,
+    Truncate,
+    Create,
+    CreateNew
+}
+
+/// Retrieves the file from a ScriptFile, or panics the script if the file has been closed
+macro_rules! unwrap_file {
+    ($file: expr) => {{
+        match *$file {
+            Some(ref mut file)  => file,
+            None                => return IO::Value(RuntimeResult::Panic("the file has been closed".to_string()))
+        }
+    }};
+}
+
+fn print(s: &str) -> IO<()> {
+    print!("{}", s);
+    IO::Value(())
+}
+
+fn println(s: &str) -> IO<()> {
+    println!("{}", s);
+    IO::Value(())
+}
+
+fn eprint(s: &str) -> IO<()> {
+    eprint!("{}", s);
+    IO::Value(())
+}
+
+fn eprintln(s: &str) -> IO<()> {
+    eprintln!("{}", s);
+    IO::Value(())
+}
+
+fn flush_stdout() -> IO<()> {
+    io::stdout().flush().into()
+}
+
+fn open_file_with(path: &str, options: Vec<OpenOptions>) -> IO<ScriptFile> {
+    let mut open_with = fs::OpenOptions::new();
+
+    for option in options {
+        match option {
+            OpenOptions::Read       => open_with.read(true),
+            OpenOptions::Write      => open_with.write(true),
+            OpenOptions::Append     => open_with.append(true),
+            OpenOptions::Truncate   => open_with.truncate(true),
+            OpenOptions::Create     => open_with.create(true),
+            OpenOptions::CreateNew  => open_with.create_new(true)
+        };
+    }
+
+    open_with.open(path)
+        .map(|file| ScriptFile(Mutex::new(Some(file))))
+        .into()
+}
+
+fn read_file_to_array(path: &str) -> IO<Vec<u8>> {
+    let mut buffer = vec![];
+    File::open(path).and_then(|mut file| file.read_to_end(&mut buffer)).map(move |_| buffer).into()
+}
+
+fn read_file_to_string(path: &str) -> IO<String> {
+    let mut buffer = String::new();
+    File::open(path).and_then(|mut file| file.read_to_string(&mut buffer)).map(move |_| buffer).into()
+}
+
+fn read_file(file: &ScriptFile, count: usize) -> IO<RuntimeResult<Option<Vec<u8>>, String>> {
+    let mut file    = file.0.lock().unwrap();
+    let file        = unwrap_file!(file);
+    let mut buffer  = vec![0; count];
+
+    match file.read(&mut buffer) {
+        Ok(0)           => IO::Value(RuntimeResult::Return(None)),
+        Ok(bytes_read)  => { buffer.truncate(bytes_read); IO::Value(RuntimeResult::Return(Some(buffer))) }
+        Err(err)        => IO::Exception(err.to_string())
+    }
+}
+
+fn read_file_to_end(file: &ScriptFile) -> IO<RuntimeResult<Vec<u8>, String>> {
+    let mut file    = file.0.lock().unwrap();
+    let file        = unwrap_file!(file);
+    let mut buffer  = vec![];
+
+    match file.read_to_end(&mut buffer) {
+        Ok(_)       => IO::Value(RuntimeResult::Return(buffer)),
+        Err(err)    => IO::Exception(err.to_string())
+    }
+}
+
+fn write_slice_file(file: &ScriptFile, buf: &[u8], start: usize, end: usize) -> IO<RuntimeResult<usize, String>> {
+    if start > end {
+        return IO::Value(RuntimeResult::Panic(format!("slice index starts at {} but ends at {}", start, end)));
+    }
+
+    if end > buf.len() {
+        return IO::Value(RuntimeResult::Panic(format!("index {} is out of range for array of length {}", end, buf.len())));
+    }
+
+    let mut file    = file.0.lock().unwrap();
+    let file        = unwrap_file!(file);
+
+    match file.write(&buf[start..end]) {
+        Ok(bytes_written)   => IO::Value(RuntimeResult::Return(bytes_written)),
+        Err(err)            => IO::Exception(err.to_string())
+    }
+}
+
+fn flush_file(file: &ScriptFile) -> IO<RuntimeResult<(), String>> {
+    let mut file = file.0.lock().unwrap();
+
+    match unwrap_file!(file).flush() {
+        Ok(())      => IO::Value(RuntimeResult::Return(())),
+        Err(err)    => IO::Exception(err.to_string())
+    }
+}
+
+fn close_file(file: &ScriptFile) -> IO<()> {
+    match file.0.lock().unwrap().take() {
+        Some(mut file)  => file.flush().into(),
+        None            => IO::Value(())
+    }
+}
+
+fn is_file_closed(file: &ScriptFile) -> bool {
+    file.0.lock().unwrap().is_none()
+}
+
+fn read_char() -> IO<char> {
+    match io::stdin().lock().bytes().next() {
+        Some(Ok(byte))  => IO::Value(byte as char),
+        Some(Err(err))  => IO::Exception(err.to_string()),
+        None            => IO::Exception("No read".to_string())
+    }
+}
+
+fn read_line() -> IO<String> {
+    let mut buffer = String::new();
+    io::stdin().read_line(&mut buffer).map(move |_| buffer).into()
+}
+
+fn throw(message: String) -> IO<OpaqueValue<RootedThread, A>> {
+    IO::Exception(message)
+}
+
+///
+/// Loads the std.io.prim module
+///
+/// This has the same contents as the module that comes with Gluon, except for `catch`, `run_expr` and `load_script`, which
+/// rely on the internals of the Gluon VM.
+///
+fn load_std_io(vm: &Thread) -> vm::Result<ExternModule> {
+    vm.register_type::<ScriptFile>("std.io.File", &[])?;
+
+    type FlatMap    = fn(fn(A) -> IO<B>, IO<A>) -> IO<B>;
+    type Wrap       = fn(A) -> IO<A>;
+
+    // flat_map f m : (a -> IO b) -> IO a -> IO b = f (m ())
+    let flat_map    = vec![Call(1), PushInt(0), TailCall(2)];
+    let wrap        = vec![Pop(1)];
+
+    ExternModule::new(vm, record_no_decl! {
+        type File                   => ScriptFile,
+        type OpenOptions            => OpenOptions,
+        type IO a                   => IO<A>,
+        flat_map                    => TypedBytecode::<FlatMap>::new("std.io.prim.flat_map", 3, flat_map),
+        wrap                        => TypedBytecode::<Wrap>::new("std.io.prim.wrap", 2, wrap),
+        open_file_with              => primitive!(2, "std.io.prim.open_file_with", open_file_with),
+        read_file_to_string         => primitive!(1, "std.io.prim.read_file_to_string", read_file_to_string),
+        read_file_to_array          => primitive!(1, "std.io.prim.read_file_to_array", read_file_to_array),
+        read_file                   => primitive!(2, "std.io.prim.read_file", read_file),
+        read_file_to_end            => primitive!(1, "std.io.prim.read_file_to_end", read_file_to_end),
+        write_slice_file            => primitive!(4, "std.io.prim.write_slice_file", write_slice_file),
+        flush_file                  => primitive!(1, "std.io.prim.flush_file", flush_file),
+        close_file                  => primitive!(1, "std.io.prim.close_file", close_file),
+        is_file_closed              => primitive!(1, "std.io.prim.is_file_closed", is_file_closed),
+        read_char                   => primitive!(0, "std.io.prim.read_char", read_char),
+        read_line                   => primitive!(0, "std.io.prim.read_line", read_line),
+        print                       => primitive!(1, "std.io.prim.print", print),
+        println                     => primitive!(1, "std.io.prim.println", println),
+        flush_stdout                => primitive!(0, "std.io.prim.flush_stdout", flush_stdout),
+        eprint                      => primitive!(1, "std.io.prim.eprint", eprint),
+        eprintln                    => primitive!(1, "std.io.prim.eprintln", eprintln),
+        throw                       => primitive!(1, "std.io.prim.throw", throw),
+        default_buf_len             => 8192
+    })
+}
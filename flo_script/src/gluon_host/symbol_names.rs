@@ -0,0 +1,155 @@
+use super::super::error::*;
+use super::super::symbol::*;
+
+use std::fmt::Write;
+
+///
+/// Words that are reserved by the Gluon language and can't be used as identifiers
+///
+const GLUON_KEYWORDS: &[&str] = &["and", "do", "else", "forall", "if", "in", "let", "match", "rec", "seq", "then", "type", "with"];
+
+///
+/// How a namespace deals with symbols whose names can't be used as identifiers in a Gluon script
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum SymbolNamePolicy {
+    /// Symbols can have any name, and scripts refer to them by that name, so symbols with unusable names can't be read by scripts
+    Allow,
+
+    /// Edits that define a symbol with an unusable name fail with `FloScriptError::InvalidSymbolName` (the default)
+    #[default]
+    Reject,
+
+    /// Symbols with unusable names are allowed, and scripts refer to them by their mangled name (see `mangle_symbol_name()`)
+    Mangle
+}
+
+///
+/// True if the specified character can be part of a Gluon identifier
+///
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+///
+/// True if a name can be used as an identifier in a Gluon script
+///
+/// Identifiers must start with a lowercase letter or an underscore, can only contain letters, digits and underscores, and
+/// cannot be a Gluon keyword.
+///
+pub fn is_valid_symbol_name(name: &str) -> bool {
+    let first_char = match name.chars().next() {
+        Some(first_char)    => first_char,
+        None                => return false
+    };
+
+    (first_char.is_ascii_lowercase() || first_char == '_')
+        && name != "_"
+        && name.chars().all(is_identifier_char)
+        && !GLUON_KEYWORDS.contains(&name)
+}
+
+///
+/// Converts a name into one that can be used as an identifier in a Gluon script
+///
+/// Names that are already valid are left alone. Otherwise, any character that can't be used in an identifier is replaced
+/// by `_x` followed by its hex code and another `_`, names that don't start with a lowercase letter get a `_` prefix, and
+/// keywords get a `_` suffix. For example, `my value` becomes `my_x20_value` and `Width` becomes `_Width`.
+///
+/// Mangled names can be the same as other valid names (`my value` and `my_x20_value` are both `my_x20_value`), so
+/// namespaces that use this reject a symbol whose mangled name is already used by another symbol.
+///
+pub fn mangle_symbol_name(name: &str) -> String {
+    if is_valid_symbol_name(name) {
+        return name.to_string();
+    }
+
+    if GLUON_KEYWORDS.contains(&name) {
+        return format!("{}_", name);
+    }
+
+    let mut mangled = String::new();
+
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        mangled.push('_');
+    }
+
+    for c in name.chars() {
+        if is_identifier_char(c) {
+            mangled.push(c);
+        } else {
+            write!(mangled, "_x{:x}_", c as u32).ok();
+        }
+    }
+
+    mangled
+}
+
+///
+/// Retrieves the name that scripts use to refer to a symbol using the specified policy
+///
+pub fn script_name(symbol: FloScriptSymbol, policy: SymbolNamePolicy) -> Option<String> {
+    match policy {
+        SymbolNamePolicy::Allow     |
+        SymbolNamePolicy::Reject    => symbol.name(),
+        SymbolNamePolicy::Mangle    => symbol.name().map(|name| mangle_symbol_name(&name))
+    }
+}
+
+///
+/// Checks that a symbol can be referenced from a script using the specified policy
+///
+/// Anonymous symbols can't be referenced from scripts at all, so they are always accepted.
+///
+pub fn check_symbol_name(symbol: FloScriptSymbol, policy: SymbolNamePolicy) -> FloScriptResult<()> {
+    match (symbol.name(), policy) {
+        (None, _)                               => Ok(()),
+        (Some(_), SymbolNamePolicy::Allow)      => Ok(()),
+        (Some(_), SymbolNamePolicy::Mangle)     => Ok(()),
+        (Some(name), SymbolNamePolicy::Reject)  => {
+            if is_valid_symbol_name(&name) {
+                Ok(())
+            } else {
+                Err(FloScriptError::InvalidSymbolName(name))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn simple_names_are_valid() {
+        assert!(is_valid_symbol_name("x"));
+        assert!(is_valid_symbol_name("opacity_2"));
+        assert!(is_valid_symbol_name("_private"));
+    }
+
+    #[test]
+    fn unusable_names_are_invalid() {
+        assert!(!is_valid_symbol_name(""));
+        assert!(!is_valid_symbol_name("_"));
+        assert!(!is_valid_symbol_name("my value"));
+        assert!(!is_valid_symbol_name("Width"));
+        assert!(!is_valid_symbol_name("2d"));
+        assert!(!is_valid_symbol_name("let"));
+        assert!(!is_valid_symbol_name("a.b"));
+    }
+
+    #[test]
+    fn mangled_names_are_valid() {
+        for name in &["my value", "Width", "2d", "let", "a.b", "_", "caf\u{e9}"] {
+            assert!(is_valid_symbol_name(&mangle_symbol_name(name)));
+        }
+    }
+
+    #[test]
+    fn mangle_names() {
+        assert!(mangle_symbol_name("x") == "x");
+        assert!(mangle_symbol_name("my value") == "my_x20_value");
+        assert!(mangle_symbol_name("Width") == "_Width");
+        assert!(mangle_symbol_name("let") == "let_");
+    }
+}
@@ -0,0 +1,337 @@
+use super::super::error::*;
+use super::super::notebook_symbol::*;
+
+use futures::*;
+use futures::task;
+use futures::task::Task;
+
+use std::sync::*;
+
+///
+/// The shared state of a status cell
+///
+struct StatusCellCore {
+    /// The current status of the symbol
+    status: SymbolStatus,
+
+    /// Incremented every time the status changes, so watchers can tell if they've seen the latest status
+    version: usize,
+
+    /// Incremented every time the symbol is redefined, so streams from an earlier definition can't change the status
+    generation: usize,
+
+    /// Incremented every time the symbol is given a new definition (a new script, input, constant or namespace), so readers can
+    /// tell when they're reading from a definition that has been replaced
+    definition: usize,
+
+    /// Set once the symbol has been removed from its namespace
+    closed: bool,
+
+    /// The tasks waiting for the status to change
+    waiting: Vec<Task>,
+
+    /// The tasks reading from the current definition, which are woken when it's replaced
+    readers: Vec<Task>,
+
+    /// Functions that cancel anything that's evaluating the current definition (called when it's replaced)
+    cancellers: Vec<Box<dyn FnOnce()+Send>>
+}
+
+///
+/// Tracks the status of a symbol in a namespace
+///
+/// The cell is shared between the namespace and any streams that are producing values for the symbol, so the status can be
+/// updated as a script runs without needing to go through the namespace.
+///
+#[derive(Clone)]
+pub struct SymbolStatusCell {
+    core: Arc<Mutex<StatusCellCore>>
+}
+
+impl SymbolStatusCell {
+    ///
+    /// Creates a new status cell
+    ///
+    pub fn new(status: SymbolStatus) -> SymbolStatusCell {
+        SymbolStatusCell {
+            core: Arc::new(Mutex::new(StatusCellCore {
+                status,
+                version:    0,
+                generation: 0,
+                definition: 0,
+                closed:     false,
+                waiting:    vec![],
+                readers:    vec![],
+                cancellers: vec![]
+            }))
+        }
+    }
+
+    ///
+    /// Creates a new cell with the same status as this one (which won't be affected by changes to this cell)
+    ///
+    pub fn detached_copy(&self) -> SymbolStatusCell {
+        SymbolStatusCell::new(self.status())
+    }
+
+    ///
+    /// Retrieves the current status
+    ///
+    pub fn status(&self) -> SymbolStatus {
+        self.core.lock().unwrap().status.clone()
+    }
+
+    ///
+    /// Sets the status for a new definition of the symbol, returning the generation that streams for that definition should use
+    ///
+    pub fn redefine(&self, status: SymbolStatus) -> usize {
+        let mut core    = self.core.lock().unwrap();
+
+        core.generation += 1;
+        core.set_status(status);
+
+        core.generation
+    }
+
+    ///
+    /// Updates the status from a stream, provided that the symbol hasn't been redefined since the stream was created
+    ///
+    pub fn update(&self, generation: usize, status: SymbolStatus) {
+        let mut core = self.core.lock().unwrap();
+
+        if core.generation == generation {
+            core.set_status(status);
+        }
+    }
+
+    ///
+    /// Marks the symbol as removed (which ends any status streams and cancels any evaluation of its definition)
+    ///
+    pub fn close(&self) {
+        let mut core    = self.core.lock().unwrap();
+
+        core.closed     = true;
+        core.waiting.drain(..).for_each(|task| task.notify());
+        core.end_definition();
+    }
+
+    ///
+    /// Indicates that the symbol has been given a new definition, cancelling any evaluation of the previous one
+    ///
+    /// Unlike `redefine()`, this isn't called when the same definition starts producing values from a new stream (for instance,
+    /// when a stream is attached to an input). Readers of the old definition can use its token to switch to the new one.
+    ///
+    pub fn replace_definition(&self) {
+        let mut core    = self.core.lock().unwrap();
+
+        core.definition += 1;
+        core.end_definition();
+    }
+
+    ///
+    /// Retrieves a token representing the current definition of the symbol
+    ///
+    pub fn definition_token(&self) -> DefinitionToken {
+        let definition = self.core.lock().unwrap().definition;
+
+        DefinitionToken {
+            cell: self.clone(),
+            definition
+        }
+    }
+
+    ///
+    /// Creates a state stream that returns the current status and then any changes to it
+    ///
+    pub fn watch(&self) -> SymbolStatusStream {
+        SymbolStatusStream {
+            cell:           self.clone(),
+            last_version:   None
+        }
+    }
+}
+
+impl StatusCellCore {
+    ///
+    /// Cancels anything evaluating the current definition and wakes its readers
+    ///
+    fn end_definition(&mut self) {
+        self.readers.drain(..).for_each(|task| task.notify());
+        self.cancellers.drain(..).for_each(|cancel| cancel());
+    }
+
+    ///
+    /// Changes the status, waking anything that's watching for changes
+    ///
+    fn set_status(&mut self, status: SymbolStatus) {
+        if self.status != status {
+            self.status     = status;
+            self.version    += 1;
+            self.waiting.drain(..).for_each(|task| task.notify());
+        }
+    }
+}
+
+///
+/// Identifies a single definition of a symbol, so readers can tell when it has been replaced or removed
+///
+#[derive(Clone)]
+pub struct DefinitionToken {
+    /// The cell for the symbol
+    cell: SymbolStatusCell,
+
+    /// The definition that this token represents
+    definition: usize
+}
+
+impl DefinitionToken {
+    ///
+    /// True if the symbol still has the definition that this token represents
+    ///
+    pub fn is_current(&self) -> bool {
+        let core = self.cell.core.lock().unwrap();
+
+        !core.closed && core.definition == self.definition
+    }
+
+    ///
+    /// Retrieves the error that stopped the definition represented by this token from producing values, if it has failed
+    ///
+    pub fn failure(&self) -> Option<FloScriptError> {
+        let core = self.cell.core.lock().unwrap();
+
+        match &core.status {
+            SymbolStatus::Failed(error) if !core.closed && core.definition == self.definition   => Some(error.clone()),
+            _                                                                                   => None
+        }
+    }
+
+    ///
+    /// Wakes the current task when the definition represented by this token is replaced (or immediately if it already has been)
+    ///
+    pub fn notify_when_replaced(&self) {
+        let mut core = self.cell.core.lock().unwrap();
+
+        if core.closed || core.definition != self.definition {
+            task::current().notify();
+        } else if !core.readers.iter().any(|reader| reader.will_notify_current()) {
+            core.readers.push(task::current());
+        }
+    }
+
+    ///
+    /// Calls a function when the definition represented by this token is replaced (or immediately if it already has been)
+    ///
+    /// This is used to stop scripts that are still evaluating an old definition.
+    ///
+    pub fn on_replaced<CancelFn: 'static+FnOnce()+Send>(&self, cancel: CancelFn) {
+        let mut core = self.cell.core.lock().unwrap();
+
+        if core.closed || core.definition != self.definition {
+            cancel();
+        } else {
+            core.cancellers.push(Box::new(cancel));
+        }
+    }
+}
+
+///
+/// Stream that returns the status of a symbol
+///
+/// This is a state stream: if the status changes several times between polls, only the latest status is returned. The stream
+/// finishes once the symbol is removed from its namespace.
+///
+pub struct SymbolStatusStream {
+    /// The cell that this is watching
+    cell: SymbolStatusCell,
+
+    /// The version of the status that was returned last
+    last_version: Option<usize>
+}
+
+impl Stream for SymbolStatusStream {
+    type Item   = SymbolStatus;
+    type Error  = ();
+
+    fn poll(&mut self) -> Poll<Option<SymbolStatus>, ()> {
+        let mut core = self.cell.core.lock().unwrap();
+
+        if core.closed {
+            Ok(Async::Ready(None))
+        } else if self.last_version != Some(core.version) {
+            self.last_version = Some(core.version);
+            Ok(Async::Ready(Some(core.status.clone())))
+        } else {
+            if !core.waiting.iter().any(|waiting| waiting.will_notify_current()) {
+                core.waiting.push(task::current());
+            }
+
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+///
+/// Wraps a stream producing values for a symbol, updating its status as values are produced
+///
+/// The symbol is up to date once a value has been produced and finished once the stream ends. Streams are only polled when
+/// something is reading from the symbol, so the status is only updated while the symbol is being read.
+///
+pub struct StatusTrackingStream<SourceStream> {
+    /// The stream producing values
+    stream: SourceStream,
+
+    /// The cell where the status should be written
+    cell: SymbolStatusCell,
+
+    /// The generation of the definition that this stream belongs to
+    generation: usize
+}
+
+impl<SourceStream: Stream> StatusTrackingStream<SourceStream>
+where SourceStream::Error: StreamFailure {
+    ///
+    /// Creates a stream that will update a status cell (for the specified generation of the symbol definition)
+    ///
+    pub fn new(stream: SourceStream, cell: SymbolStatusCell, generation: usize) -> StatusTrackingStream<SourceStream> {
+        StatusTrackingStream { stream, cell, generation }
+    }
+}
+
+impl<SourceStream: Stream> Stream for StatusTrackingStream<SourceStream>
+where SourceStream::Error: StreamFailure {
+    type Item   = SourceStream::Item;
+    type Error  = ();
+
+    fn poll(&mut self) -> Poll<Option<SourceStream::Item>, ()> {
+        match self.stream.poll() {
+            Ok(Async::NotReady)             => Ok(Async::NotReady),
+            Ok(Async::Ready(Some(value)))   => { self.cell.update(self.generation, SymbolStatus::UpToDate); Ok(Async::Ready(Some(value))) },
+            Ok(Async::Ready(None))          => { self.cell.update(self.generation, SymbolStatus::Finished); Ok(Async::Ready(None)) },
+            Err(failure)                    => { self.cell.update(self.generation, SymbolStatus::Failed(failure.failure())); Err(()) }
+        }
+    }
+}
+
+///
+/// Describes why a stream that's producing values for a symbol has failed
+///
+pub trait StreamFailure {
+    ///
+    /// The error that the status of the symbol should report
+    ///
+    fn failure(&self) -> FloScriptError;
+}
+
+impl StreamFailure for () {
+    fn failure(&self) -> FloScriptError {
+        // Input streams don't say why they failed
+        FloScriptError::ScriptError("Failed while producing a value".to_string())
+    }
+}
+
+impl StreamFailure for FloScriptError {
+    fn failure(&self) -> FloScriptError {
+        self.clone()
+    }
+}
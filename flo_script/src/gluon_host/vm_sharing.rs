@@ -0,0 +1,182 @@
+use super::derived_state;
+use super::script_dependencies;
+use super::script_type_declaration::*;
+use super::std_io::*;
+use super::super::error::*;
+use super::super::script_map::*;
+
+use gluon::{RootedThread, Compiler};
+use gluon::vm::api::{VmType};
+
+use std::any::*;
+use std::sync::*;
+
+///
+/// How the namespaces in a script host share Gluon VMs
+///
+/// Modules loaded into a VM are shared by every thread created from it, and once a script has been compiled its imports stay
+/// bound to the module that was loaded at the time (see `tests/gluon.rs`). Namespaces only load the standard modules, so
+/// they can safely share VMs: sharing just means that those modules are loaded fewer times. Anything that needs to load a
+/// module that's specific to one namespace should use isolated VMs.
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum VmSharing {
+    /// Every namespace creates its own VM (the default)
+    #[default]
+    Isolated,
+
+    /// Every namespace uses a thread created from a single VM, so the standard modules are only loaded once
+    Shared,
+
+    /// Namespaces use threads created from a pool of up to the specified number of VMs, which are handed out in turn
+    Pooled(usize)
+}
+
+///
+/// Creates the threads that namespaces evaluate their scripts on, according to a sharing policy
+///
+/// The same `ScriptVms` is used by a namespace and all of its child namespaces.
+///
+pub (crate) struct ScriptVms {
+    /// How VMs are shared
+    sharing: VmSharing,
+
+    /// The VMs that have been created for sharing between namespaces
+    vms: Mutex<Vec<RootedThread>>,
+
+    /// The number of threads that have been handed out from the shared VMs
+    next_vm: Mutex<usize>,
+
+    /// The types that are declared in every VM created for the namespaces, with their Gluon names
+    types: Mutex<Vec<(TypeId, &'static str, DeclareTypeFn)>>
+}
+
+impl ScriptVms {
+    ///
+    /// Creates a new set of VMs with the specified sharing policy
+    ///
+    pub fn new(sharing: VmSharing) -> ScriptVms {
+        ScriptVms {
+            sharing,
+            vms:        Mutex::new(vec![]),
+            next_vm:    Mutex::new(0),
+            types:      Mutex::new(vec![])
+        }
+    }
+
+    ///
+    /// Creates a new set of VMs with a different sharing policy, which declares the same types as this one
+    ///
+    pub fn with_sharing(&self, sharing: VmSharing) -> ScriptVms {
+        ScriptVms {
+            sharing,
+            vms:        Mutex::new(vec![]),
+            next_vm:    Mutex::new(0),
+            types:      Mutex::new(self.types.lock().unwrap().clone())
+        }
+    }
+
+    ///
+    /// Declares a type in the VMs created from now on, and in the VMs that are already being shared
+    ///
+    /// Types are declared by name, so this fails if a different type has already been declared with the same name.
+    ///
+    pub fn declare_type<T: DeclaredScriptType>(&self) -> FloScriptResult<()>
+    where <T as VmType>::Type: Sized {
+        {
+            let mut types = self.types.lock().unwrap();
+
+            if types.iter().any(|(type_id, _, _)| type_id == &TypeId::of::<T>()) {
+                return Ok(());
+            }
+
+            if types.iter().any(|(_, name, _)| name == &T::gluon_name()) {
+                return Err(FloScriptError::UnsupportedType(format!("`{}` cannot be declared: another type with the same name has already been declared", T::gluon_name())));
+            }
+
+            types.push((TypeId::of::<T>(), T::gluon_name(), declare_script_type::<T>));
+        }
+
+        for vm in self.vms.lock().unwrap().iter() {
+            declare_script_type::<T>(vm)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Retrieves the sharing policy for these VMs
+    ///
+    pub fn sharing(&self) -> VmSharing {
+        self.sharing
+    }
+
+    ///
+    /// Retrieves a thread for a namespace to evaluate its scripts on
+    ///
+    /// This returns an error if a new VM is needed and the standard modules can't be loaded into it.
+    ///
+    pub fn thread_for_namespace(&self) -> FloScriptResult<RootedThread> {
+        let pool_size = match self.sharing {
+            VmSharing::Isolated         => return self.create_vm(),
+            VmSharing::Shared           => 1,
+            VmSharing::Pooled(size)     => size.max(1)
+        };
+
+        // Pick the next VM from the pool, creating it if it doesn't exist yet
+        let vm = {
+            let mut vms     = self.vms.lock().unwrap();
+            let mut next_vm = self.next_vm.lock().unwrap();
+            let index       = *next_vm % pool_size;
+            *next_vm        += 1;
+
+            if index >= vms.len() {
+                vms.push(self.create_vm()?);
+            }
+
+            vms[index].clone()
+        };
+
+        vm.new_thread().map_err(|error| FloScriptError::ScriptError(error.to_string()))
+    }
+
+    ///
+    /// Creates a new VM with the standard modules loaded
+    ///
+    fn create_vm(&self) -> FloScriptResult<RootedThread> {
+        // Create the thread as a new VM
+        let thread          = new_script_vm();
+
+        // Import the standard modules
+        derived_state::load_flo_computed(&thread).map_err(|error| FloScriptError::ScriptError(error.to_string()))?;
+        script_dependencies::load_flo_script_reads(&thread);
+
+        // Declare the types that the scripts can refer to by name
+        let types           = self.types.lock().unwrap().clone();
+        for (_, _, declare) in types {
+            declare(&thread)?;
+        }
+
+        // To make user data types available to Rust, we need to invoke the side-effects of the import! macro inside gluon
+        // (std.map is imported so that maps can be used as symbol types)
+        Compiler::default().run_expr::<()>(&thread, "import_flo_computed", "import! flo.computed\nimport! flo.script.reads\nimport! std.map\n()")
+            .map_err(|error| FloScriptError::ScriptError(error.to_string()))?;
+
+        // Map values are created with a function from our own module (see `ScriptMap`)
+        Compiler::default().load_script(&thread, MAP_MODULE, MAP_MODULE_SOURCE)
+            .map_err(|error| FloScriptError::ScriptError(error.to_string()))?;
+
+        Ok(thread)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn isolated_by_default() {
+        assert!(VmSharing::default() == VmSharing::Isolated);
+        assert!(ScriptVms::new(VmSharing::default()).sharing() == VmSharing::Isolated);
+    }
+}
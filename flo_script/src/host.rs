@@ -1,5 +1,6 @@
 use super::editor::*;
 use super::notebook::*;
+use super::symbol_table::*;
 
 ///
 /// Implementations of this trait host a scripting language used with FlowBetween.
@@ -21,4 +22,12 @@ pub trait FloScriptHost : Send+Sync {
     /// The editor can be used to define input symbols, scripts and namespaces
     /// 
     fn editor(&self) -> Self::Editor;
+
+    ///
+    /// Retrieves the symbol table owned by this host
+    ///
+    /// Symbols created from this table are freed when they're released, or when the host and any other users of the
+    /// table are dropped.
+    ///
+    fn symbols(&self) -> FloScriptSymbolTable;
 }
@@ -0,0 +1,97 @@
+use super::error::*;
+use super::symbol_path::*;
+use super::script_value::*;
+use super::script_type_description::*;
+use super::streams::*;
+
+use std::any::*;
+use std::fmt;
+
+///
+/// A value for the input symbol at the end of a path, used to set several inputs at once with `send_input_tick()`
+///
+/// The value can have any type that can be declared for an input, and the input can be in any namespace within the notebook
+/// that the tick is sent to.
+///
+pub struct InputValue {
+    /// The path to the input symbol that this value is for
+    path: FloScriptPath,
+
+    /// The type of the value
+    value_type: ScriptTypeDescription,
+
+    /// The value itself
+    value: Box<dyn Any+Send>,
+
+    /// Attaches the value to an input stream source that accepts its type
+    attach: fn(&(dyn Any+Send), &mut InputStreamSource) -> FloScriptResult<()>
+}
+
+impl InputValue {
+    ///
+    /// Creates a value for the input symbol at the end of a path
+    ///
+    pub fn new<Path: Into<FloScriptPath>, Value: ScriptType>(path: Path, value: Value) -> InputValue {
+        InputValue {
+            path:       path.into(),
+            value_type: Value::description(),
+            value:      Box::new(value),
+            attach:     attach_value::<Value>
+        }
+    }
+
+    ///
+    /// Creates an input value from a script value (which will have the type of the Rust value that it holds)
+    ///
+    pub fn from_script_value<Path: Into<FloScriptPath>>(path: Path, value: ScriptValue) -> InputValue {
+        use self::ScriptValue::*;
+
+        match value {
+            Bool(value)     => InputValue::new(path, value),
+            I32(value)      => InputValue::new(path, value),
+            U32(value)      => InputValue::new(path, value),
+            I64(value)      => InputValue::new(path, value),
+            U64(value)      => InputValue::new(path, value),
+            F32(value)      => InputValue::new(path, value),
+            F64(value)      => InputValue::new(path, value),
+            String(value)   => InputValue::new(path, value)
+        }
+    }
+
+    ///
+    /// The path to the input symbol that this value is for
+    ///
+    pub fn path(&self) -> &FloScriptPath {
+        &self.path
+    }
+
+    ///
+    /// The type of this value
+    ///
+    pub fn value_type(&self) -> &ScriptTypeDescription {
+        &self.value_type
+    }
+
+    ///
+    /// Makes this value the current value of an input stream source (which must accept values of this type)
+    ///
+    pub fn attach_to(&self, input_source: &mut InputStreamSource) -> FloScriptResult<()> {
+        (self.attach)(&*self.value, input_source)
+    }
+}
+
+impl fmt::Debug for InputValue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "InputValue({}: {})", self.path, self.value_type)
+    }
+}
+
+///
+/// Attaches a value of a known type to an input stream source
+///
+fn attach_value<Value: ScriptType>(value: &(dyn Any+Send), input_source: &mut InputStreamSource) -> FloScriptResult<()> {
+    let value = value.downcast_ref::<Value>()
+        .ok_or_else(|| FloScriptError::incorrect_type(input_source.input_type(), &Value::description()))?;
+
+    input_source.attach_constant(value.clone())
+}
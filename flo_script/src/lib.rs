@@ -4,20 +4,49 @@
 #[macro_use] extern crate lazy_static;
 #[macro_use] extern crate gluon_vm;
 #[macro_use] extern crate gluon_codegen;
+#[macro_use] extern crate serde_derive;
+
+// Used by the code generated by #[derive(ScriptType)]
+#[doc(hidden)] pub use gluon;
 
 mod symbol;
+mod symbol_table;
+mod symbol_key;
+mod symbol_path;
 mod editor;
 mod notebook;
+mod notebook_symbol;
+mod dependency_graph;
 mod host;
 mod error;
 mod script_type_description;
+mod script_type_registry;
+mod script_value;
+mod input_value;
+mod script_map;
+mod symbol_metadata;
+mod notebook_definition;
+mod notebook_directory;
 
 pub use self::symbol::*;
+pub use self::symbol_table::*;
+pub use self::symbol_key::*;
+pub use self::symbol_path::*;
 pub use self::editor::*;
 pub use self::notebook::*;
+pub use self::notebook_symbol::*;
+pub use self::dependency_graph::*;
 pub use self::host::*;
 pub use self::error::*;
 pub use self::script_type_description::*;
+pub use self::script_type_registry::*;
+pub use self::script_value::*;
+pub use self::input_value::*;
+pub use self::script_map::*;
+pub use self::symbol_metadata::*;
+pub use self::notebook_definition::*;
+pub use self::notebook_directory::*;
+pub use flo_script_derive::*;
 
 pub mod gluon_host;
 pub mod streams;
@@ -1,6 +1,11 @@
 use super::error::*;
 use super::symbol::*;
+use super::symbol_path::*;
 use super::script_type_description::*;
+use super::symbol_metadata::*;
+use super::notebook_symbol::*;
+use super::dependency_graph::*;
+use super::input_value::*;
 
 use futures::*;
 use gluon::vm::api::*;
@@ -26,7 +31,13 @@ pub enum NotebookUpdate {
     WithNamespace(FloScriptSymbol, Vec<NotebookUpdate>),
 
     /// A symbol has been removed from the notebook
-    UndefinedSymbol(FloScriptSymbol)
+    UndefinedSymbol(FloScriptSymbol),
+
+    /// The metadata for a symbol has been set
+    SetMetadata(FloScriptSymbol, SymbolMetadata),
+
+    /// A cycle of scripts that depend on each other was found (these scripts won't be evaluated until the cycle is broken)
+    DependencyCycle(Vec<FloScriptPath>)
 }
 
 ///
@@ -37,27 +48,147 @@ pub trait FloScriptNotebook : Sized+Send+Sync {
     /// The type of the stream used to receive updates from this notebook
     type UpdateStream  : Stream<Item=NotebookUpdate, Error=()>+Send;
 
+    /// The type of the stream used to watch the status of a symbol
+    type StatusStream  : Stream<Item=SymbolStatus, Error=()>+Send;
+
     /// Retrieves a stream of updates for this notebook
+    /// 
+    /// The stream starts with updates describing the symbols that are currently defined (along with their metadata), so
+    /// a user interface can be generated from the notebook. Updates for the symbols in a child namespace are sent to the
+    /// update stream for that namespace.
     fn updates(&self) -> Self::UpdateStream;
 
     /// Retrieves a notebook containing the symbols in the specified namespace
     fn namespace(&self, symbol: FloScriptSymbol) -> Option<Self>;
 
+    /// Retrieves the metadata for a symbol, if any has been set
+    fn metadata(&self, symbol: FloScriptSymbol) -> Option<SymbolMetadata>;
+
+    /// Retrieves the current status of a symbol, or `None` if it's not defined
+    fn status(&self, symbol: FloScriptSymbol) -> Option<SymbolStatus>;
+
+    /// Watches the status of a symbol
+    /// 
+    /// This is a state stream: it returns the current status when first polled and then the latest status whenever it
+    /// changes (so a user interface can show which symbols are computing or have failed). The stream finishes if the
+    /// symbol is removed from the notebook.
+    fn watch_status(&self, symbol: FloScriptSymbol) -> FloScriptResult<Self::StatusStream>;
+
+    /// Lists the symbols defined in this notebook (not including the contents of any child namespaces)
+    fn symbols(&self) -> Vec<NotebookSymbol>;
+
+    /// Retrieves the graph of the dependencies between the symbols in this notebook and its child namespaces
+    /// 
+    /// This includes the dependencies found by analysing the scripts in the notebook and the dependencies that were read
+    /// the last time each script was evaluated.
+    fn dependency_graph(&self) -> DependencyGraph;
+
+    /// Lists the symbols defined in this notebook and all of its child namespaces
+    /// 
+    /// Each namespace is listed before its contents, and the paths are relative to this notebook.
+    fn all_symbols(&self) -> Vec<NotebookSymbol> {
+        let mut all_symbols: Vec<NotebookSymbol> = vec![];
+
+        for symbol in self.symbols() {
+            let namespace       = if symbol.kind == SymbolKind::Namespace { self.namespace(symbol.symbol()) } else { None };
+            let child_symbols   = namespace.map(|namespace| namespace.all_symbols()).unwrap_or_default();
+
+            all_symbols.push(symbol.clone());
+            all_symbols.extend(child_symbols.into_iter().map(|child_symbol| NotebookSymbol { path: child_symbol.path.within(symbol.symbol()), ..child_symbol }));
+        }
+
+        all_symbols
+    }
+
+    /// Retrieves the notebook for a namespace nested within this one (or `None` if the list of namespaces is empty)
+    fn nested_namespace(&self, namespaces: &[FloScriptSymbol]) -> Option<Self> {
+        let (namespace, namespaces) = namespaces.split_first()?;
+        let namespace               = self.namespace(*namespace)?;
+
+        if namespaces.is_empty() {
+            Some(namespace)
+        } else {
+            namespace.nested_namespace(namespaces)
+        }
+    }
+
+    /// Retrieves the notebook for the namespace containing the symbol that a path refers to (or `None` if the symbol is in this notebook)
+    fn namespace_for_path(&self, path: &FloScriptPath) -> Option<Self> {
+        self.nested_namespace(path.namespaces())
+    }
+
     /// Attaches an input stream to an input symbol. This will replace any existing input stream for that symbol if there is one.
     fn attach_input<InputStream: 'static+Stream<Error=()>+Send>(&self, symbol: FloScriptSymbol, input: InputStream) -> FloScriptResult<()>
     where InputStream::Item: ScriptType;
 
+    /// Sets the values of several input symbols at once, as a single 'tick'
+    /// 
+    /// Each value is addressed by a path, so a tick can set inputs of any declared type in any namespace within this notebook.
+    /// Either all of the inputs are updated or, if any of the values can't be applied, none of them are. Scripts that depend on
+    /// the inputs are evaluated again once per tick (after the scripts that they depend upon), so they never see a mix of old
+    /// and new input values. Setting a value replaces any stream that's attached to the input.
+    fn send_input_tick(&self, values: Vec<InputValue>) -> FloScriptResult<()>;
+
     /// Creates an output stream to receive the results from a script associated with the specified symbol
     /// 
     /// We currently limit ourselves to types that are supported in Gluon; once Rust fully supports specialization, it will be possible to
     /// remove this limit in order to implement the notebook trait on other scripting engines (specialization would make it possible to
     /// return type errors at runtime instead of compile time and avoid restricting the types here).
-    fn receive_output<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=()>+Send>>
+    /// 
+    /// If the symbol is given a new definition, the stream switches to reading that (any evaluation of the old definition is
+    /// cancelled, and values it was still producing are discarded). The stream finishes if the symbol is removed or if the new
+    /// definition can't be read as the requested type.
+    /// 
+    /// If the symbol fails while producing a value (for instance, because a script was stopped for exceeding its execution
+    /// limits), the stream returns the error from the status of the symbol.
+    fn receive_output<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
     where   OutputItem:             for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
     <OutputItem as VmType>::Type:   Sized;
 
     /// Receives the output stream for the specified symbol as a state stream (which will only return the most recently available symbol when polled)
-    fn receive_output_state<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=()>+Send>>
+    fn receive_output_state<OutputItem: 'static+ScriptType>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
     where   OutputItem:             for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
     <OutputItem as VmType>::Type:   Sized;
+
+    /// Creates an output stream that converts the results from a symbol to the requested type
+    ///
+    /// This is an opt-in alternative to `receive_output()` for readers that aren't strict about types (such as a user
+    /// interface control that can display any number): numbers can be read as any other numeric type, and any value that
+    /// a `ScriptValue` can represent can be read as a `String`. `ScriptTypeDescription::can_coerce_to()` describes these
+    /// rules, and `ScriptTypeDescription::coercion_kind()` reports which of them apply to a type. Other types must be read as
+    /// themselves, or this returns `IncorrectType`. Numbers outside of the range of the requested type are returned as
+    /// `OutOfRange` errors in the stream.
+    fn receive_coerced_output<OutputItem>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
+    where   OutputItem:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <OutputItem as VmType>::Type:   Sized;
+
+    /// Receives the output stream for the specified symbol as a state stream, converting its values to the requested type
+    fn receive_coerced_output_state<OutputItem>(&self, symbol: FloScriptSymbol) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
+    where   OutputItem:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <OutputItem as VmType>::Type:   Sized;
+
+    /// Attaches an input stream to the input symbol at the end of a path
+    fn attach_input_at<InputStream: 'static+Stream<Error=()>+Send>(&self, path: &FloScriptPath, input: InputStream) -> FloScriptResult<()>
+    where InputStream::Item: ScriptType {
+        if path.namespaces().is_empty() {
+            return self.attach_input(path.symbol(), input);
+        }
+
+        self.namespace_for_path(path)
+            .ok_or(FloScriptError::UndefinedSymbol(path.symbol()))?
+            .attach_input(path.symbol(), input)
+    }
+
+    /// Creates an output stream to receive the results from the symbol at the end of a path
+    fn receive_output_at<OutputItem>(&self, path: &FloScriptPath) -> FloScriptResult<Box<dyn Stream<Item=OutputItem, Error=FloScriptError>+Send>>
+    where   OutputItem:             ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+    <OutputItem as VmType>::Type:   Sized {
+        if path.namespaces().is_empty() {
+            return self.receive_output(path.symbol());
+        }
+
+        self.namespace_for_path(path)
+            .ok_or(FloScriptError::UndefinedSymbol(path.symbol()))?
+            .receive_output(path.symbol())
+    }
 }
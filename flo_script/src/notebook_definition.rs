@@ -0,0 +1,224 @@
+use super::error::*;
+use super::symbol_table::*;
+use super::editor::*;
+use super::script_type_registry::*;
+use super::script_value::*;
+
+///
+/// Describes how a single symbol is defined in a saved notebook
+///
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum NotebookSymbolDefinition {
+    /// An input symbol, with the name of the type of data it accepts
+    Input { name: String, type_name: String },
+
+    /// A streaming script and its source
+    StreamingScript { name: String, script: String },
+
+    /// A computing script and its source
+    ComputingScript { name: String, script: String },
+
+    /// A computing script with a declared output type, with the name of that type and the script's source
+    TypedComputingScript { name: String, type_name: String, script: String },
+
+    /// A constant and its value
+    Constant { name: String, value: ScriptValue },
+
+    /// A namespace and the symbols defined within it
+    Namespace { name: String, definition: NotebookDefinition }
+}
+
+///
+/// The definitions for a notebook namespace, in a form that can be saved and reloaded later on
+///
+/// Symbols are stored by name, so anonymous symbols are not part of a notebook definition. Input types and the declared
+/// types of scripts are stored by the name they were given in a `ScriptTypeRegistry`, and need to be registered with the
+/// same name for the notebook to be loaded again.
+///
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NotebookDefinition {
+    /// True if I/O expressions are evaluated in this namespace
+    pub run_io: bool,
+
+    /// The symbols defined in this namespace
+    pub symbols: Vec<NotebookSymbolDefinition>
+}
+
+impl NotebookSymbolDefinition {
+    ///
+    /// The name of the symbol that this defines
+    ///
+    pub fn name(&self) -> &str {
+        use self::NotebookSymbolDefinition::*;
+
+        match self {
+            Input { name, .. }                  |
+            StreamingScript { name, .. }        |
+            ComputingScript { name, .. }        |
+            TypedComputingScript { name, .. }   |
+            Constant { name, .. }               |
+            Namespace { name, .. }              => name
+        }
+    }
+
+    ///
+    /// Generates the script edit that will define this symbol, using the symbols from the specified symbol table
+    ///
+    /// The symbols in the edit are retained in the table, so their names stay available until the edit has been applied.
+    /// Release them with `release_edit_symbols()` once it has been.
+    ///
+    pub fn edit(&self, symbols: &FloScriptSymbolTable, types: &ScriptTypeRegistry) -> FloScriptResult<ScriptEdit> {
+        use self::NotebookSymbolDefinition::*;
+
+        // The symbol is only retained once nothing else can fail
+        match self {
+            Input { name, type_name }                           => {
+                let input_type = types.type_with_name(type_name).ok_or_else(|| FloScriptError::UnknownTypeName(type_name.clone()))?;
+                Ok(ScriptEdit::SetInputType(symbols.symbol(name), input_type))
+            },
+            StreamingScript { name, script }                    => Ok(ScriptEdit::SetStreamingScript(symbols.symbol(name), script.clone())),
+            ComputingScript { name, script }                    => Ok(ScriptEdit::SetComputingScript(symbols.symbol(name), script.clone())),
+            TypedComputingScript { name, type_name, script }    => {
+                let output_type = types.type_with_name(type_name).ok_or_else(|| FloScriptError::UnknownTypeName(type_name.clone()))?;
+                Ok(ScriptEdit::SetTypedComputingScript(symbols.symbol(name), output_type, script.clone()))
+            },
+            Constant { name, value }                            => Ok(ScriptEdit::SetConstant(symbols.symbol(name), value.clone())),
+            Namespace { name, definition }                      => {
+                let edits = definition.edits(symbols, types)?;
+                Ok(ScriptEdit::WithNamespace(symbols.symbol(name), edits))
+            }
+        }
+    }
+}
+
+impl NotebookDefinition {
+    ///
+    /// Creates a new, empty, notebook definition
+    ///
+    pub fn new() -> NotebookDefinition {
+        NotebookDefinition {
+            run_io:     false,
+            symbols:    vec![]
+        }
+    }
+
+    ///
+    /// Generates the edits required to define the symbols in this notebook
+    ///
+    /// The I/O flag is specific to the Gluon host, so it is not part of the edits generated here. The symbols are taken
+    /// from the specified symbol table (which should be the table of the host that the edits are for), and are retained
+    /// until they're released with `release_edit_symbols()`.
+    ///
+    pub fn edits(&self, symbols: &FloScriptSymbolTable, types: &ScriptTypeRegistry) -> FloScriptResult<Vec<ScriptEdit>> {
+        let mut edits = vec![];
+
+        for symbol in self.symbols.iter() {
+            match symbol.edit(symbols, types) {
+                Ok(edit)    => edits.push(edit),
+                Err(err)    => {
+                    release_edit_symbols(symbols, &edits);
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Generates the edits needed to change a notebook with the `previous` definition so it matches this one
+    ///
+    /// Symbols whose definitions are unchanged do not generate any edits. As with `edits()`, the I/O flag is not
+    /// included and the symbols are taken from the specified symbol table and retained until they're released.
+    ///
+    pub fn edits_from(&self, previous: &NotebookDefinition, symbols: &FloScriptSymbolTable, types: &ScriptTypeRegistry) -> FloScriptResult<Vec<ScriptEdit>> {
+        let mut edits = vec![];
+
+        match self.push_edits_from(previous, symbols, types, &mut edits) {
+            Ok(())      => Ok(edits),
+            Err(err)    => {
+                release_edit_symbols(symbols, &edits);
+                Err(err)
+            }
+        }
+    }
+
+    ///
+    /// Adds the edits generated by `edits_from()` to a list, stopping at the first error
+    ///
+    fn push_edits_from(&self, previous: &NotebookDefinition, symbols: &FloScriptSymbolTable, types: &ScriptTypeRegistry, edits: &mut Vec<ScriptEdit>) -> FloScriptResult<()> {
+
+        // Remove any symbol that's no longer defined
+        for previous_symbol in previous.symbols.iter() {
+            if !self.symbols.iter().any(|symbol| symbol.name() == previous_symbol.name()) {
+                edits.push(ScriptEdit::UndefineSymbol(symbols.symbol(previous_symbol.name())));
+            }
+        }
+
+        // Define any symbol that's new or has changed
+        for symbol in self.symbols.iter() {
+            let previous_symbol = previous.symbols.iter().find(|previous_symbol| previous_symbol.name() == symbol.name());
+
+            match (symbol, previous_symbol) {
+                (NotebookSymbolDefinition::Namespace { name, definition }, Some(NotebookSymbolDefinition::Namespace { definition: previous_definition, .. })) => {
+                    // Namespaces are updated in place
+                    let namespace_edits = definition.edits_from(previous_definition, symbols, types)?;
+
+                    if !namespace_edits.is_empty() {
+                        edits.push(ScriptEdit::WithNamespace(symbols.symbol(name), namespace_edits));
+                    }
+                }
+
+                (NotebookSymbolDefinition::Namespace { name, .. }, Some(_)) => {
+                    // Other kinds of symbol have to be removed before they can be replaced by a namespace
+                    let namespace_edit = symbol.edit(symbols, types)?;
+                    edits.push(ScriptEdit::UndefineSymbol(symbols.symbol(name)));
+                    edits.push(namespace_edit);
+                }
+
+                (symbol, Some(previous_symbol)) if symbol == previous_symbol => { }
+
+                (symbol, _) => {
+                    edits.push(symbol.edit(symbols, types)?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for NotebookDefinition {
+    fn default() -> NotebookDefinition {
+        NotebookDefinition::new()
+    }
+}
+
+///
+/// Releases the usages of the symbols in edits generated from a notebook definition, once the edits have been applied
+///
+/// Notebooks retain the symbols that they define, so this frees the names that are no longer needed by anything else.
+///
+pub fn release_edit_symbols(symbols: &FloScriptSymbolTable, edits: &[ScriptEdit]) {
+    use self::ScriptEdit::*;
+
+    for edit in edits.iter() {
+        match edit {
+            UndefineSymbol(symbol)                      |
+            SetInputType(symbol, _)                     |
+            SetStreamingScript(symbol, _)               |
+            SetComputingScript(symbol, _)               |
+            SetTypedComputingScript(symbol, _, _)       |
+            SetConstant(symbol, _)                      |
+            SetMetadata(symbol, _)                      => symbols.release(*symbol),
+            WithNamespace(symbol, edits)                => {
+                symbols.release(*symbol);
+                release_edit_symbols(symbols, edits);
+            }
+
+            // Notebook definitions don't generate these edits
+            Clear                                       |
+            MoveSymbol(_, _)                            => { }
+        }
+    }
+}
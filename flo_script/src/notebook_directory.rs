@@ -0,0 +1,214 @@
+use super::error::*;
+use super::editor::*;
+use super::notebook_definition::*;
+use super::script_type_registry::*;
+use super::symbol_table::*;
+
+use futures::*;
+use futures::sync::mpsc;
+
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use std::path::{Path, PathBuf};
+
+/// The name of the file in each directory that lists the input symbols for that namespace
+pub const INPUT_MANIFEST_FILENAME: &str = "inputs.manifest";
+
+/// The extension used for script files
+const SCRIPT_EXTENSION: &str = "glu";
+
+///
+/// Loads a notebook from a directory tree of script files
+///
+/// Every `*.glu` file defines a computing script named after the file, and every subdirectory defines a namespace. Input
+/// symbols are listed in a manifest file called `inputs.manifest`, one per line in the form `name: type`, where the type is
+/// the name that the type was registered with in the script type registry (`i32`, `String` and so on for the standard
+/// types). Blank lines and lines starting with `#` are ignored in the manifest.
+///
+/// Each name can only be defined once in a directory: a script file, a subdirectory or a manifest entry with the same name
+/// as another one is reported as an error when the directory is read.
+///
+pub struct NotebookDirectory {
+    /// The path to the root of the notebook
+    path: PathBuf,
+
+    /// The symbol table that names the symbols in the edits generated for the directory
+    symbols: FloScriptSymbolTable,
+
+    /// The types that can be used in the input manifests
+    types: ScriptTypeRegistry,
+
+    /// The definition that was read the last time the directory was scanned
+    last_definition: NotebookDefinition,
+
+    /// The edits returned by the last scan, whose symbols are kept in the symbol table until the next scan
+    last_edits: Vec<ScriptEdit>
+}
+
+impl NotebookDirectory {
+    ///
+    /// Creates a new notebook directory reader. The directory is not read until it is scanned.
+    ///
+    /// The edits generated for the directory use symbols from the specified symbol table, which should be the table of the
+    /// host that they're sent to.
+    ///
+    pub fn new<DirectoryPath: Into<PathBuf>>(path: DirectoryPath, symbols: FloScriptSymbolTable, types: ScriptTypeRegistry) -> NotebookDirectory {
+        NotebookDirectory {
+            path:               path.into(),
+            symbols,
+            types,
+            last_definition:    NotebookDefinition::new(),
+            last_edits:         vec![]
+        }
+    }
+
+    ///
+    /// Reads the current definition of the notebook from the directory
+    ///
+    pub fn read_definition(&self) -> FloScriptResult<NotebookDefinition> {
+        Self::read_directory(&self.path)
+    }
+
+    ///
+    /// Reads the notebook definition for a single directory
+    ///
+    fn read_directory(path: &Path) -> FloScriptResult<NotebookDefinition> {
+        let mut symbols = vec![];
+
+        for entry in fs::read_dir(path).map_err(|err| load_error(path, err))? {
+            let entry       = entry.map_err(|err| load_error(path, err))?;
+            let entry_path  = entry.path();
+            let file_name   = entry.file_name().to_string_lossy().to_string();
+
+            if file_name.starts_with('.') {
+                // Hidden files are ignored
+                continue;
+            }
+
+            if entry_path.is_dir() {
+                // Subdirectories are namespaces
+                symbols.push(NotebookSymbolDefinition::Namespace { name: file_name, definition: Self::read_directory(&entry_path)? });
+            } else if file_name == INPUT_MANIFEST_FILENAME {
+                // The manifest defines the inputs
+                symbols.extend(Self::read_manifest(&entry_path)?);
+            } else if entry_path.extension().map(|extension| extension == SCRIPT_EXTENSION).unwrap_or(false) {
+                // Script files are computing scripts
+                let name    = entry_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                let script  = fs::read_to_string(&entry_path).map_err(|err| load_error(&entry_path, err))?;
+
+                symbols.push(NotebookSymbolDefinition::ComputingScript { name, script });
+            }
+        }
+
+        // Directory order is not predictable, so sort the symbols by name
+        symbols.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // A script, a subdirectory and a manifest entry can all try to define the same symbol
+        if let Some(duplicate) = symbols.windows(2).find(|pair| pair[0].name() == pair[1].name()) {
+            return Err(FloScriptError::LoadError(format!("{}: `{}` is defined more than once (by a script file, a subdirectory or the input manifest)", path.display(), duplicate[0].name())));
+        }
+
+        Ok(NotebookDefinition {
+            run_io:     false,
+            symbols
+        })
+    }
+
+    ///
+    /// Reads the input symbol definitions from a manifest file
+    ///
+    fn read_manifest(path: &Path) -> FloScriptResult<Vec<NotebookSymbolDefinition>> {
+        let manifest = fs::read_to_string(path).map_err(|err| load_error(path, err))?;
+
+        manifest.lines()
+            .enumerate()
+            .map(|(line_number, line)| (line_number, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line_number, line)| {
+                let mut parts = line.splitn(2, ':');
+
+                match (parts.next(), parts.next()) {
+                    (Some(name), Some(type_name))   => Ok(NotebookSymbolDefinition::Input { name: name.trim().to_string(), type_name: type_name.trim().to_string() }),
+                    _                               => Err(FloScriptError::LoadError(format!("{}:{}: expected `name: type`", path.display(), line_number+1)))
+                }
+            })
+            .collect()
+    }
+
+    ///
+    /// Checks the directory for changes since it was last scanned, and returns the edits needed to bring a notebook up to date
+    ///
+    /// The first scan returns the edits needed to define everything in the directory. The symbols in the edits stay in the
+    /// symbol table until the next scan (after which the notebook keeps them if it defines them), so they don't need to be released.
+    ///
+    pub fn scan(&mut self) -> FloScriptResult<Vec<ScriptEdit>> {
+        let definition          = self.read_definition()?;
+        let edits               = definition.edits_from(&self.last_definition, &self.symbols, &self.types)?;
+
+        // The edits from the previous scan should have been applied by now
+        release_edit_symbols(&self.symbols, &self.last_edits);
+
+        self.last_definition    = definition;
+        self.last_edits         = edits.clone();
+
+        Ok(edits)
+    }
+
+    ///
+    /// Polls the directory for changes, returning a stream of the edits needed to keep a notebook up to date with it
+    ///
+    /// The directory is checked every `poll_interval` until the stream is dropped. A scan that fails (for example, because a
+    /// file is only partially written, or because the directory can no longer be read) is passed to `on_error` and tried
+    /// again at the next poll. An error is only reported again if a later scan fails with a different error, or if a scan
+    /// has succeeded in the meantime.
+    ///
+    pub fn watch<ErrorFn: 'static+Send+FnMut(FloScriptError)>(self, poll_interval: Duration, on_error: ErrorFn) -> impl Stream<Item=ScriptEdit, Error=()>+Send {
+        let (send_edits, edits) = mpsc::unbounded();
+        let mut directory       = self;
+        let mut on_error        = on_error;
+
+        thread::spawn(move || {
+            let mut last_error = None;
+
+            while !send_edits.is_closed() {
+                match directory.scan() {
+                    Ok(new_edits)   => {
+                        last_error = None;
+
+                        for edit in new_edits {
+                            if send_edits.unbounded_send(edit).is_err() {
+                                // The stream has been dropped
+                                return;
+                            }
+                        }
+                    }
+
+                    Err(error)      => {
+                        if last_error.as_ref() != Some(&error) {
+                            last_error = Some(error.clone());
+                            on_error(error);
+                        }
+                    }
+                }
+
+                thread::sleep(poll_interval);
+            }
+        });
+
+        edits
+    }
+}
+
+impl Drop for NotebookDirectory {
+    fn drop(&mut self) {
+        release_edit_symbols(&self.symbols, &self.last_edits);
+    }
+}
+
+///
+/// Generates the error for when a file in a notebook directory cannot be read
+///
+fn load_error(path: &Path, error: std::io::Error) -> FloScriptError {
+    FloScriptError::LoadError(format!("{}: {}", path.display(), error))
+}
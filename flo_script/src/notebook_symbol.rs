@@ -0,0 +1,86 @@
+use super::error::*;
+use super::symbol::*;
+use super::symbol_path::*;
+use super::symbol_metadata::*;
+use super::script_type_description::*;
+
+///
+/// The kinds of symbol that can be defined in a notebook
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SymbolKind {
+    /// An input symbol, which receives values from an attached stream
+    Input,
+
+    /// A symbol with a constant value
+    Constant,
+
+    /// A computing script
+    ComputingScript,
+
+    /// A streaming script
+    StreamingScript,
+
+    /// A namespace containing other symbols
+    Namespace,
+
+    /// A script that could not be compiled
+    Error
+}
+
+///
+/// The current status of a symbol in a notebook
+///
+#[derive(Clone, PartialEq, Debug)]
+pub enum SymbolStatus {
+    /// An input symbol that doesn't have an input stream attached yet
+    WaitingForInput,
+
+    /// A script that hasn't been compiled or evaluated yet (scripts are only started when something reads from them)
+    NotStarted,
+
+    /// A script that has been started but hasn't produced a value yet
+    Computing,
+
+    /// The symbol has a value that can be read (an input with an attached stream, a constant, or a script that has produced a value), or is a namespace
+    UpToDate,
+
+    /// The stream for the symbol has finished, so it won't produce any more values
+    Finished,
+
+    /// The symbol failed with an error (when it was compiled or while it was running)
+    Failed(FloScriptError)
+}
+
+///
+/// Describes a symbol defined in a notebook
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct NotebookSymbol {
+    /// The path to the symbol, relative to the notebook that was queried
+    pub path: FloScriptPath,
+
+    /// What kind of symbol this is
+    pub kind: SymbolKind,
+
+    /// The type of the values produced by this symbol, if it's known (streaming scripts and scripts that can't be type-checked have no type)
+    pub value_type: Option<ScriptTypeDescription>,
+
+    /// The source of the script that defines this symbol, if it's a script
+    pub script: Option<String>,
+
+    /// The current status of this symbol
+    pub status: SymbolStatus,
+
+    /// The metadata attached to this symbol, if there is any
+    pub metadata: Option<SymbolMetadata>
+}
+
+impl NotebookSymbol {
+    ///
+    /// The symbol that this describes
+    ///
+    pub fn symbol(&self) -> FloScriptSymbol {
+        self.path.symbol()
+    }
+}
@@ -0,0 +1,108 @@
+use gluon::vm::{Variants};
+use gluon::vm::thread::{Thread, ActiveThread, RootedThread};
+use gluon::vm::api::{VmType, Getable, Pushable, OpaqueValue, OwnedFunction};
+use gluon::base::types::{ArcType};
+
+use std::ops::{Deref, DerefMut};
+use std::collections::{HashMap, BTreeMap};
+
+/// The name of the module that script VMs load to create map values
+pub (crate) const MAP_MODULE: &str = "flo.script.map";
+
+/// The source of the module that creates map values
+///
+/// Gluon's own conversion from a `BTreeMap` uses a function from `std.json.de`, which is only available if Gluon is compiled
+/// with its serialization feature, so maps are created with this module instead.
+pub (crate) const MAP_MODULE_SOURCE: &str = "let map = import! std.map\nlet insert_string : String -> a -> map.Map String a -> map.Map String a = map.insert\n{ insert_string }";
+
+///
+/// A map from strings to values that can be used as a script type
+///
+/// Gluon only provides its traits for `BTreeMap`, so a `HashMap` can't be used as a script type directly. This wraps a
+/// `HashMap` so that it can be: in scripts, it's a `std.map.Map String T`, the same as a `BTreeMap<String, T>`.
+///
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ScriptMap<Value>(pub HashMap<String, Value>);
+
+impl<Value> ScriptMap<Value> {
+    ///
+    /// Creates a new, empty, map
+    ///
+    pub fn new() -> ScriptMap<Value> {
+        ScriptMap(HashMap::new())
+    }
+
+    ///
+    /// Retrieves the hash map wrapped by this object
+    ///
+    pub fn into_inner(self) -> HashMap<String, Value> {
+        self.0
+    }
+}
+
+impl<Value> Deref for ScriptMap<Value> {
+    type Target = HashMap<String, Value>;
+
+    fn deref(&self) -> &HashMap<String, Value> {
+        &self.0
+    }
+}
+
+impl<Value> DerefMut for ScriptMap<Value> {
+    fn deref_mut(&mut self) -> &mut HashMap<String, Value> {
+        &mut self.0
+    }
+}
+
+impl<Value> From<HashMap<String, Value>> for ScriptMap<Value> {
+    fn from(map: HashMap<String, Value>) -> ScriptMap<Value> {
+        ScriptMap(map)
+    }
+}
+
+impl<Value> From<ScriptMap<Value>> for HashMap<String, Value> {
+    fn from(map: ScriptMap<Value>) -> HashMap<String, Value> {
+        map.0
+    }
+}
+
+impl<Value> VmType for ScriptMap<Value>
+where   Value:          VmType,
+        Value::Type:    Sized {
+    type Type = BTreeMap<String, Value::Type>;
+
+    fn make_type(vm: &Thread) -> ArcType {
+        BTreeMap::<String, Value>::make_type(vm)
+    }
+}
+
+impl<'vm, Value> Pushable<'vm> for ScriptMap<Value>
+where   Value:          for<'vm2> Pushable<'vm2> + VmType,
+        Value::Type:    Sized {
+    fn push(self, context: &mut ActiveThread<'vm>) -> gluon::vm::Result<()> {
+        type Map<Value>     = OpaqueValue<RootedThread, BTreeMap<String, Value>>;
+        type Insert<Value>  = OwnedFunction<fn(String, Value, Map<Value>) -> Map<Value>>;
+
+        let thread                      = context.thread();
+        let mut map: Map<Value>         = thread.get_global("std.map.empty")?;
+        let mut insert: Insert<Value>   = thread.get_global(&format!("{}.insert_string", MAP_MODULE))?;
+
+        // The functions run on the same thread, so the context is released while they're called
+        context.drop();
+        for (key, value) in self.0 {
+            map = insert.call(key, value, map)?;
+        }
+        context.restore();
+
+        map.push(context)
+    }
+}
+
+impl<'vm, 'value, Value> Getable<'vm, 'value> for ScriptMap<Value>
+where   Value: Getable<'vm, 'value> {
+    impl_getable_simple!();
+
+    fn from_value(vm: &'vm Thread, value: Variants<'value>) -> ScriptMap<Value> {
+        ScriptMap(BTreeMap::<String, Value>::from_value(vm, value).into_iter().collect())
+    }
+}
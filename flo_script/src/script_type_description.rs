@@ -1,20 +1,41 @@
 use super::error::*;
 use super::symbol::*;
+use super::script_value::*;
+use super::streams::InputStreamSource;
 use super::gluon_host::derived_state::*;
+use super::gluon_host::new_script_vm;
+use super::gluon_host::{GluonScriptNamespace, FollowingStream};
 
 use gluon::{Compiler};
 use gluon::vm;
-use gluon::vm::thread::{RootedThread, Thread};
+use gluon::vm::thread::{RootedThread, Thread, ActiveThread};
 use gluon::vm::ExternModule;
+use gluon::base::types::{ArcType, Type};
 use gluon::vm::api::{UserdataValue, Function, FunctionRef, Primitive, VmType, Pushable, Getable, FutureResult};
+use desync::Desync;
 use futures::*;
 use futures::sync::oneshot;
 
-use std::any::{Any, TypeId};
+use std::any::{Any, TypeId, type_name};
 use std::fmt;
-use std::fmt::{Debug};
+use std::fmt::{Debug, Display};
 use std::result;
 use std::sync::*;
+use std::collections::HashMap;
+
+lazy_static! {
+    /// VM used to generate the Gluon types for type descriptions
+    static ref TYPE_DESCRIPTION_VM: RootedThread = new_script_vm();
+
+    /// The Gluon types that have been generated for type descriptions
+    static ref GLUON_TYPE_NAMES: Mutex<HashMap<TypeId, Option<String>>> = Mutex::new(HashMap::new());
+}
+
+/// A stream of values of a script type whose Rust type is only known at runtime
+pub (crate) type AnyValueStream = Box<dyn Stream<Item=Box<dyn Any+Send>, Error=FloScriptError>+Send>;
+
+/// A stream of values read from an input stream source, whose Rust type is only known at runtime
+pub (crate) type AnyInputStream = Box<dyn Stream<Item=Box<dyn Any+Send>, Error=()>+Send>;
 
 ///
 /// Provides a description for a type that can be used when streaming from a script
@@ -24,6 +45,36 @@ pub struct ScriptTypeDescription {
     /// The ID of this type so it can be compared to others
     type_id: TypeId,
 
+    /// The name of the Rust type that this describes
+    type_name: &'static str,
+
+    /// The Gluon type that this describes, if it could be determined
+    gluon_type: Option<String>,
+
+    /// Generates the Gluon types that a computing script can have if it produces values of this type
+    computing_script_types: fn(&Thread) -> FloScriptResult<Vec<ArcType>>,
+
+    /// Generates the Gluon type for this type in a particular VM
+    vm_type: fn(&Thread) -> FloScriptResult<ArcType>,
+
+    /// Reads a symbol in a namespace as a state stream of values of this type
+    read_any_state_stream: fn(&mut GluonScriptNamespace, FloScriptSymbol) -> FloScriptResult<AnyValueStream>,
+
+    /// Reads a symbol in a namespace as a state stream of values of this type, following it whenever it's redefined
+    follow_any_state_stream: fn(&Arc<Desync<GluonScriptNamespace>>, FloScriptSymbol) -> FloScriptResult<AnyValueStream>,
+
+    /// Reads an input stream source of this type as a stream of boxed values (using the state stream semantics if the flag is set)
+    read_any_input: fn(&mut InputStreamSource, bool) -> FloScriptResult<AnyInputStream>,
+
+    /// How values of this type are converted when they're read with coercion (`None` if they can only be read as themselves)
+    coercion: Option<ValueCoercion>,
+
+    /// Copies a value of this type
+    clone_any: fn(&(dyn Any+Send)) -> Box<dyn Any+Send>,
+
+    /// Pushes a value of this type onto the stack of a Gluon thread
+    push_any: for<'vm> fn(&(dyn Any+Send), &mut ActiveThread<'vm>) -> vm::Result<()>,
+
     /// Creates an extern module loader for the 'resolve' function of a derived state of this type
     derived_state_resolve: Arc<dyn Fn(FloScriptSymbol) -> Box<dyn FnMut(&Thread) -> vm::Result<ExternModule> + Send + 'static>+Send+Sync>
 }
@@ -35,6 +86,136 @@ impl ScriptTypeDescription {
     pub fn is<T: 'static+ScriptType>(&self) -> bool {
         TypeId::of::<T>() == self.type_id
     }
+
+    ///
+    /// Retrieves the ID of the Rust type described by this description
+    ///
+    pub (crate) fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    ///
+    /// Retrieves the name of the Rust type described by this description
+    ///
+    /// This is intended for diagnostics: it isn't guaranteed to stay the same between versions of the compiler, so saved
+    /// notebooks use the names from a `ScriptTypeRegistry` instead.
+    ///
+    pub fn name(&self) -> &'static str {
+        self.type_name
+    }
+
+    ///
+    /// Retrieves the Gluon type corresponding to the Rust type, if it's known
+    ///
+    /// Userdata types only have a Gluon type once they've been registered with a VM, so this can be `None` for
+    /// types that are defined outside of Gluon.
+    ///
+    pub fn gluon_type(&self) -> Option<&str> {
+        self.gluon_type.as_deref()
+    }
+
+    ///
+    /// Generates the Gluon types that a computing script can have if it produces values of this type, in a particular VM
+    ///
+    /// A script can either evaluate to a value of this type or to a derived state that produces values of this type.
+    ///
+    pub (crate) fn computing_script_types(&self, vm: &Thread) -> FloScriptResult<Vec<ArcType>> {
+        (self.computing_script_types)(vm)
+    }
+
+    ///
+    /// Generates the Gluon type for this type in a particular VM
+    ///
+    pub (crate) fn vm_type(&self, vm: &Thread) -> FloScriptResult<ArcType> {
+        (self.vm_type)(vm)
+    }
+
+    ///
+    /// Reads a symbol as a state stream of boxed values of this type (for when the type is only known at runtime)
+    ///
+    pub (crate) fn read_any_state_stream(&self, namespace: &mut GluonScriptNamespace, symbol: FloScriptSymbol) -> FloScriptResult<AnyValueStream> {
+        (self.read_any_state_stream)(namespace, symbol)
+    }
+
+    ///
+    /// Reads a symbol as a state stream of boxed values of this type, switching to its new definition whenever it's redefined
+    ///
+    pub (crate) fn follow_any_state_stream(&self, namespace: &Arc<Desync<GluonScriptNamespace>>, symbol: FloScriptSymbol) -> FloScriptResult<AnyValueStream> {
+        (self.follow_any_state_stream)(namespace, symbol)
+    }
+
+    ///
+    /// Reads an input stream source of this type as a stream of boxed values of this type
+    ///
+    pub (crate) fn read_any_input(&self, input_source: &mut InputStreamSource, state: bool) -> FloScriptResult<AnyInputStream> {
+        (self.read_any_input)(input_source, state)
+    }
+
+    ///
+    /// Copies a boxed value of this type
+    ///
+    pub (crate) fn clone_any(&self, value: &(dyn Any+Send)) -> Box<dyn Any+Send> {
+        (self.clone_any)(value)
+    }
+
+    ///
+    /// Pushes a boxed value of this type onto the stack of a Gluon thread
+    ///
+    pub (crate) fn push_any<'vm>(&self, value: &(dyn Any+Send), context: &mut ActiveThread<'vm>) -> vm::Result<()> {
+        (self.push_any)(value, context)
+    }
+
+    ///
+    /// Retrieves how values of this type are converted when they're read with coercion, or `None` if this isn't a type that
+    /// a `ScriptValue` can represent
+    ///
+    pub fn coercion_kind(&self) -> Option<CoercionKind> {
+        self.coercion.map(|coercion| coercion.kind)
+    }
+
+    ///
+    /// True if this describes one of the numeric types that can be represented by a `ScriptValue`
+    ///
+    pub fn is_numeric(&self) -> bool {
+        self.coercion_kind() == Some(CoercionKind::Numeric)
+    }
+
+    ///
+    /// True if this describes a type that can be represented by a `ScriptValue`
+    ///
+    pub fn is_script_value(&self) -> bool {
+        self.coercion.is_some()
+    }
+
+    ///
+    /// True if values of this type can be converted to the target type when they're read with coercion (see
+    /// `FloScriptNotebook::receive_coerced_output()`)
+    ///
+    /// Any numeric type can be converted to any other numeric type, and any type that can be represented by a `ScriptValue`
+    /// can be converted to a `String`. Other types can only be read as themselves.
+    ///
+    pub fn can_coerce_to(&self, target: &ScriptTypeDescription) -> bool {
+        self == target
+            || (self.is_numeric() && target.is_numeric())
+            || (self.is_script_value() && target.coercion_kind() == Some(CoercionKind::Text))
+    }
+
+    ///
+    /// Converts a boxed value of this type to a script value, if this is a type that a `ScriptValue` can represent
+    ///
+    pub (crate) fn to_script_value(&self, value: &(dyn Any+Send)) -> Option<ScriptValue> {
+        self.coercion.and_then(|coercion| (coercion.to_value)(value))
+    }
+
+    ///
+    /// Converts a script value to a script value of this type, following the rules described by `can_coerce_to()`
+    ///
+    pub (crate) fn coerce_value(&self, value: &ScriptValue) -> FloScriptResult<ScriptValue> {
+        match self.coercion {
+            Some(coercion)  => (coercion.from_value)(value),
+            None            => Err(FloScriptError::incorrect_type(&value.value_type(), self))
+        }
+    }
 }
 
 impl PartialEq for ScriptTypeDescription {
@@ -45,13 +226,203 @@ impl PartialEq for ScriptTypeDescription {
 
 impl Debug for ScriptTypeDescription {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> result::Result<(), fmt::Error> {
-        write!(formatter, "{:?}", self.type_id)
+        formatter.debug_struct("ScriptTypeDescription")
+            .field("type_name", &self.type_name)
+            .field("gluon_type", &self.gluon_type)
+            .finish()
+    }
+}
+
+impl Display for ScriptTypeDescription {
+    ///
+    /// Formats the description as the Rust type name followed by the Gluon type (for example, `i32 (Int)`)
+    ///
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> result::Result<(), fmt::Error> {
+        match &self.gluon_type {
+            Some(gluon_type)    => write!(formatter, "{} ({})", self.type_name, gluon_type),
+            None                => write!(formatter, "{}", self.type_name)
+        }
+    }
+}
+
+///
+/// The Gluon modules that the standard script types depend upon, along with a type that's only known once each one is loaded
+///
+const TYPE_MODULES: &[(&str, &str)] = &[
+    ("std.map", "std.map.Map")
+];
+
+///
+/// Generates the Gluon type for a Rust type in a particular VM
+///
+/// Some types depend on a module that might not be loaded into the VM (for example, maps need `std.map`): these modules are
+/// loaded first if they're missing. Userdata types must already be registered with the VM.
+///
+pub fn make_gluon_type<T: VmType>(vm: &Thread) -> FloScriptResult<ArcType> {
+    load_type_modules(vm)?;
+
+    Ok(T::make_type(vm))
+}
+
+///
+/// True if two Gluon types are the same type
+///
+/// Gluon creates new symbols for the field names each time it generates a record or tuple type, and compares symbols by
+/// identity, so the same type can compare as different. Types with the same name are treated as the same type here.
+///
+pub (crate) fn same_gluon_type(a: &ArcType, b: &ArcType) -> bool {
+    a == b || a.to_string() == b.to_string()
+}
+
+///
+/// Finds the type description to use when a script reads a computing script with the specified type (and no declared type)
+///
+pub (crate) fn default_type_description(script_type: &ArcType, vm: &Thread) -> FloScriptResult<ScriptTypeDescription> {
+    for description in &[bool::description(), i64::description(), f64::description(), String::description()] {
+        if &description.vm_type(vm)? == script_type {
+            return Ok(description.clone());
+        }
+    }
+
+    Err(FloScriptError::UnsupportedType(format!("{} (declare the type of the script so other scripts can read it)", script_type)))
+}
+
+///
+/// Creates the error for reading a script that produces values of the specified Gluon type as a different type
+///
+/// Scripts are described by the type they're read as by default. Scripts producing other kinds of value can only be described
+/// by their Gluon type.
+///
+pub (crate) fn incorrect_script_type(script_type: &ArcType, requested: &ScriptTypeDescription, vm: &Thread) -> FloScriptError {
+    match default_type_description(script_type, vm) {
+        Ok(script_description)  => FloScriptError::incorrect_type(&script_description, requested),
+        Err(_)                  => FloScriptError::IncorrectType { expected: script_type.to_string(), requested: requested.to_string() }
+    }
+}
+
+///
+/// Loads the modules that the standard script types depend upon into a VM, if they're not already loaded
+///
+fn load_type_modules(vm: &Thread) -> FloScriptResult<()> {
+    for (module, module_type) in TYPE_MODULES.iter() {
+        if vm.find_type_info(module_type).is_err() {
+            Compiler::default().run_expr::<()>(vm, "load_type_modules", &format!("import! {}\n()", module))
+                .map_err(|error| FloScriptError::UnsupportedType(format!("{} could not be loaded: {}", module, error)))?;
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Generates the Gluon types that a computing script producing values of a Rust type can have
+///
+fn computing_script_types<T>(vm: &Thread) -> FloScriptResult<Vec<ArcType>>
+where   T:                                  VmType,
+        T::Type:                            Sized,
+        for<'vm> DerivedState<'vm, T>:      VmType {
+    Ok(vec![make_gluon_type::<T>(vm)?, make_gluon_type::<DerivedState<T>>(vm)?])
+}
+
+///
+/// Reads a symbol as a state stream of boxed values
+///
+fn read_any_state_stream<T: ScriptType>(namespace: &mut GluonScriptNamespace, symbol: FloScriptSymbol) -> FloScriptResult<AnyValueStream>
+where   T:                                  for<'vm, 'value> Getable<'vm, 'value> + VmType,
+        T::Type:                            Sized {
+    let stream = namespace.read_state_stream::<T>(symbol)?;
+
+    Ok(Box::new(stream
+        .map(|value| -> Box<dyn Any+Send> { Box::new(value) })
+        .map_err(move |()| FloScriptError::Unavailable(format!("{:?} stopped producing values", symbol)))))
+}
+
+///
+/// Reads a symbol as a state stream of boxed values, following it when it's redefined
+///
+fn follow_any_state_stream<T: ScriptType>(namespace: &Arc<Desync<GluonScriptNamespace>>, symbol: FloScriptSymbol) -> FloScriptResult<AnyValueStream>
+where   T:                                  for<'vm, 'value> Getable<'vm, 'value> + VmType,
+        T::Type:                            Sized {
+    let stream = FollowingStream::follow(namespace, symbol, GluonScriptNamespace::read_state_stream::<T>)?;
+
+    Ok(Box::new(stream.map(|value| -> Box<dyn Any+Send> { Box::new(value) })))
+}
+
+///
+/// Reads an input stream source as a stream of boxed values
+///
+fn read_any_input<T: ScriptType>(input_source: &mut InputStreamSource, state: bool) -> FloScriptResult<AnyInputStream> {
+    if state {
+        Ok(Box::new(input_source.read_as_state_stream::<T>()?.map(|value| -> Box<dyn Any+Send> { Box::new(value) })))
+    } else {
+        Ok(Box::new(input_source.read_as_stream::<T>()?.map(|value| -> Box<dyn Any+Send> { Box::new(value) })))
+    }
+}
+
+///
+/// Copies a boxed value
+///
+fn clone_any<T: ScriptType>(value: &(dyn Any+Send)) -> Box<dyn Any+Send> {
+    Box::new(value.downcast_ref::<T>().expect("value of the described type").clone())
+}
+
+///
+/// Pushes a boxed value onto the stack of a Gluon thread
+///
+fn push_any<'vm, T>(value: &(dyn Any+Send), context: &mut ActiveThread<'vm>) -> vm::Result<()>
+where T: ScriptType + for<'a> Pushable<'a> {
+    match value.downcast_ref::<T>() {
+        Some(value) => value.clone().push(context),
+        None        => Err(vm::Error::Message(format!("Expected a value of type {}", type_name::<T>())))
+    }
+}
+
+///
+/// Generates the Gluon type for a Rust type, for use in its description
+///
+fn gluon_type<T: 'static+VmType>() -> Option<String> 
+where T::Type: Sized {
+    // Types are only generated once, as make_gluon_type() can need to load modules
+    let type_id = TypeId::of::<T>();
+
+    if let Some(gluon_type) = GLUON_TYPE_NAMES.lock().unwrap().get(&type_id) {
+        return gluon_type.clone();
+    }
+
+    // Types registered with the VM (such as userdata) are looked up rather than generated
+    let gluon_type = match TYPE_DESCRIPTION_VM.get_type::<T::Type>() {
+        Some(registered_type)   => Some(registered_type_name(&registered_type)),
+        None                    => make_gluon_type::<T>(&TYPE_DESCRIPTION_VM).ok().map(|gluon_type| gluon_type.to_string())
+    };
+    GLUON_TYPE_NAMES.lock().unwrap().insert(type_id, gluon_type.clone());
+
+    gluon_type
+}
+
+///
+/// Describes a type that has been registered with a VM
+///
+/// Declared script types are registered the first time their Gluon type is generated, so they're described by their definition
+/// (which is what they'd be described by before they're registered). Other types, such as userdata, are described by name.
+///
+fn registered_type_name(registered_type: &ArcType) -> String {
+    match &**registered_type {
+        Type::Alias(alias) if !matches!(**alias.unresolved_type(), Type::Opaque)    => alias.unresolved_type().to_string(),
+        _                                                                           => registered_type.to_string()
     }
 }
 
 ///
 /// Trait implemented by things that can be used with a script
 ///
+/// Any type with Gluon `VmType`, `Getable` and `Pushable` implementations is a script type. This includes the primitive types,
+/// `String`, `Vec<T>`, `Option<T>`, tuples, `BTreeMap<String, T>`, `ScriptMap<T>` (a `HashMap<String, T>`, which can't
+/// implement the Gluon traits directly) and any struct or enum with `#[derive(ScriptType)]`. Gluon can only pass a `BTreeMap`
+/// to a script if it's compiled with its serialization feature, so inputs that scripts read as maps should use `ScriptMap`.
+///
+/// Any script type can be used for input symbols and for reading their values. Scripts can only produce or read values whose
+/// Gluon type is available in the namespace: types that aren't result in `FloScriptError::UnsupportedType`.
+///
 pub trait ScriptType : Any+Clone+Send {
     ///
     /// Creates or retrieves a description for this type
@@ -64,6 +435,16 @@ where   for<'vm, 'value> T: Any+VmType+Getable<'vm, 'value>+Pushable<'vm>+Sized+
         T::Type : Sized {
     fn description() -> ScriptTypeDescription {
         let type_id                 = TypeId::of::<T>();
+        let type_name               = type_name::<T>();
+        let gluon_type              = gluon_type::<T>();
+        let computing_script_types  = computing_script_types::<T>;
+        let vm_type                 = make_gluon_type::<T>;
+        let read_any_state_stream   = read_any_state_stream::<T>;
+        let follow_any_state_stream = follow_any_state_stream::<T>;
+        let read_any_input          = read_any_input::<T>;
+        let coercion                = value_coercion(type_id);
+        let clone_any               = clone_any::<T>;
+        let push_any                = push_any::<T>;
         let state_resolver          = userdata_derived_state_resolve::<T>;
         let derived_state_resolve   = Arc::new(move |symbol: FloScriptSymbol| {
             // Can't pass symbols directly to gluon at the moment, so get the ID
@@ -87,6 +468,16 @@ where   for<'vm, 'value> T: Any+VmType+Getable<'vm, 'value>+Pushable<'vm>+Sized+
 
         ScriptTypeDescription {
             type_id,
+            type_name,
+            gluon_type,
+            computing_script_types,
+            vm_type,
+            read_any_state_stream,
+            follow_any_state_stream,
+            read_any_input,
+            coercion,
+            clone_any,
+            push_any,
             derived_state_resolve
         }
     }
@@ -181,4 +572,27 @@ mod test {
     fn create_i32_type_description() {
         let _t: ScriptTypeDescription = i32::description();
     }
+
+    #[test]
+    fn describe_gluon_type() {
+        assert!(i32::description().gluon_type() == Some("Int"));
+        assert!(String::description().gluon_type() == Some("String"));
+    }
+
+    #[test]
+    fn display_type_description() {
+        assert!(i32::description().to_string() == "i32 (Int)");
+        assert!(format!("{:?}", f64::description()) == "ScriptTypeDescription { type_name: \"f64\", gluon_type: Some(\"Float\") }");
+    }
+
+    #[test]
+    fn coercion_rules() {
+        assert!(i32::description().can_coerce_to(&f64::description()));
+        assert!(f32::description().can_coerce_to(&u64::description()));
+        assert!(bool::description().can_coerce_to(&String::description()));
+        assert!(bool::description().can_coerce_to(&bool::description()));
+        assert!(!bool::description().can_coerce_to(&i32::description()));
+        assert!(!String::description().can_coerce_to(&f64::description()));
+        assert!(!<Vec<i32>>::description().can_coerce_to(&String::description()));
+    }
 }
\ No newline at end of file
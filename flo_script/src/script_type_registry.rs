@@ -0,0 +1,99 @@
+use super::error::*;
+use super::script_type_description::*;
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+///
+/// Maps the names of script types to their descriptions and back again
+///
+/// Script types are identified in saved notebooks by the name they're registered with here, so any type used as an input
+/// type or as the declared type of a script must be registered before a notebook that uses it can be saved or loaded. The
+/// names are chosen when the type is registered, so they stay the same if the Rust type is renamed or moved.
+///
+#[derive(Clone, Debug)]
+pub struct ScriptTypeRegistry {
+    /// The known types, indexed by name
+    types: HashMap<String, ScriptTypeDescription>,
+
+    /// The name that each type was registered with
+    names: HashMap<TypeId, String>
+}
+
+impl ScriptTypeRegistry {
+    ///
+    /// Creates a new registry with no types in it
+    ///
+    pub fn new() -> ScriptTypeRegistry {
+        ScriptTypeRegistry {
+            types: HashMap::new(),
+            names: HashMap::new()
+        }
+    }
+
+    ///
+    /// Creates a new registry containing the standard primitive types
+    ///
+    pub fn with_standard_types() -> ScriptTypeRegistry {
+        let mut registry = ScriptTypeRegistry::new();
+
+        registry.register::<bool>("bool");
+        registry.register::<i32>("i32");
+        registry.register::<u32>("u32");
+        registry.register::<i64>("i64");
+        registry.register::<u64>("u64");
+        registry.register::<f32>("f32");
+        registry.register::<f64>("f64");
+        registry.register::<String>("String");
+
+        registry
+    }
+
+    ///
+    /// Adds a type to this registry, with the name that identifies it in saved notebooks
+    ///
+    /// Registering a type again replaces its name, and registering a different type with the same name replaces that type.
+    ///
+    pub fn register<T: ScriptType>(&mut self, name: &str) {
+        let description = T::description();
+
+        if let Some(old_name) = self.names.insert(description.type_id(), name.to_string()) {
+            self.types.remove(&old_name);
+        }
+
+        if let Some(old_type) = self.types.insert(name.to_string(), description) {
+            if old_type.type_id() != TypeId::of::<T>() {
+                self.names.remove(&old_type.type_id());
+            }
+        }
+    }
+
+    ///
+    /// Retrieves the description of the type with the specified name
+    ///
+    pub fn type_with_name(&self, name: &str) -> Option<ScriptTypeDescription> {
+        self.types.get(name).cloned()
+    }
+
+    ///
+    /// Retrieves the name that a type was registered with
+    ///
+    pub fn name_of_type(&self, script_type: &ScriptTypeDescription) -> Option<&str> {
+        self.names.get(&script_type.type_id()).map(|name| &name[..])
+    }
+
+    ///
+    /// Retrieves the name that a type was registered with, or an error if it hasn't been registered
+    ///
+    pub fn registered_name(&self, script_type: &ScriptTypeDescription) -> FloScriptResult<String> {
+        self.name_of_type(script_type)
+            .map(|name| name.to_string())
+            .ok_or_else(|| FloScriptError::UnregisteredType(script_type.name().to_string()))
+    }
+}
+
+impl Default for ScriptTypeRegistry {
+    fn default() -> ScriptTypeRegistry {
+        ScriptTypeRegistry::new()
+    }
+}
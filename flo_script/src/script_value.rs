@@ -0,0 +1,254 @@
+use super::error::*;
+use super::script_type_description::*;
+
+use std::any::*;
+use std::convert::{TryFrom};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display};
+use std::result;
+
+///
+/// A literal value that can be assigned to a symbol
+///
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ScriptValue {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String)
+}
+
+///
+/// Converts a numeric script value to an integer type, failing if it's outside of the range of the type
+///
+/// Floating point values are rounded towards zero (as the `as` operator does), and can't be converted if they're not finite.
+///
+macro_rules! coerce_integer {
+    ($value: expr, $target: ty) => {
+        match $value {
+            ScriptValue::I32(number)    => <$target>::try_from(*number).map_err(|_| out_of_range::<$target>($value)),
+            ScriptValue::U32(number)    => <$target>::try_from(*number).map_err(|_| out_of_range::<$target>($value)),
+            ScriptValue::I64(number)    => <$target>::try_from(*number).map_err(|_| out_of_range::<$target>($value)),
+            ScriptValue::U64(number)    => <$target>::try_from(*number).map_err(|_| out_of_range::<$target>($value)),
+            ScriptValue::F32(number)    => float_to_integer!(f64::from(*number), $value, $target),
+            ScriptValue::F64(number)    => float_to_integer!(*number, $value, $target),
+            ScriptValue::Bool(_)        |
+            ScriptValue::String(_)      => Err(FloScriptError::incorrect_type(&$value.value_type(), &<$target>::description()))
+        }
+    }
+}
+
+///
+/// Converts a floating point number to an integer type if it's in range
+///
+macro_rules! float_to_integer {
+    ($number: expr, $value: expr, $target: ty) => {{
+        let number = $number.trunc();
+
+        // The maximum of the 64-bit types can't be represented exactly, but one more than it can
+        if number.is_finite() && number >= <$target>::MIN as f64 && number < <$target>::MAX as f64 + 1.0 {
+            Ok(number as $target)
+        } else {
+            Err(out_of_range::<$target>($value))
+        }
+    }}
+}
+
+///
+/// Converts a numeric script value to a floating point type, failing if it's a finite number too large for the type
+///
+/// Integers are always in range, but can lose precision when they're converted.
+///
+macro_rules! coerce_float {
+    ($value: expr, $target: ty) => {
+        match $value {
+            ScriptValue::I32(number)    => Ok(*number as $target),
+            ScriptValue::U32(number)    => Ok(*number as $target),
+            ScriptValue::I64(number)    => Ok(*number as $target),
+            ScriptValue::U64(number)    => Ok(*number as $target),
+            ScriptValue::F32(number)    => Ok(*number as $target),
+            ScriptValue::F64(number)    => {
+                if number.is_finite() && number.abs() > <$target>::MAX as f64 {
+                    Err(out_of_range::<$target>($value))
+                } else {
+                    Ok(*number as $target)
+                }
+            }
+            ScriptValue::Bool(_)        |
+            ScriptValue::String(_)      => Err(FloScriptError::incorrect_type(&$value.value_type(), &<$target>::description()))
+        }
+    }
+}
+
+///
+/// The error returned when a value is outside of the range of the type it's being converted to
+///
+fn out_of_range<Target: ScriptType>(value: &ScriptValue) -> FloScriptError {
+    FloScriptError::OutOfRange { value: value.to_string(), requested: Target::description().to_string() }
+}
+
+///
+/// How the values of a type that a `ScriptValue` can represent are converted when they're read with coercion
+///
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CoercionKind {
+    /// A number, which can be converted to or from any other numeric type
+    Numeric,
+
+    /// A string, which any value that a `ScriptValue` can represent can be converted to (using its display form)
+    Text,
+
+    /// A value that can only be converted to a string
+    Displayable
+}
+
+///
+/// The coercion rule declared for a type that a `ScriptValue` can represent
+///
+#[derive(Copy, Clone)]
+pub (crate) struct ValueCoercion {
+    /// How values of the type can be converted
+    pub kind: CoercionKind,
+
+    /// Converts a (boxed) value of the type to a script value
+    pub to_value: fn(&(dyn Any+Send)) -> Option<ScriptValue>,
+
+    /// Converts a script value to a script value holding the type
+    pub from_value: fn(&ScriptValue) -> FloScriptResult<ScriptValue>
+}
+
+lazy_static! {
+    /// The coercion rules for each of the types that a `ScriptValue` can represent
+    static ref VALUE_COERCIONS: HashMap<TypeId, ValueCoercion> = {
+        use self::CoercionKind::*;
+
+        let mut coercions = HashMap::new();
+
+        declare_coercion::<bool>(&mut coercions, Displayable,   |value| if let ScriptValue::Bool(value) = value { Ok(ScriptValue::Bool(*value)) } else { Err(FloScriptError::incorrect_type(&value.value_type(), &bool::description())) });
+        declare_coercion::<i32>(&mut coercions, Numeric,        |value| coerce_integer!(value, i32).map(ScriptValue::I32));
+        declare_coercion::<u32>(&mut coercions, Numeric,        |value| coerce_integer!(value, u32).map(ScriptValue::U32));
+        declare_coercion::<i64>(&mut coercions, Numeric,        |value| coerce_integer!(value, i64).map(ScriptValue::I64));
+        declare_coercion::<u64>(&mut coercions, Numeric,        |value| coerce_integer!(value, u64).map(ScriptValue::U64));
+        declare_coercion::<f32>(&mut coercions, Numeric,        |value| coerce_float!(value, f32).map(ScriptValue::F32));
+        declare_coercion::<f64>(&mut coercions, Numeric,        |value| coerce_float!(value, f64).map(ScriptValue::F64));
+        declare_coercion::<String>(&mut coercions, Text,        |value| Ok(ScriptValue::String(value.to_string())));
+
+        coercions
+    };
+}
+
+///
+/// Declares how a type that a `ScriptValue` can represent is converted when it's read with coercion
+///
+fn declare_coercion<T: 'static+Clone+Into<ScriptValue>>(coercions: &mut HashMap<TypeId, ValueCoercion>, kind: CoercionKind, from_value: fn(&ScriptValue) -> FloScriptResult<ScriptValue>) {
+    coercions.insert(TypeId::of::<T>(), ValueCoercion { kind, to_value: to_script_value::<T>, from_value });
+}
+
+///
+/// Converts a boxed value to a script value
+///
+fn to_script_value<T: 'static+Clone+Into<ScriptValue>>(value: &(dyn Any+Send)) -> Option<ScriptValue> {
+    value.downcast_ref::<T>().map(|value| value.clone().into())
+}
+
+///
+/// Retrieves the coercion rule for a type, if it's one that a `ScriptValue` can represent
+///
+pub (crate) fn value_coercion(type_id: TypeId) -> Option<ValueCoercion> {
+    VALUE_COERCIONS.get(&type_id).cloned()
+}
+
+impl ScriptValue {
+    ///
+    /// Retrieves the type of this value
+    ///
+    pub fn value_type(&self) -> ScriptTypeDescription {
+        use self::ScriptValue::*;
+
+        match self {
+            Bool(_)     => bool::description(),
+            I32(_)      => i32::description(),
+            U32(_)      => u32::description(),
+            I64(_)      => i64::description(),
+            U64(_)      => u64::description(),
+            F32(_)      => f32::description(),
+            F64(_)      => f64::description(),
+            String(_)   => <std::string::String>::description()
+        }
+    }
+
+    ///
+    /// Converts this value to the specified type, following the rules described by `ScriptTypeDescription::can_coerce_to()`
+    ///
+    /// Numbers can be converted to any other numeric type that can represent them: floating point numbers are rounded towards
+    /// zero when they're converted to an integer, and integers can lose precision when they're converted to a floating point
+    /// type. Numbers outside of the range of the target type produce an `OutOfRange` error. Any value can be converted to a
+    /// string, using its display form. Values that can't be converted at all produce an `IncorrectType` error.
+    ///
+    pub fn coerce_to(&self, target: &ScriptTypeDescription) -> FloScriptResult<ScriptValue> {
+        target.coerce_value(self)
+    }
+
+    ///
+    /// Converts this value to a Rust value of the specified type, following the same rules as `coerce_to()`
+    ///
+    pub fn coerce_into<T: ScriptType>(&self) -> FloScriptResult<T> {
+        let target = T::description();
+
+        self.coerce_to(&target)?
+            .into_type::<T>()
+            .ok_or_else(|| FloScriptError::incorrect_type(&self.value_type(), &target))
+    }
+
+    ///
+    /// Retrieves the value as a Rust value, if it has the specified type
+    ///
+    pub fn into_type<T: 'static>(self) -> Option<T> {
+        use self::ScriptValue::*;
+
+        let value: Box<dyn Any> = match self {
+            Bool(value)     => Box::new(value),
+            I32(value)      => Box::new(value),
+            U32(value)      => Box::new(value),
+            I64(value)      => Box::new(value),
+            U64(value)      => Box::new(value),
+            F32(value)      => Box::new(value),
+            F64(value)      => Box::new(value),
+            String(value)   => Box::new(value)
+        };
+
+        value.downcast().ok().map(|value| *value)
+    }
+}
+
+impl Display for ScriptValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> result::Result<(), fmt::Error> {
+        use self::ScriptValue::*;
+
+        match self {
+            Bool(value)     => write!(formatter, "{}", value),
+            I32(value)      => write!(formatter, "{}", value),
+            U32(value)      => write!(formatter, "{}", value),
+            I64(value)      => write!(formatter, "{}", value),
+            U64(value)      => write!(formatter, "{}", value),
+            F32(value)      => write!(formatter, "{}", value),
+            F64(value)      => write!(formatter, "{}", value),
+            String(value)   => write!(formatter, "{}", value)
+        }
+    }
+}
+
+impl From<bool> for ScriptValue    { fn from(value: bool) -> ScriptValue    { ScriptValue::Bool(value) } }
+impl From<i32> for ScriptValue     { fn from(value: i32) -> ScriptValue     { ScriptValue::I32(value) } }
+impl From<u32> for ScriptValue     { fn from(value: u32) -> ScriptValue     { ScriptValue::U32(value) } }
+impl From<i64> for ScriptValue     { fn from(value: i64) -> ScriptValue     { ScriptValue::I64(value) } }
+impl From<u64> for ScriptValue     { fn from(value: u64) -> ScriptValue     { ScriptValue::U64(value) } }
+impl From<f32> for ScriptValue     { fn from(value: f32) -> ScriptValue     { ScriptValue::F32(value) } }
+impl From<f64> for ScriptValue     { fn from(value: f64) -> ScriptValue     { ScriptValue::F64(value) } }
+impl From<String> for ScriptValue  { fn from(value: String) -> ScriptValue  { ScriptValue::String(value) } }
+impl<'a> From<&'a str> for ScriptValue { fn from(value: &'a str) -> ScriptValue { ScriptValue::String(value.to_string()) } }
@@ -1,5 +1,7 @@
 use futures::*;
 use futures::task::Task;
+use futures::executor;
+use futures::executor::{Notify, NotifyHandle};
 use desync::Desync;
 
 use std::usize;
@@ -10,6 +12,17 @@ use std::collections::{HashMap, VecDeque};
 /// The default max buffer size for an input stream core
 const DEFAULT_MAX_BUFFER_SIZE: usize = 256;
 
+///
+/// Wakes the task that polled an input stream when its source stream is ready
+///
+struct NotifyTask(Task);
+
+impl Notify for NotifyTask {
+    fn notify(&self, _id: usize) {
+        self.0.notify();
+    }
+}
+
 ///
 /// The data for a single stream generating output from this input
 ///
@@ -196,7 +209,10 @@ impl<Symbol: 'static+Clone+Send, Source: 'static+Send+Stream<Item=Symbol, Error=
     /// 
     /// Returns (new_data_available, stream_finished)
     ///
-    fn drain_stream(buffer_to: &mut StreamBuffers<Symbol, Source>, max_buffer_size: usize) -> (bool, bool, Option<Symbol>) {
+    /// The source stream is polled on behalf of the task that's reading from this input: this might not be the current task,
+    /// as the buffers can be accessed from another thread.
+    ///
+    fn drain_stream(buffer_to: &mut StreamBuffers<Symbol, Source>, max_buffer_size: usize, poll_task: &Task) -> (bool, bool, Option<Symbol>) {
         // Determine the maximum number of symbols to load for the streams
         let stream                      = buffer_to.source_stream.as_mut();
 
@@ -209,13 +225,17 @@ impl<Symbol: 'static+Clone+Send, Source: 'static+Send+Stream<Item=Symbol, Error=
             let mut stream_finished     = false;
             let mut last_symbol         = None;
 
+            let notify                  = NotifyHandle::from(Arc::new(NotifyTask(poll_task.clone())));
+            let mut stream              = executor::spawn(stream);
+
             loop {
                 // Stop once any of the receiving streams has a fullybuffer
                 if remaining_symbols <= 0 { break; }
 
                 // Poll for the next symbol until the stream finishes or indicates it's not ready
-                match stream.poll() {
-                    Err(())                             => { break; }
+                match stream.poll_stream_notify(&notify, 0) {
+                    // A stream that fails won't produce any more symbols (the owner of the stream reports why it failed)
+                    Err(())                             => { stream_finished = true; break; }
                     Ok(Async::NotReady)                 => { break; }
                     Ok(Async::Ready(None))              => { stream_finished = true; break; }
 
@@ -323,7 +343,7 @@ impl<Symbol: 'static+Clone+Send, Source: 'static+Send+Stream<Item=Symbol, Error=
 
             // Read as many symbols as we can from the source stream and buffer them (this avoids too much round-robin signalling)
             let max_buffer_size                                 = self.max_buffer_size; 
-            let (new_data_available, finished, new_last_symbol) = Self::drain_stream(buffers, max_buffer_size);
+            let (new_data_available, finished, new_last_symbol) = Self::drain_stream(buffers, max_buffer_size, &task);
 
             // Update the last symbol if there's a new one
             new_last_symbol.map(|new_last_symbol| self.update_last_symbol(new_last_symbol, stream_id, buffers));
@@ -381,7 +401,7 @@ impl<Symbol: 'static+Clone+Send, Source: 'static+Send+Stream<Item=Symbol, Error=
 
             // Read as many symbols as we can from the source stream and buffer them (this avoids too much round-robin signalling)
             let max_buffer_size                                 = self.max_buffer_size; 
-            let (new_data_available, finished, new_last_symbol) = Self::drain_stream(buffers, max_buffer_size);
+            let (new_data_available, finished, new_last_symbol) = Self::drain_stream(buffers, max_buffer_size, &poll_task);
 
             // Update the last symbol if there's a new one
             new_last_symbol.map(|new_last_symbol| self.update_last_symbol(new_last_symbol, stream_id, buffers));
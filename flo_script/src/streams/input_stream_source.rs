@@ -2,6 +2,7 @@ use super::input_stream::*;
 use super::state_stream::*;
 use super::input_stream_core::*;
 use super::super::script_type_description::*;
+use super::super::script_value::*;
 use super::super::error::*;
 
 use futures::*;
@@ -9,6 +10,9 @@ use futures::*;
 use std::any::*;
 use std::sync::*;
 
+/// A stream of values converted to another type, where values that can't be converted are returned as errors
+pub type CoercedStream<Target> = Box<dyn Stream<Item=FloScriptResult<Target>, Error=()>+Send>;
+
 ///
 /// A structure representing an input stream for a script (provides a possible way to implement a typed input stream for a script host)
 ///
@@ -18,7 +22,10 @@ pub struct InputStreamSource {
     input_symbol_type: ScriptTypeDescription,
 
     /// The stream core object (if it's been attached)
-    stream_core: Option<Arc<dyn Any+Send+Sync>>
+    stream_core: Option<Arc<dyn Any+Send+Sync>>,
+
+    /// True if an input stream has been attached to this source
+    has_source: bool
 }
 
 impl InputStreamSource {
@@ -28,17 +35,32 @@ impl InputStreamSource {
     pub fn new(input_symbol_type: ScriptTypeDescription) -> InputStreamSource {
         InputStreamSource {
             input_symbol_type:  input_symbol_type,
-            stream_core:        None
+            stream_core:        None,
+            has_source:         false
         }
     }
 
+    ///
+    /// Retrieves the type of symbol accepted by this input stream
+    ///
+    pub fn input_type(&self) -> &ScriptTypeDescription {
+        &self.input_symbol_type
+    }
+
+    ///
+    /// True if an input stream has been attached to this source
+    ///
+    pub fn has_source(&self) -> bool {
+        self.has_source
+    }
+
     ///
     /// Retrieves a reference to the core of this stream source, if available
     ///
     fn core<SymbolType: 'static+ScriptType>(&mut self) -> FloScriptResult<Arc<InputStreamCore<SymbolType, Box<dyn Stream<Item=SymbolType, Error=()>+Send>>>> {
         // Make sure we don't try to create a core of the wrong type
         if !self.input_symbol_type.is::<SymbolType>() {
-            return Err(FloScriptError::IncorrectType)
+            return Err(FloScriptError::incorrect_type(&self.input_symbol_type, &SymbolType::description()))
         }
 
         // Fetch the stream core
@@ -52,7 +74,7 @@ impl InputStreamSource {
         if let Ok(stream_core) = Arc::clone(&stream_core).downcast() {
             Ok(stream_core)
         } else {
-            Err(FloScriptError::IncorrectType)
+            Err(FloScriptError::incorrect_type(&self.input_symbol_type, &SymbolType::description()))
         }
     }
 
@@ -63,10 +85,20 @@ impl InputStreamSource {
     where SymbolStream::Item: 'static+ScriptType {
         // Replace the stream in the core with the new one that has been passed in
         self.core()?.replace_stream(Box::new(input_stream));
+        self.has_source = true;
 
         Ok(())
     } 
 
+    ///
+    /// Sets this script input to a single value, which stays its value until something else is attached
+    ///
+    /// The stream returns the value and then waits forever, so that readers stay around in case the value is changed.
+    ///
+    pub fn attach_constant<SymbolType: 'static+ScriptType>(&mut self, value: SymbolType) -> FloScriptResult<()> {
+        self.attach(stream::once(Ok(value)).chain(stream::poll_fn(|| Ok(Async::NotReady))))
+    }
+
     ///
     /// Creates a new stream reader for this input source
     ///
@@ -88,4 +120,60 @@ impl InputStreamSource {
 
         Ok(new_stream)
     }
+
+    ///
+    /// Creates a new stream reader for this input source that converts its values to the specified type
+    ///
+    /// The rules for converting values are described by `ScriptTypeDescription::can_coerce_to()`. If this source already
+    /// has the target type, this is the same as `read_as_stream()`. Values that are out of the range of the target type are
+    /// returned as errors.
+    ///
+    pub fn read_as_coerced_stream<Target: 'static+ScriptType>(&mut self) -> FloScriptResult<CoercedStream<Target>> {
+        if self.input_symbol_type.is::<Target>() {
+            Ok(Box::new(self.read_as_stream::<Target>()?.map(Ok)))
+        } else {
+            self.coerce_values(false)
+        }
+    }
+
+    ///
+    /// Creates a new state stream reader for this input source that converts its values to the specified type
+    ///
+    pub fn read_as_coerced_state_stream<Target: 'static+ScriptType>(&mut self) -> FloScriptResult<CoercedStream<Target>> {
+        if self.input_symbol_type.is::<Target>() {
+            Ok(Box::new(self.read_as_state_stream::<Target>()?.map(Ok)))
+        } else {
+            self.coerce_values(true)
+        }
+    }
+
+    ///
+    /// Reads the values from this source as script values, and converts them to the target type
+    ///
+    fn coerce_values<Target: 'static+ScriptType>(&mut self, state: bool) -> FloScriptResult<CoercedStream<Target>> {
+        let target = Target::description();
+
+        if !self.input_symbol_type.can_coerce_to(&target) {
+            return Err(FloScriptError::incorrect_type(&self.input_symbol_type, &target));
+        }
+
+        let values = self.read_as_script_values(state)?;
+        let values = values.map(move |value| value.coerce_into::<Target>());
+
+        Ok(Box::new(values))
+    }
+
+    ///
+    /// Reads the values from this source as script values (which is possible if its type is one of the types that a script value can represent)
+    ///
+    fn read_as_script_values(&mut self, state: bool) -> FloScriptResult<Box<dyn Stream<Item=ScriptValue, Error=()>+Send>> {
+        let input_type = self.input_symbol_type.clone();
+
+        if !input_type.is_script_value() {
+            return Err(FloScriptError::UnsupportedType(input_type.name().to_string()));
+        }
+
+        let values = input_type.read_any_input(self, state)?;
+        Ok(Box::new(values.filter_map(move |value| input_type.to_script_value(&*value))))
+    }
 }
@@ -1,13 +1,17 @@
-use std::sync::*;
-use std::collections::HashMap;
+use super::symbol_table::*;
+
 use std::sync::atomic::{Ordering, AtomicU64};
 
 lazy_static! {
     static ref NEXT_SYMBOL:     AtomicU64 = AtomicU64::new(0);
-    static ref NAME_TO_SYMBOL:  Mutex<HashMap<String, u64>> = Mutex::new(HashMap::new());
-    static ref SYMBOL_TO_NAME:  Mutex<HashMap<u64, String>> = Mutex::new(HashMap::new());
 }
 
+/// The number of bits of a symbol ID that are assigned to each symbol (the remaining bits identify the table that names the symbol)
+const SYMBOL_ID_BITS: u32 = 40;
+
+/// The largest ID that a symbol table can have
+pub (crate) const MAX_TABLE_ID: u64 = (1 << (64 - SYMBOL_ID_BITS)) - 1;
+
 ///
 /// An abstract representation of a symbol in a script
 ///
@@ -18,10 +22,16 @@ pub struct FloScriptSymbol {
 }
 
 ///
-/// Assigns a new symbol ID
+/// Assigns a new symbol ID, for a symbol named by the symbol table with the specified ID (0 for anonymous symbols)
 ///
-fn assign_symbol_id() -> u64 {
-    NEXT_SYMBOL.fetch_add(1, Ordering::Relaxed)
+fn assign_symbol_id(table_id: u64) -> u64 {
+    let symbol_id = NEXT_SYMBOL.fetch_add(1, Ordering::Relaxed);
+
+    // Symbols would share IDs if either part overflowed, so there's no way to continue
+    assert!(symbol_id < (1 << SYMBOL_ID_BITS), "Too many symbols have been created (symbol IDs are limited to {} bits)", SYMBOL_ID_BITS);
+    assert!(table_id <= MAX_TABLE_ID, "Symbol table ID {} is too large (table IDs are limited to {})", table_id, MAX_TABLE_ID);
+
+    (table_id << SYMBOL_ID_BITS) | symbol_id
 }
 
 impl FloScriptSymbol {
@@ -29,37 +39,36 @@ impl FloScriptSymbol {
     /// Creates a new symbol (with no name, so it cannot be referenced within a script)
     ///
     pub fn new() -> FloScriptSymbol {
-        let symbol_id = assign_symbol_id();
+        let symbol_id = assign_symbol_id(0);
         FloScriptSymbol {
             id: symbol_id
         }
     }
 
     ///
-    /// Retrieves the symbol with the specified name
+    /// Creates a new symbol to be named by the symbol table with the specified ID
     ///
-    pub fn with_name(name: &str) -> FloScriptSymbol {
-        let mut name_to_symbol = NAME_TO_SYMBOL.lock().unwrap();
-
-        if let Some(existing_id) = name_to_symbol.get(name) {
-            // This name is already in use
-            FloScriptSymbol {
-                id: *existing_id
-            }
-        } else {
-            // Assign a new symbol ID for the name
-            let mut symbol_to_name  = SYMBOL_TO_NAME.lock().unwrap();
-            let symbol_id           = assign_symbol_id();
+    pub (crate) fn in_table(table_id: u64) -> FloScriptSymbol {
+        FloScriptSymbol {
+            id: assign_symbol_id(table_id)
+        }
+    }
 
-            // Store so future requests retrieve this name
-            name_to_symbol.insert(name.to_string(), symbol_id);
-            symbol_to_name.insert(symbol_id, name.to_string());
+    ///
+    /// Retrieves the ID of the symbol table that names this symbol (0 if this symbol was created without a name)
+    ///
+    pub (crate) fn table_id(&self) -> u64 {
+        self.id >> SYMBOL_ID_BITS
+    }
 
-            // Return this symbol
-            FloScriptSymbol {
-                id: symbol_id
-            }
-        }
+    ///
+    /// Retrieves the symbol with the specified name from the default symbol table
+    ///
+    /// Names in the default table are never freed: use a `FloScriptSymbolTable` to create symbols that can be
+    /// released when they're no longer needed.
+    ///
+    pub fn with_name(name: &str) -> FloScriptSymbol {
+        FloScriptSymbolTable::default_table().pinned_symbol(name)
     }
 
     ///
@@ -74,15 +83,16 @@ impl FloScriptSymbol {
     ///
     /// Retrieves the name of this symbol, if it's a named symbol
     ///
+    /// Symbols from any symbol table can be named here, provided that their name has not been released and the table still exists.
+    ///
     pub fn name(&self) -> Option<String> {
-        let symbol_to_name = SYMBOL_TO_NAME.lock().unwrap();
-        symbol_to_name.get(&self.id).cloned()
+        FloScriptSymbolTable::for_symbol(*self)?.name(*self)
     }
 
     ///
     /// Retrieves a number representing the unique ID of this symbol
     /// 
-    /// Note that symbol IDs are only valid for a single session
+    /// Note that symbol IDs are only valid for a single session: use `key()` to retrieve an identity that can be saved
     ///
     pub fn id(&self) -> u64 {
         self.id
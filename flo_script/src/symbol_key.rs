@@ -0,0 +1,91 @@
+use super::symbol::*;
+use super::symbol_table::*;
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::hash::{BuildHasher, Hasher};
+use std::collections::hash_map::{RandomState};
+use std::sync::atomic::{Ordering, AtomicU64};
+
+lazy_static! {
+    static ref NEXT_KEY_SEED: AtomicU64 = AtomicU64::new(0);
+}
+
+///
+/// A stable identity for a symbol, which can be saved and used to find the same symbol in a later session
+///
+/// Named symbols are identified by their name. Anonymous symbols are identified by a 128-bit key made from a random
+/// value chosen by a symbol table and the ID of the symbol, which is then used to restore them.
+///
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum FloScriptSymbolKey {
+    /// A symbol identified by its name
+    Named(String),
+
+    /// An anonymous symbol identified by a random key
+    Anonymous(u128)
+}
+
+impl FloScriptSymbolKey {
+    ///
+    /// Generates a new random key for an anonymous symbol
+    ///
+    pub fn new_anonymous() -> FloScriptSymbolKey {
+        FloScriptSymbolKey::Anonymous(random_key())
+    }
+
+    ///
+    /// Retrieves the symbol this key refers to in a symbol table (named symbols are retained in the table)
+    ///
+    pub fn symbol(&self, symbols: &FloScriptSymbolTable) -> FloScriptSymbol {
+        symbols.symbol_for_key(self)
+    }
+}
+
+///
+/// Generates a random 128-bit key
+///
+pub (crate) fn random_key() -> u128 {
+    // RandomState is seeded randomly, so hashing a unique seed and the current time produces a key that is very unlikely to collide
+    let seed        = NEXT_KEY_SEED.fetch_add(1, Ordering::Relaxed);
+    let time        = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos()).unwrap_or(0);
+    let random      = RandomState::new();
+
+    let mut high    = random.build_hasher();
+    high.write_u64(seed);
+    high.write_u128(time);
+
+    let mut low     = random.build_hasher();
+    low.write_u64(!seed);
+    low.write_u128(time);
+
+    ((high.finish() as u128) << 64) | (low.finish() as u128)
+}
+
+impl FloScriptSymbol {
+    ///
+    /// Retrieves the stable identity of this symbol, using the default symbol table to assign keys to anonymous symbols
+    ///
+    pub fn key(&self) -> FloScriptSymbolKey {
+        FloScriptSymbolTable::default_table().key_for(*self)
+    }
+
+    ///
+    /// Retrieves the symbol with the specified stable identity from the default symbol table
+    ///
+    pub fn with_key(key: &FloScriptSymbolKey) -> FloScriptSymbol {
+        match key {
+            FloScriptSymbolKey::Named(name)     => FloScriptSymbol::with_name(name),
+            FloScriptSymbolKey::Anonymous(_)    => FloScriptSymbolTable::default_table().symbol_for_key(key)
+        }
+    }
+}
+
+impl fmt::Display for FloScriptSymbolKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloScriptSymbolKey::Named(name)     => write!(formatter, "{}", name),
+            FloScriptSymbolKey::Anonymous(key)  => write!(formatter, "<{:032x}>", key)
+        }
+    }
+}
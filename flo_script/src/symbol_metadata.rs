@@ -0,0 +1,94 @@
+///
+/// Suggests the kind of control that a user interface should use to edit or display a symbol
+///
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum DisplayHint {
+    /// A slider covering a range of values, with an optional step between values
+    Slider { min: f64, max: f64, step: Option<f64> },
+
+    /// A colour picker
+    ColourPicker,
+
+    /// A checkbox, for boolean values
+    Checkbox,
+
+    /// A single-line text field
+    TextField,
+
+    /// A multi-line text area
+    TextArea,
+
+    /// A control that's specific to the application, identified by name
+    Custom(String)
+}
+
+///
+/// Describes a symbol for the benefit of a user interface
+///
+/// Metadata doesn't affect how scripts are evaluated: it's kept alongside the definition of the symbol so that
+/// a user interface can be generated from the notebook itself.
+///
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct SymbolMetadata {
+    /// The label to display for this symbol
+    pub label: Option<String>,
+
+    /// A longer description of what this symbol is for
+    pub description: Option<String>,
+
+    /// The units of the values of this symbol (for example, `px` or `degrees`)
+    pub units: Option<String>,
+
+    /// The preferred control for this symbol
+    pub display_hint: Option<DisplayHint>,
+
+    /// Tags that can be used to group or filter symbols
+    pub tags: Vec<String>
+}
+
+impl SymbolMetadata {
+    ///
+    /// Creates a new, empty, set of metadata
+    ///
+    pub fn new() -> SymbolMetadata {
+        SymbolMetadata::default()
+    }
+
+    ///
+    /// Sets the label for the symbol
+    ///
+    pub fn with_label(self, label: &str) -> SymbolMetadata {
+        SymbolMetadata { label: Some(label.to_string()), ..self }
+    }
+
+    ///
+    /// Sets the description for the symbol
+    ///
+    pub fn with_description(self, description: &str) -> SymbolMetadata {
+        SymbolMetadata { description: Some(description.to_string()), ..self }
+    }
+
+    ///
+    /// Sets the units for the symbol
+    ///
+    pub fn with_units(self, units: &str) -> SymbolMetadata {
+        SymbolMetadata { units: Some(units.to_string()), ..self }
+    }
+
+    ///
+    /// Sets the preferred control for the symbol
+    ///
+    pub fn with_display_hint(self, display_hint: DisplayHint) -> SymbolMetadata {
+        SymbolMetadata { display_hint: Some(display_hint), ..self }
+    }
+
+    ///
+    /// Adds a tag to the symbol
+    ///
+    pub fn with_tag(self, tag: &str) -> SymbolMetadata {
+        let mut tags = self.tags;
+        tags.push(tag.to_string());
+
+        SymbolMetadata { tags, ..self }
+    }
+}
@@ -0,0 +1,108 @@
+use super::error::*;
+use super::symbol::*;
+use super::symbol_table::*;
+use super::editor::*;
+
+use std::fmt;
+
+///
+/// The path to a symbol through a chain of namespaces (written as dotted names, such as `layers.background.opacity`)
+///
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FloScriptPath {
+    /// The namespaces containing the symbol, outermost first
+    namespaces: Vec<FloScriptSymbol>,
+
+    /// The symbol that this path refers to
+    symbol: FloScriptSymbol
+}
+
+impl FloScriptPath {
+    ///
+    /// Creates a path to a symbol within the specified namespaces (outermost first)
+    ///
+    pub fn new(namespaces: Vec<FloScriptSymbol>, symbol: FloScriptSymbol) -> FloScriptPath {
+        FloScriptPath {
+            namespaces,
+            symbol
+        }
+    }
+
+    ///
+    /// Parses a dotted name into a path, using the symbols from a particular symbol table (each part of the name is a symbol)
+    ///
+    /// Each symbol in the resulting path is retained in the table. Use the symbol table of the host that the path will be
+    /// used with (or `FloScriptSymbolTable::default_table()` for the symbols returned by `FloScriptSymbol::with_name()`).
+    ///
+    pub fn parse(symbols: &FloScriptSymbolTable, name: &str) -> FloScriptResult<FloScriptPath> {
+        if name.split('.').any(|part| part.is_empty()) {
+            return Err(FloScriptError::InvalidPath(name.to_string()));
+        }
+
+        let mut symbols = name.split('.')
+            .map(|part| symbols.symbol(part))
+            .collect::<Vec<_>>();
+
+        // The last part of the path is the symbol that it refers to ('split' always returns at least one item)
+        let symbol = symbols.pop().unwrap();
+
+        Ok(FloScriptPath::new(symbols, symbol))
+    }
+
+    ///
+    /// The namespaces containing the symbol, outermost first
+    ///
+    pub fn namespaces(&self) -> &[FloScriptSymbol] {
+        &self.namespaces
+    }
+
+    ///
+    /// The symbol that this path refers to
+    ///
+    pub fn symbol(&self) -> FloScriptSymbol {
+        self.symbol
+    }
+
+    ///
+    /// Returns all of the symbols in this path, ending with the symbol it refers to
+    ///
+    pub fn symbols(&self) -> Vec<FloScriptSymbol> {
+        self.namespaces.iter().cloned().chain(Some(self.symbol)).collect()
+    }
+
+    ///
+    /// Returns the path to the same symbol from the namespace that contains the namespaces of this path
+    ///
+    pub fn within(&self, namespace: FloScriptSymbol) -> FloScriptPath {
+        let namespaces = Some(namespace).into_iter().chain(self.namespaces.iter().cloned()).collect();
+
+        FloScriptPath::new(namespaces, self.symbol)
+    }
+
+    ///
+    /// Wraps an edit for the symbol at the end of this path in the edits needed to perform it in the right namespace
+    ///
+    /// Any namespaces that don't exist yet are created when the edit is performed.
+    ///
+    pub fn edit<EditFn: FnOnce(FloScriptSymbol) -> ScriptEdit>(&self, edit: EditFn) -> ScriptEdit {
+        self.namespaces.iter()
+            .rev()
+            .fold(edit(self.symbol), |edit, namespace| ScriptEdit::WithNamespace(*namespace, vec![edit]))
+    }
+}
+
+impl From<FloScriptSymbol> for FloScriptPath {
+    fn from(symbol: FloScriptSymbol) -> FloScriptPath {
+        FloScriptPath::new(vec![], symbol)
+    }
+}
+
+impl fmt::Display for FloScriptPath {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.symbols().into_iter()
+            .map(|symbol| symbol.name().unwrap_or_else(|| format!("<{}>", symbol.id())))
+            .collect::<Vec<_>>();
+
+        write!(formatter, "{}", names.join("."))
+    }
+}
@@ -0,0 +1,310 @@
+use super::symbol::*;
+use super::symbol_key::*;
+
+use std::sync::*;
+use std::sync::atomic::{Ordering, AtomicU64};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref NEXT_TABLE_ID:           AtomicU64 = AtomicU64::new(1);
+    static ref SYMBOL_TABLES:           Mutex<HashMap<u64, Weak<Mutex<SymbolTableCore>>>> = Mutex::new(HashMap::new());
+    static ref DEFAULT_SYMBOL_TABLE:    FloScriptSymbolTable = FloScriptSymbolTable::new();
+}
+
+///
+/// An entry for a named symbol in a symbol table
+///
+struct SymbolTableEntry {
+    /// The symbol that the name maps to
+    symbol: FloScriptSymbol,
+
+    /// The number of times this symbol has been retained
+    usages: usize,
+
+    /// Pinned symbols stay in the table even when they're released
+    pinned: bool
+}
+
+///
+/// The contents of a symbol table
+///
+struct SymbolTableCore {
+    /// The ID of this table (which is part of the ID of each symbol that it names)
+    table_id: u64,
+
+    /// Maps names to their symbols
+    name_to_symbol: HashMap<String, SymbolTableEntry>,
+
+    /// Maps the symbols in this table back to their names
+    symbol_to_name: HashMap<FloScriptSymbol, String>,
+
+    /// Random value that identifies this table in this session (the high part of the keys of anonymous symbols)
+    session_key: u64,
+
+    /// The keys of anonymous symbols that were restored from keys that this table didn't assign
+    anonymous_to_key: HashMap<FloScriptSymbol, u128>,
+
+    /// Maps the keys of restored anonymous symbols back to their symbols
+    key_to_anonymous: HashMap<u128, FloScriptSymbol>
+}
+
+///
+/// A table mapping names to symbols
+///
+/// Symbol tables are usually owned by a script host (or shared by the hosts in a single session), so the names they
+/// contain are freed when the session is finished. Entries are reference counted: `symbol()` and `retain()` add a
+/// usage and `release()` removes one, with the name being freed when it is no longer used.
+///
+/// `FloScriptSymbol::with_name()` uses a process-wide default table, whose entries are never freed. Symbols from
+/// different tables are always distinct, even if they have the same name. Each table stores its own names: the only
+/// state shared between tables is the list of tables that exist, which `FloScriptSymbol::name()` uses to find the table
+/// that named a symbol.
+///
+#[derive(Clone)]
+pub struct FloScriptSymbolTable {
+    /// The contents of this table
+    core: Arc<Mutex<SymbolTableCore>>
+}
+
+impl FloScriptSymbolTable {
+    ///
+    /// Creates a new, empty, symbol table
+    ///
+    pub fn new() -> FloScriptSymbolTable {
+        let table_id    = NEXT_TABLE_ID.fetch_add(1, Ordering::Relaxed);
+        assert!(table_id <= MAX_TABLE_ID, "Too many symbol tables have been created (table IDs are limited to {})", MAX_TABLE_ID);
+
+        let core        = Arc::new(Mutex::new(SymbolTableCore {
+            table_id,
+            session_key:        (random_key() >> 64) as u64,
+            name_to_symbol:     HashMap::new(),
+            symbol_to_name:     HashMap::new(),
+            anonymous_to_key:   HashMap::new(),
+            key_to_anonymous:   HashMap::new()
+        }));
+
+        SYMBOL_TABLES.lock().unwrap().insert(table_id, Arc::downgrade(&core));
+
+        FloScriptSymbolTable { core }
+    }
+
+    ///
+    /// Retrieves the table that named a symbol, if it still exists
+    ///
+    pub (crate) fn for_symbol(symbol: FloScriptSymbol) -> Option<FloScriptSymbolTable> {
+        // The table is upgraded after the list of tables is unlocked, as the table is unregistered if this is the last reference to it
+        let core = SYMBOL_TABLES.lock().unwrap().get(&symbol.table_id()).cloned()?;
+        let core = core.upgrade()?;
+
+        Some(FloScriptSymbolTable { core })
+    }
+
+    ///
+    /// Retrieves the default symbol table (used by `FloScriptSymbol::with_name()`)
+    ///
+    pub fn default_table() -> FloScriptSymbolTable {
+        DEFAULT_SYMBOL_TABLE.clone()
+    }
+
+    ///
+    /// Retrieves the symbol with the specified name, creating it if it's not already in the table
+    ///
+    /// This adds a usage to the symbol, which should be removed by calling `release()` when it's no longer needed.
+    ///
+    pub fn symbol(&self, name: &str) -> FloScriptSymbol {
+        self.core.lock().unwrap().symbol(name, false)
+    }
+
+    ///
+    /// Retrieves the symbol with the specified name, and marks it as never being freed
+    ///
+    pub (crate) fn pinned_symbol(&self, name: &str) -> FloScriptSymbol {
+        self.core.lock().unwrap().symbol(name, true)
+    }
+
+    ///
+    /// Finds the symbol with the specified name without adding a usage, if it's in this table
+    ///
+    pub fn find(&self, name: &str) -> Option<FloScriptSymbol> {
+        self.core.lock().unwrap()
+            .name_to_symbol.get(name)
+            .map(|entry| entry.symbol)
+    }
+
+    ///
+    /// Retrieves the name of a symbol in this table
+    ///
+    pub fn name(&self, symbol: FloScriptSymbol) -> Option<String> {
+        self.core.lock().unwrap()
+            .symbol_to_name.get(&symbol)
+            .cloned()
+    }
+
+    ///
+    /// Retrieves the stable identity of a symbol
+    ///
+    /// Named symbols are identified by their name. Anonymous symbols restored by `symbol_for_key()` keep the key they were
+    /// restored from until they're released. Other anonymous symbols are identified by a random value chosen by this table
+    /// combined with their ID, so this table doesn't need to store their keys.
+    ///
+    pub fn key_for(&self, symbol: FloScriptSymbol) -> FloScriptSymbolKey {
+        let (table_id, session_key) = {
+            let core = self.core.lock().unwrap();
+
+            if let Some(name) = core.symbol_to_name.get(&symbol) {
+                return FloScriptSymbolKey::Named(name.clone());
+            }
+
+            if let Some(key) = core.anonymous_to_key.get(&symbol) {
+                return FloScriptSymbolKey::Anonymous(*key);
+            }
+
+            (core.table_id, core.session_key)
+        };
+
+        // Naming a symbol from another table locks that table, so this table must be unlocked first
+        if symbol.table_id() != table_id {
+            if let Some(name) = symbol.name() {
+                return FloScriptSymbolKey::Named(name);
+            }
+        }
+
+        FloScriptSymbolKey::Anonymous(((session_key as u128) << 64) | (symbol.id() as u128))
+    }
+
+    ///
+    /// Retrieves the symbol with the specified stable identity
+    ///
+    /// Named symbols are retained as for `symbol()`. A new anonymous symbol is created if the key was not assigned by this
+    /// table in this session and has not already been restored.
+    ///
+    pub fn symbol_for_key(&self, key: &FloScriptSymbolKey) -> FloScriptSymbol {
+        match key {
+            FloScriptSymbolKey::Named(name)     => self.symbol(name),
+            FloScriptSymbolKey::Anonymous(key)  => {
+                let mut core = self.core.lock().unwrap();
+
+                if (key >> 64) as u64 == core.session_key {
+                    FloScriptSymbol::with_id(*key as u64)
+                } else if let Some(symbol) = core.key_to_anonymous.get(key) {
+                    *symbol
+                } else {
+                    let symbol = FloScriptSymbol::new();
+                    core.anonymous_to_key.insert(symbol, *key);
+                    core.key_to_anonymous.insert(*key, symbol);
+
+                    symbol
+                }
+            }
+        }
+    }
+
+    ///
+    /// Adds a usage to a symbol in this table
+    ///
+    pub fn retain(&self, symbol: FloScriptSymbol) {
+        let mut core = self.core.lock().unwrap();
+
+        if let Some(name) = core.symbol_to_name.get(&symbol).cloned() {
+            if let Some(entry) = core.name_to_symbol.get_mut(&name) {
+                entry.usages += 1;
+            }
+        }
+    }
+
+    ///
+    /// Removes a usage from a symbol in this table, freeing its name if it's no longer in use
+    ///
+    /// Anonymous symbols are not reference counted, so releasing one that was restored from a key frees that key immediately.
+    ///
+    pub fn release(&self, symbol: FloScriptSymbol) {
+        let mut core    = self.core.lock().unwrap();
+
+        if let Some(key) = core.anonymous_to_key.remove(&symbol) {
+            core.key_to_anonymous.remove(&key);
+            return;
+        }
+
+        let name        = if let Some(name) = core.symbol_to_name.get(&symbol) { name.clone() } else { return; };
+
+        let unused      = if let Some(entry) = core.name_to_symbol.get_mut(&name) {
+            entry.usages = entry.usages.saturating_sub(1);
+            entry.usages == 0 && !entry.pinned
+        } else {
+            false
+        };
+
+        if unused {
+            core.name_to_symbol.remove(&name);
+            core.symbol_to_name.remove(&symbol);
+        }
+    }
+
+    ///
+    /// The number of names in this table
+    ///
+    pub fn len(&self) -> usize {
+        self.core.lock().unwrap().name_to_symbol.len()
+    }
+
+    ///
+    /// True if there are no names in this table
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SymbolTableCore {
+    ///
+    /// Retrieves or creates a symbol, adding a usage to it
+    ///
+    fn symbol(&mut self, name: &str, pinned: bool) -> FloScriptSymbol {
+        if let Some(entry) = self.name_to_symbol.get_mut(name) {
+            // This name is already in use
+            entry.usages    += 1;
+            entry.pinned    |= pinned;
+
+            entry.symbol
+        } else {
+            // Assign a new symbol for the name
+            let symbol = FloScriptSymbol::in_table(self.table_id);
+
+            self.name_to_symbol.insert(name.to_string(), SymbolTableEntry { symbol, usages: 1, pinned });
+            self.symbol_to_name.insert(symbol, name.to_string());
+
+            symbol
+        }
+    }
+}
+
+impl Drop for SymbolTableCore {
+    fn drop(&mut self) {
+        // Names from this table are no longer available once it's gone
+        SYMBOL_TABLES.lock().unwrap().remove(&self.table_id);
+    }
+}
+
+///
+/// Adds a usage to a named symbol in the table that it belongs to, if that table still exists
+///
+pub (crate) fn retain_symbol(symbol: FloScriptSymbol) {
+    if let Some(table) = FloScriptSymbolTable::for_symbol(symbol) {
+        table.retain(symbol);
+    }
+}
+
+///
+/// Removes a usage from a named symbol in the table that it belongs to, if that table still exists
+///
+pub (crate) fn release_symbol(symbol: FloScriptSymbol) {
+    if let Some(table) = FloScriptSymbolTable::for_symbol(symbol) {
+        table.release(symbol);
+    }
+}
+
+impl Default for FloScriptSymbolTable {
+    fn default() -> FloScriptSymbolTable {
+        FloScriptSymbolTable::new()
+    }
+}
@@ -0,0 +1,215 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::Stream;
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn read_i32_input_as_f64() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1, 2, -3])).unwrap();
+
+    let output      = host.notebook().receive_coerced_output::<f64>(x).unwrap();
+    assert!(executor::spawn(output.collect()).wait_future() == Ok(vec![1.0, 2.0, -3.0]));
+}
+
+#[test]
+fn read_f64_input_as_i32() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<f64>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1.5, -2.7])).unwrap();
+
+    let output      = host.notebook().receive_coerced_output::<i32>(x).unwrap();
+    assert!(executor::spawn(output.collect()).wait_future() == Ok(vec![1, -2]));
+}
+
+#[test]
+fn read_numbers_as_strings() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<u64>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![42u64, 7])).unwrap();
+
+    let output      = host.notebook().receive_coerced_output::<String>(x).unwrap();
+    assert!(executor::spawn(output.collect()).wait_future() == Ok(vec!["42".to_string(), "7".to_string()]));
+}
+
+#[test]
+fn read_bool_as_string() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<bool>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![true])).unwrap();
+
+    let output      = host.notebook().receive_coerced_output::<String>(x).unwrap();
+    assert!(executor::spawn(output.collect()).wait_future() == Ok(vec!["true".to_string()]));
+}
+
+#[test]
+fn read_same_type_without_conversion() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<Vec<i32>>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![vec![1, 2]])).unwrap();
+
+    let output      = host.notebook().receive_coerced_output::<Vec<i32>>(x).unwrap();
+    assert!(executor::spawn(output.collect()).wait_future() == Ok(vec![vec![1, 2]]));
+}
+
+#[test]
+fn read_constant_as_other_numeric_type() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 42u32);
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output_state::<f32>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(42.0)));
+}
+
+#[test]
+fn cannot_coerce_string_to_number() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<String>(x);
+
+    assert!(host.notebook().receive_coerced_output::<f64>(x).err() == Some(FloScriptError::incorrect_type(&String::description(), &f64::description())));
+}
+
+#[test]
+fn cannot_coerce_collection_to_string() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<Vec<i32>>(x);
+
+    assert!(host.notebook().receive_coerced_output::<String>(x).err() == Some(FloScriptError::incorrect_type(&<Vec<i32>>::description(), &String::description())));
+}
+
+#[test]
+fn uncoerced_output_is_still_strict() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+
+    assert!(host.notebook().receive_coerced_output::<f64>(x).is_ok());
+    assert!(host.notebook().receive_output::<f64>(x).err() == Some(FloScriptError::incorrect_type(&i32::description(), &f64::description())));
+}
+
+#[test]
+fn coerce_script_values() {
+    assert!(ScriptValue::I32(3).coerce_to(&u64::description()) == Ok(ScriptValue::U64(3)));
+    assert!(ScriptValue::F32(1.5).coerce_to(&String::description()) == Ok(ScriptValue::String("1.5".to_string())));
+    assert!(ScriptValue::String("1".to_string()).coerce_to(&i32::description()).is_err());
+    assert!(ScriptValue::I64(9).into_type::<i64>() == Some(9));
+    assert!(ScriptValue::I64(9).into_type::<i32>().is_none());
+}
+
+#[test]
+fn coerce_out_of_range_integers() {
+    assert!(matches!(ScriptValue::I64(-1).coerce_to(&u32::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::U64(u64::max_value()).coerce_to(&i64::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::I64(4294967296).coerce_to(&u32::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(ScriptValue::I64(4294967295).coerce_to(&u32::description()) == Ok(ScriptValue::U32(4294967295)));
+    assert!(ScriptValue::I64(-1).coerce_into::<u32>() == Err(FloScriptError::OutOfRange { value: "-1".to_string(), requested: u32::description().to_string() }));
+}
+
+#[test]
+fn coerce_out_of_range_floats() {
+    assert!(matches!(ScriptValue::F64(3e10).coerce_to(&i32::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::F64(-1.0).coerce_to(&u64::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::F64(std::f64::NAN).coerce_to(&i32::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::F64(std::f64::INFINITY).coerce_to(&i64::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(matches!(ScriptValue::F64(1e300).coerce_to(&f32::description()), Err(FloScriptError::OutOfRange { .. })));
+    assert!(ScriptValue::F64(-0.5).coerce_to(&u32::description()) == Ok(ScriptValue::U32(0)));
+    assert!(ScriptValue::F64(2147483647.9).coerce_to(&i32::description()) == Ok(ScriptValue::I32(2147483647)));
+}
+
+#[test]
+fn out_of_range_values_are_errors_in_coerced_output() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i64>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![-1i64])).unwrap();
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output::<u32>(x).unwrap());
+    assert!(matches!(output.wait_stream(), Some(Err(FloScriptError::OutOfRange { .. }))));
+}
+
+#[test]
+fn read_int_script_as_f64() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "1 + 2");
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output::<f64>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(3.0)));
+}
+
+#[test]
+fn read_int_script_as_i32_without_conversion() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "1 + 2");
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output_state::<i32>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(3)));
+}
+
+#[test]
+fn read_script_reading_input_as_string() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<f64>(x);
+    host.editor().set_computing_script(y, "x * 2.0");
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1.5])).unwrap();
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output::<String>(y).unwrap());
+    assert!(output.wait_stream() == Some(Ok("3".to_string())));
+}
+
+#[test]
+fn read_typed_script_as_other_numeric_type() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_typed_computing_script::<f64>(x, "2.5");
+
+    let mut output  = executor::spawn(host.notebook().receive_coerced_output::<i32>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn cannot_coerce_string_script_to_number() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "\"Hello\"");
+
+    assert!(host.notebook().receive_coerced_output::<f64>(x).err() == Some(FloScriptError::incorrect_type(&String::description(), &f64::description())));
+}
+
+#[test]
+fn coercion_kind_is_declared_for_script_value_types() {
+    assert!(i32::description().coercion_kind() == Some(CoercionKind::Numeric));
+    assert!(f64::description().coercion_kind() == Some(CoercionKind::Numeric));
+    assert!(String::description().coercion_kind() == Some(CoercionKind::Text));
+    assert!(bool::description().coercion_kind() == Some(CoercionKind::Displayable));
+    assert!(<Vec<i32>>::description().coercion_kind().is_none());
+}
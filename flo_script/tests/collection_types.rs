@@ -0,0 +1,207 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use gluon::vm::api::*;
+use futures::Stream;
+use futures::stream;
+use futures::executor;
+
+use std::collections::{HashMap, BTreeMap};
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+enum Direction {
+    Up,
+    Down
+}
+
+///
+/// Sends values through an input symbol of the specified type, and returns the values that are read back
+///
+fn round_trip<Value>(values: Vec<Value>) -> Vec<Value>
+where   Value:          ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+        Value::Type:    Sized {
+    let host        = GluonScriptHost::new();
+    let input       = FloScriptSymbol::with_name("input");
+
+    host.editor().set_input_type::<Value>(input);
+    host.notebook().attach_input(input, stream::iter_ok::<_, ()>(values)).unwrap();
+
+    let output      = host.notebook().receive_output::<Value>(input).unwrap();
+    executor::spawn(output.collect()).wait_future().unwrap()
+}
+
+#[test]
+fn vec_input() {
+    let values = vec![vec![1, 2, 3], vec![], vec![4]];
+
+    assert!(round_trip(values.clone()) == values);
+    assert!(<Vec<i32>>::description().gluon_type() == Some("Array Int"));
+}
+
+#[test]
+fn option_input() {
+    let values = vec![Some("Hello".to_string()), None];
+
+    assert!(round_trip(values.clone()) == values);
+    assert!(<Option<String>>::description().gluon_type() == Some("std.types.Option String"));
+}
+
+#[test]
+fn tuple_input() {
+    let values = vec![(1, "One".to_string()), (2, "Two".to_string())];
+
+    assert!(round_trip(values.clone()) == values);
+    assert!(<(i32, String)>::description().gluon_type() == Some("(Int, String)"));
+}
+
+#[test]
+fn hash_map_input() {
+    let mut map = HashMap::new();
+    map.insert("width".to_string(), 100.0);
+    map.insert("height".to_string(), 50.0);
+
+    let values  = vec![ScriptMap::from(map), ScriptMap::new()];
+
+    assert!(round_trip(values.clone()) == values);
+}
+
+#[test]
+fn btree_map_input() {
+    let mut map = BTreeMap::new();
+    map.insert("count".to_string(), 3);
+
+    let values  = vec![map];
+
+    assert!(round_trip(values.clone()) == values);
+}
+
+#[test]
+fn enum_input() {
+    let values = vec![Direction::Up, Direction::Down, Direction::Up];
+
+    assert!(round_trip(values.clone()) == values);
+    assert!(Direction::description().gluon_type().unwrap().contains("| Up"));
+}
+
+#[test]
+fn nested_collection_input() {
+    let values = vec![vec![Some((1, Direction::Down)), None]];
+
+    assert!(round_trip(values.clone()) == values);
+    assert!(<Vec<Option<(i32, Direction)>>>::description().name().contains("Direction"));
+}
+
+#[test]
+fn collections_of_different_types_are_different() {
+    let host        = GluonScriptHost::new();
+    let input       = FloScriptSymbol::with_name("input");
+
+    host.editor().set_input_type::<Vec<i32>>(input);
+
+    assert!(host.notebook().receive_output::<Vec<f64>>(input).err() == Some(FloScriptError::incorrect_type(&<Vec<i32>>::description(), &<Vec<f64>>::description())));
+}
+
+#[test]
+fn map_type_loads_module() {
+    // std.map is only imported by the VMs created by a script namespace, so it's loaded when a map type is needed
+    let vm = new_script_vm();
+
+    assert!(make_gluon_type::<ScriptMap<i32>>(&vm).is_ok());
+    assert!(vm.find_type_info("std.map.Map").is_ok());
+    assert!(<ScriptMap<i32>>::description().gluon_type().is_some());
+}
+
+///
+/// Evaluates a computing script with a declared type and returns the first value it produces
+///
+fn evaluate<Value>(host: &GluonScriptHost, script: &str) -> Value
+where   Value:          ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+        Value::Type:    Sized {
+    let output = FloScriptSymbol::with_name("output");
+
+    host.editor().set_typed_computing_script::<Value>(output, script);
+
+    let mut output_stream = executor::spawn(host.notebook().receive_output_state::<Value>(output).unwrap());
+    output_stream.wait_stream().unwrap().unwrap()
+}
+
+///
+/// Sets an input symbol to a value, then evaluates a computing script that reads it
+///
+fn evaluate_with_input<Input, Value>(input: Input, script: &str) -> Value
+where   Input:          ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+        Input::Type:    Sized,
+        Value:          ScriptType + for<'vm, 'value> Getable<'vm, 'value> + VmType + Send + 'static,
+        Value::Type:    Sized {
+    let host    = GluonScriptHost::new();
+    let symbol  = FloScriptSymbol::with_name("input");
+
+    host.editor().set_input_type::<Input>(symbol);
+    host.notebook().attach_input(symbol, stream::iter_ok::<_, ()>(vec![input])).unwrap();
+
+    evaluate(&host, script)
+}
+
+#[test]
+fn script_produces_vec() {
+    assert!(evaluate::<Vec<i32>>(&GluonScriptHost::new(), "[1, 2, 3]") == vec![1, 2, 3]);
+}
+
+#[test]
+fn script_produces_option() {
+    assert!(evaluate::<Option<String>>(&GluonScriptHost::new(), "Some \"Hello\"") == Some("Hello".to_string()));
+    assert!(evaluate::<Option<String>>(&GluonScriptHost::new(), "None").is_none());
+}
+
+#[test]
+fn script_produces_tuple() {
+    assert!(evaluate::<(i32, String)>(&GluonScriptHost::new(), "(1, \"One\")") == (1, "One".to_string()));
+}
+
+#[test]
+fn script_produces_map() {
+    let mut map = BTreeMap::new();
+    map.insert("count".to_string(), 3);
+
+    assert!(evaluate::<BTreeMap<String, i32>>(&GluonScriptHost::new(), "let map = import! std.map in map.singleton \"count\" 3") == map);
+}
+
+#[test]
+fn script_produces_enum() {
+    // Scripts declare the variant type to use its constructors
+    assert!(evaluate::<Direction>(&GluonScriptHost::new(), "type Direction = | Up | Down\nDown") == Direction::Down);
+}
+
+#[test]
+fn script_reads_vec() {
+    assert!(evaluate_with_input::<Vec<i32>, i32>(vec![1, 2, 3], "let array = import! std.array in array.len input") == 3);
+}
+
+#[test]
+fn script_reads_option() {
+    assert!(evaluate_with_input::<Option<i32>, i32>(Some(4), "match input with\n| Some x -> x\n| None -> 0") == 4);
+    assert!(evaluate_with_input::<Option<i32>, i32>(None, "match input with\n| Some x -> x\n| None -> 0") == 0);
+}
+
+#[test]
+fn script_reads_tuple() {
+    assert!(evaluate_with_input::<(i32, String), String>((1, "One".to_string()), "let (_, name) = input in name") == "One");
+}
+
+#[test]
+fn script_reads_map() {
+    let mut map = HashMap::new();
+    map.insert("count".to_string(), 3);
+
+    assert!(evaluate_with_input::<ScriptMap<i32>, Option<i32>>(ScriptMap::from(map), "let map = import! std.map in map.find \"count\" input") == Some(3));
+}
+
+#[test]
+fn script_reads_empty_map() {
+    assert!(evaluate_with_input::<ScriptMap<i32>, Option<i32>>(ScriptMap::new(), "let map = import! std.map in map.find \"count\" input").is_none());
+}
+
+#[test]
+fn script_reads_enum() {
+    assert!(evaluate_with_input::<Direction, i32>(Direction::Down, "type Direction = | Up | Down\nmatch input with\n| Up -> 1\n| Down -> -1") == -1);
+}
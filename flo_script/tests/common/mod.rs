@@ -0,0 +1,30 @@
+//!
+//! Helpers shared by the integration tests (each test only uses some of them)
+//!
+#![allow(dead_code)]
+
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+use futures::executor;
+
+///
+/// Parses a path to a symbol in the default symbol table
+///
+pub fn path(name: &str) -> FloScriptPath {
+    FloScriptPath::parse(&FloScriptSymbolTable::default_table(), name).unwrap()
+}
+
+///
+/// Sends some edits to a host in order, and returns the result of each edit
+///
+pub fn apply_edits(host: &GluonScriptHost, edits: Vec<ScriptEdit>) -> Vec<FloScriptResult<()>> {
+    let acknowledgements = host.editor().send_sequenced_edits(stream::iter_ok(edits));
+
+    executor::spawn(acknowledgements.collect()).wait_future().unwrap()
+        .into_iter()
+        .map(|acknowledgement| acknowledgement.result)
+        .collect()
+}
@@ -0,0 +1,79 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn read_constant_value() {
+    let host                = GluonScriptHost::new();
+    let constant_x          = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(constant_x, 42);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(constant_x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn every_reader_sees_constant_value() {
+    let host                = GluonScriptHost::new();
+    let constant_x          = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(constant_x, "Hello");
+
+    let mut output_stream_1 = executor::spawn(host.notebook().receive_output_state::<String>(constant_x).expect("output stream"));
+    assert!(output_stream_1.wait_stream() == Some(Ok("Hello".to_string())));
+
+    let mut output_stream_2 = executor::spawn(host.notebook().receive_output::<String>(constant_x).expect("output stream"));
+    assert!(output_stream_2.wait_stream() == Some(Ok("Hello".to_string())));
+}
+
+#[test]
+fn readers_receive_updated_constant() {
+    let host                = GluonScriptHost::new();
+    let constant_x          = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(constant_x, 1.0);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<f64>(constant_x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1.0)));
+
+    host.editor().set_constant(constant_x, 2.0);
+    assert!(output_stream.wait_stream() == Some(Ok(2.0)));
+}
+
+#[test]
+fn cannot_read_constant_as_wrong_type() {
+    let host                = GluonScriptHost::new();
+    let constant_x          = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(constant_x, 42);
+
+    assert!(host.notebook().receive_output::<u32>(constant_x).err() == Some(FloScriptError::IncorrectType { expected: "i32 (Int)".to_string(), requested: "u32 (Int)".to_string() }));
+}
+
+#[test]
+fn cannot_attach_input_to_constant() {
+    let host                = GluonScriptHost::new();
+    let constant_x          = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(constant_x, 42);
+
+    assert!(host.notebook().attach_input(constant_x, stream::iter_ok::<_, ()>(vec![1, 2])) == Err(FloScriptError::NotAnInputSymbol));
+}
+
+#[test]
+fn constants_are_saved_in_definition() {
+    let host                = GluonScriptHost::new();
+    host.editor().set_constant(FloScriptSymbol::with_name("x"), true);
+
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols == vec![NotebookSymbolDefinition::Constant { name: "x".to_string(), value: ScriptValue::Bool(true) }]);
+
+    let new_host            = GluonScriptHost::new();
+    new_host.editor().load_definition(&definition, &ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    let mut output_stream   = executor::spawn(new_host.notebook().receive_output::<bool>(new_host.symbols().symbol("x")).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(true)));
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use self::common::*;
+
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::executor;
+
+#[test]
+fn detect_cycle_when_script_is_defined() {
+    let host    = GluonScriptHost::new();
+    let a       = FloScriptSymbol::with_name("a");
+    let b       = FloScriptSymbol::with_name("b");
+    let c       = FloScriptSymbol::with_name("c");
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetComputingScript(a, "b + 1".to_string()),
+        ScriptEdit::SetComputingScript(b, "c + 1".to_string()),
+        ScriptEdit::SetComputingScript(c, "a + 1".to_string())
+    ]);
+
+    assert!(results[0] == Ok(()));
+    assert!(results[1] == Ok(()));
+    assert!(results[2] == Err(FloScriptError::DependencyCycle(vec![path("c"), path("a"), path("b")])));
+}
+
+#[test]
+fn detect_script_that_reads_itself() {
+    let host    = GluonScriptHost::new();
+    let results = apply_edits(&host, vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("a"), "a + 1".to_string())]);
+
+    assert!(results == vec![Err(FloScriptError::DependencyCycle(vec![path("a")]))]);
+}
+
+#[test]
+fn cannot_read_from_script_in_cycle() {
+    let host    = GluonScriptHost::new();
+    let a       = FloScriptSymbol::with_name("a");
+    let b       = FloScriptSymbol::with_name("b");
+
+    host.editor().set_computing_script(a, "b + 1");
+    host.editor().set_computing_script(b, "a + 1");
+
+    assert!(host.notebook().receive_output::<i32>(a).err() == Some(FloScriptError::DependencyCycle(vec![path("a"), path("b")])));
+}
+
+#[test]
+fn cycle_is_reported_as_update() {
+    let host        = GluonScriptHost::new();
+    let a           = FloScriptSymbol::with_name("a");
+    let b           = FloScriptSymbol::with_name("b");
+    let mut updates = executor::spawn(host.notebook().updates());
+
+    host.editor().set_computing_script(a, "b + 1");
+    host.editor().set_computing_script(b, "a + 1");
+
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::DependencyCycle(vec![path("b"), path("a")]))));
+}
+
+#[test]
+fn cycle_can_be_broken() {
+    let host    = GluonScriptHost::new();
+    let a       = FloScriptSymbol::with_name("a");
+    let b       = FloScriptSymbol::with_name("b");
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetComputingScript(a, "b + 1".to_string()),
+        ScriptEdit::SetComputingScript(b, "a + 1".to_string()),
+        ScriptEdit::SetComputingScript(b, "1".to_string())
+    ]);
+
+    assert!(results[2] == Ok(()));
+    assert!(host.notebook().dependency_graph().find_cycle(&path("a")).is_none());
+}
+
+#[test]
+fn detect_cycle_through_namespace() {
+    let host    = GluonScriptHost::new();
+    let results = apply_edits(&host, vec![
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("x"), "y".to_string())]),
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("y"), "x".to_string())])
+    ]);
+
+    assert!(results[1] == Err(FloScriptError::DependencyCycle(vec![path("y"), path("x")])));
+}
+
+#[test]
+fn local_that_shadows_symbol_is_not_a_cycle() {
+    let host    = GluonScriptHost::new();
+    let results = apply_edits(&host, vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("y"), "let y = 1 in y".to_string())]);
+
+    assert!(results == vec![Ok(())]);
+}
+
+#[test]
+fn lambda_parameter_that_shadows_symbol_is_not_a_cycle() {
+    let host    = GluonScriptHost::new();
+    let results = apply_edits(&host, vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("x"), "(\\x -> x + 1) 2".to_string())]);
+
+    assert!(results == vec![Ok(())]);
+}
+
+#[test]
+fn record_field_with_symbol_name_is_not_a_cycle() {
+    let host    = GluonScriptHost::new();
+    let x       = FloScriptSymbol::with_name("x");
+    let y       = FloScriptSymbol::with_name("y");
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetComputingScript(x, "let p = { x = 1, y = 2 } in p.x".to_string()),
+        ScriptEdit::SetComputingScript(y, "{ y = 1 }.y".to_string())
+    ]);
+
+    assert!(results == vec![Ok(()), Ok(())]);
+}
+
+#[test]
+fn punned_record_field_is_a_reference() {
+    let host    = GluonScriptHost::new();
+    let results = apply_edits(&host, vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("x"), "{ x }".to_string())]);
+
+    assert!(results == vec![Err(FloScriptError::DependencyCycle(vec![path("x")]))]);
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use self::common::*;
+
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::executor;
+
+#[test]
+fn scripts_depend_on_symbols_they_refer_to() {
+    let host    = GluonScriptHost::new();
+
+    host.editor().set_input_type::<i32>(FloScriptSymbol::with_name("x"));
+    host.editor().set_input_type::<i32>(FloScriptSymbol::with_name("y"));
+    host.editor().set_computing_script(FloScriptSymbol::with_name("sum"), "x + y + not_a_symbol");
+    host.editor().set_computing_script(FloScriptSymbol::with_name("double"), "// y\nsum * 2");
+
+    let graph   = host.notebook().dependency_graph();
+
+    assert!(graph.dependencies_of(&path("sum")) == vec![&path("x"), &path("y")]);
+    assert!(graph.dependencies_of(&path("double")) == vec![&path("sum")]);
+    assert!(graph.dependents_of(&path("sum")) == vec![&path("double")]);
+    assert!(graph.edges().iter().all(|edge| edge.is_static && !edge.is_dynamic));
+}
+
+#[test]
+fn scripts_can_depend_on_symbols_in_child_namespaces() {
+    let host    = GluonScriptHost::new();
+
+    host.editor().set_input_type_at::<f64>(&path("layers.background.opacity"));
+    host.editor().set_computing_script(FloScriptSymbol::with_name("faded"), "layers.background.opacity / 2.0");
+    host.editor().set_computing_script_at(&path("layers.total"), "background.opacity");
+
+    let graph   = host.notebook().dependency_graph();
+
+    assert!(graph.contains_symbol(&path("layers.background.opacity")));
+    assert!(graph.dependencies_of(&path("faded")) == vec![&path("layers.background.opacity")]);
+    assert!(graph.dependencies_of(&path("layers.total")) == vec![&path("layers.background.opacity")]);
+}
+
+#[test]
+fn graph_for_child_namespace_is_relative() {
+    let host    = GluonScriptHost::new();
+
+    host.editor().set_input_type_at::<f64>(&path("layers.opacity"));
+    host.editor().set_computing_script_at(&path("layers.total"), "opacity");
+
+    let layers  = host.notebook().namespace(FloScriptSymbol::with_name("layers")).unwrap();
+    let graph   = layers.dependency_graph();
+
+    assert!(graph.dependencies_of(&path("total")) == vec![&path("opacity")]);
+}
+
+#[test]
+fn export_as_dot() {
+    let mut graph = DependencyGraph::new();
+
+    graph.add_symbol(path("x"));
+    graph.add_symbol(path("y"));
+    graph.add_symbol(path("ns.z"));
+    graph.add_dependency(path("y"), path("x"), DependencyKind::Static);
+    graph.add_dependency(path("ns.z"), path("x"), DependencyKind::Dynamic);
+    graph.add_dependency(path("ns.z"), path("y"), DependencyKind::Static);
+    graph.add_dependency(path("ns.z"), path("y"), DependencyKind::Dynamic);
+
+    assert!(graph.edges().len() == 3);
+    assert!(graph.to_dot() == "digraph notebook {\n    \"x\";\n    \"y\";\n    \"ns.z\";\n    \"y\" -> \"x\";\n    \"ns.z\" -> \"x\" [style=dashed];\n    \"ns.z\" -> \"y\" [style=bold];\n}\n");
+}
+
+#[test]
+fn evaluation_order_lists_dependents_after_their_dependencies() {
+    let mut graph = DependencyGraph::new();
+
+    graph.add_symbol(path("total"));
+    graph.add_symbol(path("double"));
+    graph.add_symbol(path("sum"));
+    graph.add_symbol(path("x"));
+    graph.add_symbol(path("y"));
+    graph.add_dependency(path("total"), path("double"), DependencyKind::Static);
+    graph.add_dependency(path("total"), path("x"), DependencyKind::Static);
+    graph.add_dependency(path("double"), path("sum"), DependencyKind::Static);
+    graph.add_dependency(path("sum"), path("x"), DependencyKind::Static);
+    graph.add_dependency(path("sum"), path("y"), DependencyKind::Dynamic);
+
+    assert!(graph.evaluation_order(&[path("x"), path("y")]) == vec![path("sum"), path("double"), path("total")]);
+    assert!(graph.evaluation_order(&[path("double")]) == vec![path("total")]);
+    assert!(graph.evaluation_order(&[path("total")]) == vec![]);
+}
+
+#[test]
+fn evaluation_order_leaves_out_cycles() {
+    let mut graph = DependencyGraph::new();
+
+    graph.add_symbol(path("x"));
+    graph.add_symbol(path("a"));
+    graph.add_symbol(path("b"));
+    graph.add_symbol(path("c"));
+    graph.add_dependency(path("a"), path("x"), DependencyKind::Static);
+    graph.add_dependency(path("a"), path("b"), DependencyKind::Static);
+    graph.add_dependency(path("b"), path("a"), DependencyKind::Static);
+    graph.add_dependency(path("c"), path("x"), DependencyKind::Static);
+
+    assert!(graph.evaluation_order(&[path("x")]) == vec![path("c")]);
+}
+
+#[test]
+fn evaluation_order_for_notebook() {
+    let host    = GluonScriptHost::new();
+
+    host.editor().set_input_type::<i32>(FloScriptSymbol::with_name("x"));
+    host.editor().set_input_type::<i32>(FloScriptSymbol::with_name("y"));
+    host.editor().set_computing_script(FloScriptSymbol::with_name("double"), "sum * 2");
+    host.editor().set_computing_script(FloScriptSymbol::with_name("sum"), "x + y");
+
+    let graph   = host.notebook().dependency_graph();
+
+    assert!(graph.evaluation_order(&[path("x"), path("y")]) == vec![path("sum"), path("double")]);
+    assert!(graph.evaluation_order(&[path("sum")]) == vec![path("double")]);
+}
+
+#[test]
+fn evaluated_scripts_have_dynamic_dependencies() {
+    let host    = GluonScriptHost::new();
+
+    let x       = FloScriptSymbol::with_name("x");
+    let double  = FloScriptSymbol::with_name("double");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(double, "x * 2");
+
+    let graph   = host.notebook().dependency_graph();
+    assert!(graph.edges().iter().all(|edge| edge.is_static && !edge.is_dynamic));
+
+    let mut double_stream = executor::spawn(host.notebook().receive_output_state::<i32>(double).unwrap());
+    host.notebook().send_input_tick(vec![InputValue::new(x, 21)]).unwrap();
+    assert!(double_stream.wait_stream() == Some(Ok(42)));
+
+    let graph   = host.notebook().dependency_graph();
+    assert!(graph.edges() == [DependencyEdge { dependent: path("double"), dependency: path("x"), is_static: true, is_dynamic: true }]);
+}
+
+#[test]
+fn dynamic_dependencies_are_the_symbols_that_were_read() {
+    let host    = GluonScriptHost::new();
+
+    let flag    = FloScriptSymbol::with_name("flag");
+    let x       = FloScriptSymbol::with_name("x");
+    let y       = FloScriptSymbol::with_name("y");
+    let choice  = FloScriptSymbol::with_name("choice");
+
+    host.editor().set_input_type::<bool>(flag);
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_input_type::<i32>(y);
+    host.editor().set_computing_script(choice, "if flag then x else y");
+
+    let dynamic_dependencies = || {
+        host.notebook().dependency_graph().edges().iter()
+            .filter(|edge| edge.is_dynamic)
+            .map(|edge| edge.dependency.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let mut choice_stream = executor::spawn(host.notebook().receive_output_state::<i32>(choice).unwrap());
+
+    host.notebook().send_input_tick(vec![InputValue::new(flag, true), InputValue::new(x, 1), InputValue::new(y, 2)]).unwrap();
+    assert!(choice_stream.wait_stream() == Some(Ok(1)));
+
+    assert!(host.notebook().dependency_graph().edges().iter().filter(|edge| edge.is_static).count() == 3);
+    assert!(dynamic_dependencies() == vec![path("flag"), path("x")]);
+
+    host.notebook().send_input_tick(vec![InputValue::new(flag, false)]).unwrap();
+    assert!(choice_stream.wait_stream() == Some(Ok(2)));
+
+    assert!(dynamic_dependencies() == vec![path("flag"), path("y")]);
+}
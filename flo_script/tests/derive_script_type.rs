@@ -0,0 +1,144 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use gluon::vm::api::*;
+use futures::stream;
+use futures::executor;
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+struct Point {
+    x: f64,
+    y: f64
+}
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+enum Shape {
+    Empty,
+    Circle(Point, f64),
+    Rect { min: Point, max: Point }
+}
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+struct Opacity(f32);
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+struct Label {
+    text: String
+}
+
+#[derive(Clone, Debug, PartialEq, ScriptType)]
+struct Tagged {
+    label: Label,
+    value: i32
+}
+
+mod other {
+    use flo_script::*;
+
+    #[derive(Clone, Debug, PartialEq, ScriptType)]
+    pub struct Point {
+        pub z: i32
+    }
+}
+
+#[test]
+fn struct_is_a_record() {
+    assert!(Point::description().gluon_type() == Some("{ x : Float, y : Float }"));
+}
+
+#[test]
+fn type_registered_by_another_type_is_still_described_by_its_definition() {
+    // Describing `Tagged` registers `Label` with the VM used to generate descriptions
+    assert!(Tagged::description().gluon_type().is_some());
+    assert!(Label::description().gluon_type() == Some("{ text : String }"));
+}
+
+#[test]
+fn enum_is_a_variant() {
+    let shape_type = Shape::description().gluon_type().unwrap().to_string();
+
+    assert!(shape_type.contains("| Empty"));
+    assert!(shape_type.contains("| Circle { x : Float, y : Float } Float"));
+    assert!(shape_type.contains("| Rect { x : Float, y : Float } { x : Float, y : Float }"));
+}
+
+#[test]
+fn newtype_uses_inner_type() {
+    assert!(Opacity::description().gluon_type() == f32::description().gluon_type());
+}
+
+#[test]
+fn type_is_declared_by_name() {
+    let vm = new_script_vm();
+    Shape::make_type(&vm);
+
+    assert!(vm.find_type_info("Shape").is_ok());
+    assert!(vm.find_type_info("Point").is_ok());
+}
+
+#[test]
+fn declared_type_can_be_named_by_scripts() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.declare_type::<Point>().unwrap();
+    host.editor().set_computing_script_at(&FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "shapes.x").unwrap(), "let point : Point = { x = 1.0, y = 2.0 } in point.x");
+
+    let shapes      = host.notebook().namespace(FloScriptSymbol::with_name("shapes")).unwrap();
+    let mut output  = executor::spawn(shapes.receive_output_state::<f64>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(1.0)));
+}
+
+#[test]
+fn undeclared_type_is_not_known_to_other_hosts() {
+    let declared    = GluonScriptHost::new();
+    declared.declare_type::<Point>().unwrap();
+
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "let point : Point = { x = 1.0, y = 2.0 } in point.x");
+
+    match host.notebook().receive_output_state::<f64>(x) {
+        Err(FloScriptError::ScriptError(_)) => { }
+        other                               => panic!("Unexpected result {:?}", other.map(|_| ()))
+    }
+}
+
+#[test]
+fn types_with_the_same_name_cannot_both_be_declared() {
+    let host = GluonScriptHost::new();
+
+    assert!(host.declare_type::<Point>().is_ok());
+    assert!(host.declare_type::<Point>().is_ok());
+    assert!(matches!(host.declare_type::<other::Point>(), Err(FloScriptError::UnsupportedType(_))));
+}
+
+#[test]
+fn type_cannot_be_declared_with_the_name_of_a_type_already_in_use() {
+    let host    = GluonScriptHost::new();
+    let x       = FloScriptSymbol::with_name("x");
+    let z       = FloScriptSymbol::with_name("z");
+
+    // Reading `other::Point` from a script declares it by name in the namespace's VM
+    host.editor().set_input_type::<other::Point>(x);
+    host.editor().set_computing_script(z, "x.z");
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![other::Point { z: 1 }])).unwrap();
+    let mut output = executor::spawn(host.notebook().receive_output_state::<i32>(z).unwrap());
+    assert!(output.wait_stream() == Some(Ok(1)));
+
+    assert!(matches!(host.declare_type::<Point>(), Err(FloScriptError::UnsupportedType(_))));
+}
+
+#[test]
+fn derived_type_can_be_used_as_input() {
+    let host        = GluonScriptHost::new();
+    let shape       = FloScriptSymbol::with_name("shape");
+    let circle      = Shape::Circle(Point { x: 1.0, y: 2.0 }, 3.0);
+
+    host.editor().set_input_type::<Shape>(shape);
+    host.notebook().attach_input(shape, stream::iter_ok::<_, ()>(vec![circle.clone()])).unwrap();
+
+    let mut output  = executor::spawn(host.notebook().receive_output::<Shape>(shape).unwrap());
+    assert!(output.wait_stream() == Some(Ok(circle)));
+}
@@ -0,0 +1,80 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::Stream;
+use futures::stream;
+use futures::executor;
+
+use std::time::{Duration};
+
+#[test]
+fn set_execution_limits() {
+    let host        = GluonScriptHost::new();
+    let limits      = ExecutionLimits { time: Some(Duration::from_millis(100)), steps: Some(10_000), memory: Some(1024 * 1024) };
+
+    let edits       = host.editor().send_sequenced_gluon_edits(stream::iter_ok(vec![
+        GluonScriptEdit::SetExecutionLimits(limits),
+        GluonScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![GluonScriptEdit::SetExecutionLimits(ExecutionLimits::unlimited())])
+    ]));
+    let results     = executor::spawn(edits.collect()).wait_future().unwrap();
+
+    assert!(results.into_iter().map(|acknowledgement| acknowledgement.result).collect::<Vec<_>>() == vec![Ok(()), Ok(())]);
+}
+
+#[test]
+fn limits_are_unlimited_by_default() {
+    let limits      = ExecutionLimits::default();
+
+    assert!(limits == ExecutionLimits::unlimited());
+    assert!(limits.time.is_none() && limits.steps.is_none() && limits.memory.is_none());
+}
+
+
+fn set_limits(host: &GluonScriptHost, limits: ExecutionLimits) {
+    executor::spawn(host.editor().send_gluon_edits(stream::iter_ok(vec![GluonScriptEdit::SetExecutionLimits(limits)]))).wait_future().unwrap();
+}
+
+#[test]
+fn runaway_script_is_stopped_by_step_limit() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    set_limits(&host, ExecutionLimits { steps: Some(10_000), ..ExecutionLimits::unlimited() });
+    host.editor().set_computing_script(x, "let forever n : Int -> Int = forever (n + 1)\nforever 0");
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+
+    assert!(output_stream.wait_stream() == Some(Err(FloScriptError::StepLimitExceeded(10_000))));
+    assert!(host.notebook().status(x) == Some(SymbolStatus::Failed(FloScriptError::StepLimitExceeded(10_000))));
+}
+
+#[test]
+fn runaway_script_is_stopped_by_time_limit() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let time_limit  = Duration::from_millis(100);
+
+    set_limits(&host, ExecutionLimits { time: Some(time_limit), ..ExecutionLimits::unlimited() });
+    host.editor().set_computing_script(x, "let forever n : Int -> Int = forever (n + 1)\nforever 0");
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+
+    assert!(output_stream.wait_stream() == Some(Err(FloScriptError::TimeLimitExceeded(time_limit))));
+}
+
+#[test]
+fn other_scripts_run_after_runaway_script_is_stopped() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+
+    set_limits(&host, ExecutionLimits { steps: Some(10_000), ..ExecutionLimits::unlimited() });
+    host.editor().set_computing_script(x, "let forever n : Int -> Int = forever (n + 1)\nforever 0");
+    host.editor().set_computing_script(y, "1 + 2");
+
+    let mut x_stream    = executor::spawn(host.notebook().receive_output::<i32>(x).expect("x stream"));
+    let mut y_stream    = executor::spawn(host.notebook().receive_output::<i32>(y).expect("y stream"));
+
+    assert!(x_stream.wait_stream() == Some(Err(FloScriptError::StepLimitExceeded(10_000))));
+    assert!(y_stream.wait_stream() == Some(Ok(3)));
+}
@@ -1,5 +1,7 @@
 #[macro_use] extern crate gluon_vm;
 
+use flo_script::gluon_host::new_script_vm;
+
 use gluon::*;
 use gluon::compiler_pipeline::*;
 use gluon::vm::ExternModule;
@@ -13,7 +15,7 @@ use futures::executor;
 fn threads_have_same_modules() {
     // In Gluon: importing a module replaces an existing module and also applies across all threads created for the same VM
     // Once bound by the compiler, modules stay bound to the same place
-    let root_thread = new_vm();
+    let root_thread = new_script_vm();
 
     // Create two threads
     let thread1     = root_thread.new_thread().unwrap();
@@ -57,7 +59,7 @@ fn threads_have_same_modules() {
 #[test]
 fn modules_stay_bound() {
     // Once a symbol is bound, it stays bound (we can't replace it as we did in the previous example)
-    let root_thread = new_vm();
+    let root_thread = new_script_vm();
 
     // Create two threads
     let thread1     = root_thread.new_thread().unwrap();
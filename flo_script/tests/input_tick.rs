@@ -0,0 +1,174 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::future;
+use futures::future::Executor;
+use futures::stream;
+use futures::executor;
+
+use std::sync::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn tick_sets_every_input() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_input_type::<String>(y);
+
+    let mut x_stream        = executor::spawn(host.notebook().receive_output_state::<i32>(x).expect("x stream"));
+    let mut y_stream        = executor::spawn(host.notebook().receive_output_state::<String>(y).expect("y stream"));
+
+    host.notebook().send_input_tick(vec![InputValue::new(x, 1), InputValue::new(y, "One".to_string())]).unwrap();
+    assert!(x_stream.wait_stream() == Some(Ok(1)));
+    assert!(y_stream.wait_stream() == Some(Ok("One".to_string())));
+
+    host.notebook().send_input_tick(vec![InputValue::new(x, 2), InputValue::new(y, "Two".to_string())]).unwrap();
+    assert!(x_stream.wait_stream() == Some(Ok(2)));
+    assert!(y_stream.wait_stream() == Some(Ok("Two".to_string())));
+}
+
+#[test]
+fn tick_replaces_attached_stream() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok(vec![1, 2, 3])).unwrap();
+    host.notebook().send_input_tick(vec![InputValue::new(x, 42)]).unwrap();
+
+    let mut x_stream        = executor::spawn(host.notebook().receive_output::<i32>(x).expect("x stream"));
+    assert!(x_stream.wait_stream() == Some(Ok(42)));
+    assert!(host.notebook().status(x) == Some(SymbolStatus::UpToDate));
+}
+
+#[test]
+fn tick_with_wrong_type_changes_nothing() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_input_type::<i32>(y);
+    host.notebook().send_input_tick(vec![InputValue::new(x, 1), InputValue::new(y, 1)]).unwrap();
+
+    let result              = host.notebook().send_input_tick(vec![InputValue::new(x, 2), InputValue::new(y, 2.0)]);
+    assert!(result == Err(FloScriptError::IncorrectType { expected: "i32 (Int)".to_string(), requested: "f64 (Float)".to_string() }));
+
+    let mut x_stream        = executor::spawn(host.notebook().receive_output_state::<i32>(x).expect("x stream"));
+    assert!(x_stream.wait_stream() == Some(Ok(1)));
+}
+
+#[test]
+fn tick_only_sets_inputs() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+    let constant            = FloScriptSymbol::with_name("constant");
+    let undefined           = FloScriptSymbol::with_name("undefined");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_constant(constant, 1);
+
+    assert!(host.notebook().send_input_tick(vec![InputValue::new(x, 1), InputValue::new(constant, 2)]) == Err(FloScriptError::NotAnInputSymbol));
+    assert!(host.notebook().send_input_tick(vec![InputValue::new(undefined, 2)]) == Err(FloScriptError::UndefinedSymbol(undefined)));
+}
+
+///
+/// Executor that counts the script evaluations it's given, running each one immediately
+///
+struct CountingExecutor(Arc<AtomicUsize>);
+
+impl Executor<ScriptFuture> for CountingExecutor {
+    fn execute(&self, future: ScriptFuture) -> Result<(), future::ExecuteError<ScriptFuture>> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        executor::spawn(future).wait_future().ok();
+        Ok(())
+    }
+}
+
+#[test]
+fn dependent_script_is_evaluated_once_per_tick() {
+    let evaluations         = Arc::new(AtomicUsize::new(0));
+    let host                = GluonScriptHost::with_executor(CountingExecutor(Arc::clone(&evaluations)));
+    let a                   = FloScriptSymbol::with_name("a");
+    let b                   = FloScriptSymbol::with_name("b");
+    let total               = FloScriptSymbol::with_name("total");
+    let scaled              = FloScriptSymbol::with_name("scaled");
+
+    host.editor().set_input_type::<i32>(a);
+    host.editor().set_input_type::<i32>(b);
+    host.editor().set_computing_script(total, "a + b");
+    host.editor().set_computing_script(scaled, "total * 10 + a");
+
+    let mut scaled_stream   = executor::spawn(host.notebook().receive_output::<i32>(scaled).expect("scaled stream"));
+
+    // Each tick evaluates `total` and then `scaled` once, and `scaled` only ever sees values of `a` and `total` from the same tick
+    host.notebook().send_input_tick(vec![InputValue::new(a, 1), InputValue::new(b, 2)]).unwrap();
+    assert!(scaled_stream.wait_stream() == Some(Ok(31)));
+    assert!(evaluations.load(Ordering::SeqCst) == 2);
+
+    host.notebook().send_input_tick(vec![InputValue::new(a, 2), InputValue::new(b, 5)]).unwrap();
+    assert!(scaled_stream.wait_stream() == Some(Ok(72)));
+    assert!(evaluations.load(Ordering::SeqCst) == 4);
+
+    host.notebook().send_input_tick(vec![InputValue::new(b, 0), InputValue::new(a, 3)]).unwrap();
+    assert!(scaled_stream.wait_stream() == Some(Ok(33)));
+    assert!(evaluations.load(Ordering::SeqCst) == 6);
+}
+
+#[test]
+fn tick_sets_inputs_in_two_namespaces() {
+    let evaluations         = Arc::new(AtomicUsize::new(0));
+    let host                = GluonScriptHost::with_executor(CountingExecutor(Arc::clone(&evaluations)));
+    let path                = |name| FloScriptPath::parse(&host.symbols(), name).unwrap();
+    let total               = FloScriptSymbol::with_name("total");
+
+    host.editor().set_input_type_at::<i32>(&path("left.x"));
+    host.editor().set_input_type_at::<i32>(&path("right.x"));
+    host.editor().set_computing_script(total, "left.x + right.x * 10");
+
+    let mut total_stream    = executor::spawn(host.notebook().receive_output::<i32>(total).expect("total stream"));
+
+    // `total` is evaluated once per tick, with the values of both inputs from the same tick
+    host.notebook().send_input_tick(vec![InputValue::new(path("left.x"), 1), InputValue::new(path("right.x"), 2)]).unwrap();
+    assert!(total_stream.wait_stream() == Some(Ok(21)));
+    assert!(evaluations.load(Ordering::SeqCst) == 1);
+
+    host.notebook().send_input_tick(vec![InputValue::new(path("right.x"), 4), InputValue::new(path("left.x"), 3)]).unwrap();
+    assert!(total_stream.wait_stream() == Some(Ok(43)));
+    assert!(evaluations.load(Ordering::SeqCst) == 2);
+}
+
+#[test]
+fn tick_with_missing_namespace_changes_nothing() {
+    let host                = GluonScriptHost::new();
+    let path                = |name| FloScriptPath::parse(&host.symbols(), name).unwrap();
+
+    host.editor().set_input_type_at::<i32>(&path("left.x"));
+    host.notebook().send_input_tick(vec![InputValue::new(path("left.x"), 1)]).unwrap();
+
+    let result              = host.notebook().send_input_tick(vec![InputValue::new(path("left.x"), 2), InputValue::new(path("right.x"), 2)]);
+    assert!(result == Err(FloScriptError::UndefinedSymbol(path("right.x").symbol())));
+
+    let mut x_stream        = executor::spawn(host.notebook().receive_output_at::<i32>(&path("left.x")).expect("x stream"));
+    assert!(x_stream.wait_stream() == Some(Ok(1)));
+}
+
+#[test]
+fn tick_sets_input_of_any_type() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<Vec<i32>>(x);
+    host.editor().set_input_type::<f64>(y);
+
+    host.notebook().send_input_tick(vec![InputValue::new(x, vec![1, 2, 3]), InputValue::from_script_value(y, ScriptValue::F64(0.5))]).unwrap();
+
+    let mut x_stream        = executor::spawn(host.notebook().receive_output_state::<Vec<i32>>(x).expect("x stream"));
+    let mut y_stream        = executor::spawn(host.notebook().receive_output_state::<f64>(y).expect("y stream"));
+    assert!(x_stream.wait_stream() == Some(Ok(vec![1, 2, 3])));
+    assert!(y_stream.wait_stream() == Some(Ok(0.5)));
+}
@@ -0,0 +1,191 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn renamed_input_keeps_readers() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(input_x).expect("output stream"));
+
+    host.editor().rename_symbol(input_x, input_y);
+    host.notebook().attach_input(input_y, stream::iter_ok::<_, ()>(vec![1, 2])).expect("attaching input");
+
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+    assert!(output_stream.wait_stream() == Some(Ok(2)));
+    assert!(host.notebook().receive_output::<i32>(input_x).err() == Some(FloScriptError::UndefinedSymbol(input_x)));
+}
+
+#[test]
+fn moved_input_keeps_attached_stream() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let namespace           = FloScriptSymbol::with_name("ns");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.notebook().attach_input(input_x, stream::iter_ok::<_, ()>(vec![1, 2])).expect("attaching input");
+
+    host.editor().move_symbol(vec![input_x], vec![namespace, input_x]);
+
+    let mut output_stream   = executor::spawn(host.notebook().namespace(namespace).unwrap().receive_output::<i32>(input_x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+    assert!(output_stream.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn rename_updates_scripts_in_same_namespace() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_z             = FloScriptSymbol::with_name("z");
+    let output_y            = FloScriptSymbol::with_name("y");
+    let namespace           = FloScriptSymbol::with_name("ns");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_computing_script(output_y, "x + 1");
+    host.editor().edit(ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetComputingScript(output_y, "x + 2".to_string())]));
+
+    host.editor().rename_symbol(input_x, input_z);
+
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols == vec![
+        NotebookSymbolDefinition::Namespace { name: "ns".to_string(), definition: NotebookDefinition {
+            run_io:     false,
+            symbols:    vec![NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "x + 2".to_string() }]
+        } },
+        NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "z + 1".to_string() },
+        NotebookSymbolDefinition::Input { name: "z".to_string(), type_name: "i32".to_string() }
+    ]);
+}
+
+#[test]
+fn cannot_move_into_symbol_that_is_not_a_namespace() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_input_type::<i32>(input_y);
+
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::MoveSymbol(vec![input_x], vec![input_y, input_x]),
+        ScriptEdit::MoveSymbol(vec![FloScriptSymbol::with_name("not_defined")], vec![input_x])
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements[0].result == Err(FloScriptError::NotANamespace));
+    assert!(acknowledgements[1].result == Err(FloScriptError::UndefinedSymbol(FloScriptSymbol::with_name("not_defined"))));
+    assert!(host.notebook().receive_output::<i32>(input_x).is_ok());
+}
+
+#[test]
+fn cannot_move_onto_defined_symbol() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_input_type::<f64>(input_y);
+
+    let edits               = stream::iter_ok(vec![ScriptEdit::MoveSymbol(vec![input_x], vec![input_y])]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements[0].result == Err(FloScriptError::AlreadyDefined(input_y)));
+    assert!(host.notebook().receive_output::<i32>(input_x).is_ok());
+    assert!(host.notebook().receive_output::<f64>(input_y).is_ok());
+}
+
+#[test]
+fn failed_move_changes_nothing() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+    let input_z             = FloScriptSymbol::with_name("z");
+    let namespace           = FloScriptSymbol::with_name("ns");
+    let constant            = FloScriptSymbol::with_name("constant");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_input_type::<f64>(input_y);
+    host.editor().set_input_type_at::<i32>(&FloScriptPath::new(vec![namespace], input_z));
+
+    let mut status          = executor::spawn(host.notebook().watch_status(input_x).unwrap());
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::WaitingForInput)));
+
+    let updates             = host.notebook().updates();
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::MoveSymbol(vec![input_x], vec![input_y]),
+        ScriptEdit::MoveSymbol(vec![input_x], vec![input_y, input_x]),
+        ScriptEdit::MoveSymbol(vec![input_x], vec![namespace, input_z])
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements[0].result == Err(FloScriptError::AlreadyDefined(input_y)));
+    assert!(acknowledgements[1].result == Err(FloScriptError::NotANamespace));
+    assert!(acknowledgements[2].result == Err(FloScriptError::AlreadyDefined(input_z)));
+
+    // Nothing is told that `x` was undefined, and its status is still tracked by the same stream
+    host.editor().set_constant(constant, 1);
+
+    let updates             = updates.take_while(|update| Ok(update != &NotebookUpdate::DefinedOutputSymbol(constant, i32::description())));
+    let updates             = executor::spawn(updates.collect()).wait_future().unwrap();
+    assert!(!updates.iter().any(|update| matches!(update, NotebookUpdate::UndefinedSymbol(_))));
+
+    host.notebook().attach_input(input_x, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::UpToDate)));
+}
+
+#[test]
+fn rename_leaves_locals_and_fields_alone() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let output_y            = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_computing_script(output_y, "let p = { x = 1, y = 2 } in p.x + x + (let x = 3 in x)");
+
+    host.editor().rename_symbol(input_x, FloScriptSymbol::with_name("z"));
+
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols.contains(&NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "let p = { x = 1, y = 2 } in p.x + z + (let x = 3 in x)".to_string() }));
+}
+
+#[test]
+fn rename_updates_paths_from_parent_namespace() {
+    let host                = GluonScriptHost::new();
+    let namespace           = FloScriptSymbol::with_name("ns");
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_z             = FloScriptSymbol::with_name("z");
+    let output_y            = FloScriptSymbol::with_name("y");
+
+    host.editor().edit(ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetInputType(input_x, i32::description())]));
+    host.editor().set_computing_script(output_y, "ns.x + 1");
+
+    host.editor().move_symbol(vec![namespace, input_x], vec![namespace, input_z]);
+
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols.contains(&NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "ns.z + 1".to_string() }));
+}
+
+#[test]
+fn move_into_namespace_updates_scripts() {
+    let host                = GluonScriptHost::new();
+    let namespace           = FloScriptSymbol::with_name("ns");
+    let input_x             = FloScriptSymbol::with_name("x");
+    let output_y            = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().set_computing_script(output_y, "x + 1");
+
+    host.editor().move_symbol(vec![input_x], vec![namespace, input_x]);
+
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols.contains(&NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "ns.x + 1".to_string() }));
+}
@@ -0,0 +1,223 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn definition_contains_defined_symbols() {
+    let host        = GluonScriptHost::new();
+    let editor      = host.editor();
+
+    let input_x     = FloScriptSymbol::with_name("x");
+    let output_y    = FloScriptSymbol::with_name("y");
+    let namespace   = FloScriptSymbol::with_name("ns");
+
+    editor.set_input_type::<i32>(input_x);
+    editor.set_computing_script(output_y, "1 + 2");
+    editor.edit(ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetStreamingScript(output_y, "3".to_string())]));
+
+    let definition  = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    assert!(definition == NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::Namespace { name: "ns".to_string(), definition: NotebookDefinition {
+                run_io:     false,
+                symbols:    vec![NotebookSymbolDefinition::StreamingScript { name: "y".to_string(), script: "3".to_string() }]
+            } },
+            NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() },
+            NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "1 + 2".to_string() }
+        ]
+    });
+}
+
+#[test]
+fn anonymous_symbols_are_not_saved() {
+    let host        = GluonScriptHost::new();
+
+    host.editor().set_input_type::<i32>(FloScriptSymbol::new());
+
+    assert!(host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == NotebookDefinition::new());
+}
+
+#[test]
+fn definition_round_trips_through_json() {
+    let definition  = NotebookDefinition {
+        run_io:     true,
+        symbols:    vec![
+            NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() },
+            NotebookSymbolDefinition::Namespace { name: "ns".to_string(), definition: NotebookDefinition {
+                run_io:     false,
+                symbols:    vec![NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "x + 1".to_string() }]
+            } }
+        ]
+    };
+
+    let json        = serde_json::to_string(&definition).unwrap();
+    let loaded      = serde_json::from_str::<NotebookDefinition>(&json).unwrap();
+
+    assert!(loaded == definition);
+}
+
+#[test]
+fn load_definition_into_new_host() {
+    let host        = GluonScriptHost::new();
+    let editor      = host.editor();
+
+    editor.set_input_type::<f64>(FloScriptSymbol::with_name("x"));
+    editor.set_computing_script(FloScriptSymbol::with_name("y"), "x + 1.0");
+    let edits       = editor.send_gluon_edits(stream::iter_ok(vec![
+        GluonScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![
+            GluonScriptEdit::SetRunIo(true),
+            GluonScriptEdit::ScriptEdit(ScriptEdit::SetInputType(FloScriptSymbol::with_name("z"), String::description()))
+        ])
+    ]));
+    executor::spawn(edits).wait_future().unwrap();
+
+    let definition  = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    let new_host    = GluonScriptHost::new();
+    new_host.editor().load_gluon_definition(&definition, &ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    assert!(new_host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == definition);
+}
+
+#[test]
+fn load_definition_replaces_existing_symbols() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() }]
+    };
+
+    let host        = GluonScriptHost::new();
+    host.editor().set_input_type::<i32>(FloScriptSymbol::with_name("old_symbol"));
+    host.editor().load_definition(&definition, &ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    assert!(host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == definition);
+}
+
+#[test]
+fn unknown_types_are_not_loaded() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "1".to_string() },
+            NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "not_a_type".to_string() }
+        ]
+    };
+
+    let host        = GluonScriptHost::new();
+    let result      = host.editor().load_definition(&definition, &ScriptTypeRegistry::with_standard_types());
+
+    assert!(result == Err(FloScriptError::UnknownTypeName("not_a_type".to_string())));
+    assert!(host.symbols().is_empty());
+}
+
+#[test]
+fn loading_a_symbol_that_cannot_be_defined_is_an_error() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() },
+            NotebookSymbolDefinition::TypedComputingScript { name: "y".to_string(), type_name: "i32".to_string(), script: "\"not a number\"".to_string() }
+        ]
+    };
+
+    let host        = GluonScriptHost::new();
+    let result      = host.editor().load_definition(&definition, &ScriptTypeRegistry::with_standard_types());
+
+    assert!(matches!(result, Err(FloScriptError::IncorrectType { .. })));
+}
+
+#[test]
+fn loading_a_gluon_definition_with_a_cycle_is_an_error() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::Namespace { name: "ns".to_string(), definition: NotebookDefinition {
+                run_io:     true,
+                symbols:    vec![NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "y + 1".to_string() }]
+            } }
+        ]
+    };
+
+    let host        = GluonScriptHost::new();
+    let result      = host.editor().load_gluon_definition(&definition, &ScriptTypeRegistry::with_standard_types());
+
+    assert!(matches!(result, Err(FloScriptError::DependencyCycle(_))));
+}
+
+#[test]
+fn typed_computing_script_has_declared_type_when_loaded() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![NotebookSymbolDefinition::TypedComputingScript { name: "y".to_string(), type_name: "f64".to_string(), script: "1.0".to_string() }]
+    };
+
+    let edits       = definition.edits(&FloScriptSymbolTable::default_table(), &ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    assert!(edits == vec![ScriptEdit::SetTypedComputingScript(FloScriptSymbol::with_name("y"), f64::description(), "1.0".to_string())]);
+}
+
+#[test]
+fn typed_computing_script_with_unknown_type_is_not_loaded() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![NotebookSymbolDefinition::TypedComputingScript { name: "y".to_string(), type_name: "Unknown".to_string(), script: "1.0".to_string() }]
+    };
+
+    assert!(definition.edits(&FloScriptSymbolTable::default_table(), &ScriptTypeRegistry::with_standard_types()).err() == Some(FloScriptError::UnknownTypeName("Unknown".to_string())));
+}
+
+#[test]
+fn types_are_saved_with_their_registered_names() {
+    let host        = GluonScriptHost::new();
+    let mut types   = ScriptTypeRegistry::new();
+
+    types.register::<String>("text");
+    types.register::<Vec<f64>>("points");
+    host.editor().set_input_type::<String>(FloScriptSymbol::with_name("title"));
+    host.editor().set_typed_computing_script::<Vec<f64>>(FloScriptSymbol::with_name("line"), "[1.0, 2.0]");
+
+    let definition  = host.notebook().definition(&types).unwrap();
+
+    assert!(definition.symbols == vec![
+        NotebookSymbolDefinition::TypedComputingScript { name: "line".to_string(), type_name: "points".to_string(), script: "[1.0, 2.0]".to_string() },
+        NotebookSymbolDefinition::Input { name: "title".to_string(), type_name: "text".to_string() }
+    ]);
+
+    let new_host    = GluonScriptHost::new();
+    new_host.editor().load_definition(&definition, &types).unwrap();
+
+    assert!(new_host.notebook().definition(&types).unwrap() == definition);
+}
+
+#[test]
+fn unregistered_types_are_not_saved() {
+    let host        = GluonScriptHost::new();
+
+    host.editor().set_input_type::<Vec<f64>>(FloScriptSymbol::with_name("points"));
+
+    let result      = host.notebook().definition(&ScriptTypeRegistry::with_standard_types());
+
+    assert!(result == Err(FloScriptError::UnregisteredType(std::any::type_name::<Vec<f64>>().to_string())));
+}
+
+#[test]
+fn registering_a_type_again_replaces_its_name() {
+    let mut types   = ScriptTypeRegistry::with_standard_types();
+
+    types.register::<f64>("number");
+
+    assert!(types.name_of_type(&f64::description()) == Some("number"));
+    assert!(types.type_with_name("number").map(|number| number.is::<f64>()) == Some(true));
+    assert!(types.type_with_name("f64").is_none());
+}
+
+#[test]
+fn default_registry_is_empty() {
+    assert!(ScriptTypeRegistry::default().type_with_name("i32").is_none());
+    assert!(ScriptTypeRegistry::new().type_with_name("i32").is_none());
+    assert!(ScriptTypeRegistry::with_standard_types().type_with_name("i32").is_some());
+}
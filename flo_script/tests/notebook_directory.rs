@@ -0,0 +1,235 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+
+use std::fs;
+use std::thread;
+use std::sync::mpsc;
+use std::path::{PathBuf};
+use std::time::{Duration, Instant};
+
+///
+/// Creates an empty directory to use for a test
+///
+fn test_directory(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flo_script_{}_{}", name, std::process::id()));
+
+    fs::remove_dir_all(&path).ok();
+    fs::create_dir_all(&path).unwrap();
+
+    path
+}
+
+#[test]
+fn first_scan_defines_everything() {
+    let path            = test_directory("first_scan");
+    fs::write(path.join("y.glu"), "1 + 2").unwrap();
+    fs::write(path.join("inputs.manifest"), "# Inputs\nx: i32\n").unwrap();
+    fs::write(path.join("notes.txt"), "Not a script").unwrap();
+    fs::create_dir(path.join("ns")).unwrap();
+    fs::write(path.join("ns").join("z.glu"), "3").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    let edits           = directory.scan().unwrap();
+
+    fs::remove_dir_all(&path).ok();
+
+    assert!(edits == vec![
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("z"), "3".to_string())]),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("x"), i32::description()),
+        ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("y"), "1 + 2".to_string())
+    ]);
+}
+
+#[test]
+fn unchanged_directory_generates_no_edits() {
+    let path            = test_directory("unchanged");
+    fs::write(path.join("y.glu"), "1 + 2").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    directory.scan().unwrap();
+    let edits           = directory.scan().unwrap();
+
+    fs::remove_dir_all(&path).ok();
+
+    assert!(edits == vec![]);
+}
+
+#[test]
+fn rescan_reports_changes() {
+    let path            = test_directory("rescan");
+    fs::write(path.join("a.glu"), "1").unwrap();
+    fs::write(path.join("b.glu"), "2").unwrap();
+    fs::create_dir(path.join("ns")).unwrap();
+    fs::write(path.join("ns").join("c.glu"), "3").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    directory.scan().unwrap();
+
+    fs::write(path.join("a.glu"), "4").unwrap();
+    fs::remove_file(path.join("b.glu")).unwrap();
+    fs::write(path.join("ns").join("d.glu"), "5").unwrap();
+    let edits           = directory.scan().unwrap();
+
+    fs::remove_dir_all(&path).ok();
+
+    assert!(edits == vec![
+        ScriptEdit::UndefineSymbol(FloScriptSymbol::with_name("b")),
+        ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("a"), "4".to_string()),
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("ns"), vec![ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("d"), "5".to_string())])
+    ]);
+}
+
+#[test]
+fn rescans_do_not_keep_extra_names() {
+    let path            = test_directory("rescan_names");
+    fs::write(path.join("a.glu"), "1").unwrap();
+    fs::write(path.join("b.glu"), "2").unwrap();
+
+    let host            = GluonScriptHost::new();
+    let mut directory   = NotebookDirectory::new(&path, host.symbols(), ScriptTypeRegistry::with_standard_types());
+
+    host.editor().edit(ScriptEdit::WithNamespace(host.symbols().symbol("ns"), vec![]));
+    host.editor().send_edits(stream::iter_ok(directory.scan().unwrap())).wait().unwrap();
+
+    fs::write(path.join("a.glu"), "3").unwrap();
+    fs::remove_file(path.join("b.glu")).unwrap();
+    host.editor().send_edits(stream::iter_ok(directory.scan().unwrap())).wait().unwrap();
+    host.editor().send_edits(stream::iter_ok(directory.scan().unwrap())).wait().unwrap();
+
+    fs::remove_dir_all(&path).ok();
+
+    // Once the directory is gone, the names are only kept by the notebook, so `b` has been freed and `a` is freed with the notebook
+    drop(directory);
+    assert!(host.symbols().find("b").is_none());
+
+    host.editor().undefine_symbol(host.symbols().find("a").unwrap());
+    assert!(host.symbols().find("a").is_none());
+}
+
+#[test]
+fn invalid_manifest_is_an_error() {
+    let path            = test_directory("invalid_manifest");
+    fs::write(path.join("inputs.manifest"), "x i32\n").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    let result          = directory.scan();
+
+    fs::remove_dir_all(&path).ok();
+
+    match result {
+        Err(FloScriptError::LoadError(_))   => { }
+        other                               => panic!("Unexpected result {:?}", other)
+    }
+}
+
+#[test]
+fn manifest_uses_registered_type_names() {
+    let path            = test_directory("registered_names");
+    fs::write(path.join("inputs.manifest"), "title: String
+points: points
+").unwrap();
+
+    let mut types       = ScriptTypeRegistry::with_standard_types();
+    types.register::<Vec<f64>>("points");
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), types);
+    let edits           = directory.scan().unwrap();
+
+    fs::remove_dir_all(&path).ok();
+
+    assert!(edits == vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("points"), <Vec<f64>>::description()),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("title"), String::description())
+    ]);
+}
+
+#[test]
+fn script_and_namespace_with_same_name_is_an_error() {
+    let path            = test_directory("script_namespace_collision");
+    fs::write(path.join("foo.glu"), "1").unwrap();
+    fs::create_dir(path.join("foo")).unwrap();
+    fs::write(path.join("foo").join("bar.glu"), "2").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    let result          = directory.scan();
+
+    fs::remove_dir_all(&path).ok();
+
+    match result {
+        Err(FloScriptError::LoadError(message)) => assert!(message.contains("`foo` is defined more than once")),
+        other                                   => panic!("Unexpected result {:?}", other)
+    }
+}
+
+#[test]
+fn script_and_input_with_same_name_is_an_error() {
+    let path            = test_directory("script_input_collision");
+    fs::write(path.join("foo.glu"), "1").unwrap();
+    fs::write(path.join("inputs.manifest"), "foo: i32
+").unwrap();
+
+    let mut directory   = NotebookDirectory::new(&path, FloScriptSymbolTable::default_table(), ScriptTypeRegistry::with_standard_types());
+    let result          = directory.scan();
+
+    fs::remove_dir_all(&path).ok();
+
+    match result {
+        Err(FloScriptError::LoadError(message)) => assert!(message.contains("`foo` is defined more than once")),
+        other                                   => panic!("Unexpected result {:?}", other)
+    }
+}
+
+#[test]
+fn watch_directory_for_changes() {
+    let path            = test_directory("watch");
+    fs::write(path.join("y.glu"), "1 + 2").unwrap();
+
+    let host            = GluonScriptHost::new();
+    let directory       = NotebookDirectory::new(&path, host.symbols(), ScriptTypeRegistry::with_standard_types());
+    let _watching       = host.editor().send_edits(directory.watch(Duration::from_millis(10), |_error| { }));
+
+    // Waits for the notebook to contain a particular computing script
+    let wait_for_script = |script: &str| {
+        let expected    = NotebookDefinition { run_io: false, symbols: vec![NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: script.to_string() }] };
+        let start       = Instant::now();
+
+        while host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() != expected {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    wait_for_script("1 + 2");
+
+    fs::write(path.join("y.glu"), "3 + 4").unwrap();
+    wait_for_script("3 + 4");
+
+    fs::remove_dir_all(&path).ok();
+}
+
+#[test]
+fn report_errors_while_watching() {
+    let path            = test_directory("watch_errors");
+    fs::write(path.join("y.glu"), "1 + 2").unwrap();
+
+    let (send_error, errors) = mpsc::channel();
+    let host            = GluonScriptHost::new();
+    let directory       = NotebookDirectory::new(&path, host.symbols(), ScriptTypeRegistry::with_standard_types());
+    let _watching       = host.editor().send_edits(directory.watch(Duration::from_millis(10), move |error| { send_error.send(error).ok(); }));
+
+    // Wait for the directory to be loaded, then remove it
+    let start           = Instant::now();
+    while host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap().symbols.is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    fs::remove_dir_all(&path).unwrap();
+
+    // The failed scans are reported instead of being ignored
+    let error           = errors.recv_timeout(Duration::from_secs(10)).expect("error report");
+    assert!(matches!(error, FloScriptError::LoadError(_)));
+}
@@ -0,0 +1,117 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+
+#[test]
+fn list_symbols_in_namespace() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+    let z           = FloScriptSymbol::with_name("z");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(y, "x + 1");
+    host.editor().set_constant(z, "Hello");
+
+    let symbols     = host.notebook().symbols();
+
+    assert!(symbols.len() == 3);
+
+    assert!(symbols[0].symbol() == x);
+    assert!(symbols[0].kind == SymbolKind::Input);
+    assert!(symbols[0].value_type == Some(i32::description()));
+    assert!(symbols[0].status == SymbolStatus::WaitingForInput);
+
+    assert!(symbols[1].symbol() == y);
+    assert!(symbols[1].kind == SymbolKind::ComputingScript);
+    assert!(symbols[1].value_type == Some(i64::description()));
+    assert!(symbols[1].script == Some("x + 1".to_string()));
+    assert!(symbols[1].status == SymbolStatus::NotStarted);
+
+    assert!(symbols[2].symbol() == z);
+    assert!(symbols[2].kind == SymbolKind::Constant);
+    assert!(symbols[2].value_type == Some(String::description()));
+    assert!(symbols[2].status == SymbolStatus::UpToDate);
+}
+
+#[test]
+fn unevaluated_script_has_inferred_type() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+    let z           = FloScriptSymbol::with_name("z");
+
+    host.editor().set_computing_script(x, "\"Hello\"");
+    host.editor().set_computing_script(y, "1.5 * 2.0");
+    host.editor().set_computing_script(z, "not_defined + 1");
+
+    let symbols     = host.notebook().symbols();
+
+    // The types are found without evaluating the scripts
+    assert!(symbols[0].value_type == Some(String::description()));
+    assert!(symbols[0].status == SymbolStatus::NotStarted);
+    assert!(symbols[1].value_type == Some(f64::description()));
+    assert!(symbols[1].status == SymbolStatus::NotStarted);
+
+    // Scripts that can't be type-checked don't have a type
+    assert!(symbols[2].value_type.is_none());
+}
+
+#[test]
+fn input_is_up_to_date_once_stream_is_attached() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+
+    assert!(host.notebook().symbols()[0].status == SymbolStatus::UpToDate);
+}
+
+#[test]
+fn symbols_include_metadata() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let metadata    = SymbolMetadata::new().with_label("X");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_metadata(x, metadata.clone());
+
+    assert!(host.notebook().symbols()[0].metadata == Some(metadata));
+}
+
+#[test]
+fn list_all_symbols_recursively() {
+    let host        = GluonScriptHost::new();
+
+    host.editor().set_input_type_at::<f64>(&FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap());
+    host.editor().set_input_type_at::<f64>(&FloScriptPath::parse(&host.symbols(), "layers.foreground.opacity").unwrap());
+    host.editor().set_constant_at(&FloScriptPath::parse(&host.symbols(), "title").unwrap(), "Picture");
+
+    let paths       = host.notebook().all_symbols()
+        .into_iter()
+        .map(|symbol| (symbol.path.to_string(), symbol.kind))
+        .collect::<Vec<_>>();
+
+    assert!(paths == vec![
+        ("layers".to_string(), SymbolKind::Namespace),
+        ("layers.background".to_string(), SymbolKind::Namespace),
+        ("layers.background.opacity".to_string(), SymbolKind::Input),
+        ("layers.foreground".to_string(), SymbolKind::Namespace),
+        ("layers.foreground.opacity".to_string(), SymbolKind::Input),
+        ("title".to_string(), SymbolKind::Constant)
+    ]);
+}
+
+#[test]
+fn list_child_namespace() {
+    let host        = GluonScriptHost::new();
+
+    host.editor().set_input_type_at::<f64>(&FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap());
+
+    let layers      = host.notebook().namespace(host.symbols().symbol("layers")).unwrap();
+    let paths       = layers.all_symbols().into_iter().map(|symbol| symbol.path.to_string()).collect::<Vec<_>>();
+
+    assert!(paths == vec!["background".to_string(), "background.opacity".to_string()]);
+}
@@ -0,0 +1,110 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+use std::thread;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn reader_switches_to_new_definition() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok(vec![1, 2, 3])).unwrap();
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+
+    // Values still waiting in the old input are discarded
+    host.editor().set_constant(x, 42);
+    assert!(output_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn reader_follows_constant_replaced_by_input() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 1);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok(vec![5])).unwrap();
+    assert!(output_stream.wait_stream() == Some(Ok(5)));
+}
+
+#[test]
+fn reader_ends_when_redefined_with_different_type() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 1);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+
+    host.editor().set_constant(x, "Hello");
+    assert!(output_stream.wait_stream().is_none());
+}
+
+#[test]
+fn reader_ends_when_symbol_is_undefined() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 1);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(1)));
+
+    host.editor().undefine_symbol(x);
+    assert!(output_stream.wait_stream().is_none());
+}
+
+#[test]
+fn reader_ends_when_notebook_is_cleared() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+
+    host.editor().clear();
+    assert!(output_stream.wait_stream().is_none());
+}
+
+#[test]
+fn running_evaluation_is_cancelled_when_script_is_redefined() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "let forever n : Int -> Int = forever (n + 1)\nforever 0");
+
+    // The script never finishes on its own, so the reader is stuck evaluating it until it's interrupted
+    let output_stream       = host.notebook().receive_output::<i32>(x).expect("output stream");
+    let (send, recv)        = mpsc::channel();
+    let reader              = thread::spawn(move || {
+        let mut output_stream = executor::spawn(output_stream);
+        send.send(output_stream.wait_stream()).unwrap();
+    });
+
+    // Wait for the evaluation to start
+    while host.notebook().status(x) != Some(SymbolStatus::Computing) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    thread::sleep(Duration::from_millis(50));
+    assert!(recv.try_recv().is_err());
+
+    host.editor().set_computing_script(x, "42");
+
+    // Interrupting the old evaluation lets the reader carry on with the new definition
+    assert!(recv.recv_timeout(Duration::from_secs(10)) == Ok(Some(Ok(42))));
+    reader.join().unwrap();
+}
@@ -0,0 +1,86 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::future;
+use futures::future::Executor;
+use futures::stream;
+use futures::executor;
+
+use std::thread;
+use std::sync::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+///
+/// Executor that runs each future on its own thread
+///
+struct ThreadExecutor;
+
+impl Executor<ScriptFuture> for ThreadExecutor {
+    fn execute(&self, future: ScriptFuture) -> Result<(), future::ExecuteError<ScriptFuture>> {
+        thread::spawn(move || { executor::spawn(future).wait_future().ok(); });
+        Ok(())
+    }
+}
+
+#[test]
+fn host_with_executor_reads_constants() {
+    let host                = GluonScriptHost::with_executor(ThreadExecutor);
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 42);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn host_with_executor_shares_symbol_table() {
+    let symbols             = FloScriptSymbolTable::new();
+    let host                = GluonScriptHost::with_symbol_table_and_executor(symbols.clone(), ThreadExecutor);
+
+    let x                   = host.symbols().symbol("x");
+
+    assert!(symbols.find("x") == Some(x));
+}
+
+///
+/// Executor that counts the futures it's given before running them on their own threads
+///
+struct CountingExecutor(Arc<AtomicUsize>);
+
+impl Executor<ScriptFuture> for CountingExecutor {
+    fn execute(&self, future: ScriptFuture) -> Result<(), future::ExecuteError<ScriptFuture>> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        ThreadExecutor.execute(future)
+    }
+}
+
+#[test]
+fn computing_script_is_evaluated_by_executor() {
+    let evaluations         = Arc::new(AtomicUsize::new(0));
+    let host                = GluonScriptHost::with_executor(CountingExecutor(Arc::clone(&evaluations)));
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(x, "1 + 2");
+    assert!(evaluations.load(Ordering::SeqCst) == 0);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i64>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(3)));
+    assert!(evaluations.load(Ordering::SeqCst) == 1);
+}
+
+#[test]
+fn script_reading_symbols_is_evaluated_by_executor() {
+    let evaluations         = Arc::new(AtomicUsize::new(0));
+    let host                = GluonScriptHost::with_executor(CountingExecutor(Arc::clone(&evaluations)));
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(y, "x + 1");
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(y).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(2)));
+    assert!(evaluations.load(Ordering::SeqCst) == 1);
+}
@@ -0,0 +1,123 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::executor;
+
+#[test]
+fn script_reads_input() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(y, "x + 1");
+    host.notebook().send_input_tick(vec![InputValue::new(x, 1)]).unwrap();
+
+    let mut y_stream        = executor::spawn(host.notebook().receive_output_state::<i32>(y).expect("y stream"));
+    assert!(y_stream.wait_stream() == Some(Ok(2)));
+
+    host.notebook().send_input_tick(vec![InputValue::new(x, 41)]).unwrap();
+    assert!(y_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn script_reads_several_symbols() {
+    let host                = GluonScriptHost::new();
+    let width               = FloScriptSymbol::with_name("width");
+    let height              = FloScriptSymbol::with_name("height");
+    let area                = FloScriptSymbol::with_name("area");
+
+    host.editor().set_constant(width, 3.0);
+    host.editor().set_constant(height, 4.0);
+    host.editor().set_computing_script(area, "width * height");
+
+    let mut area_stream     = executor::spawn(host.notebook().receive_output_state::<f64>(area).expect("area stream"));
+    assert!(area_stream.wait_stream() == Some(Ok(12.0)));
+}
+
+#[test]
+fn script_reads_computing_script() {
+    let host                = GluonScriptHost::new();
+    let a                   = FloScriptSymbol::with_name("a");
+    let b                   = FloScriptSymbol::with_name("b");
+
+    host.editor().set_computing_script(a, "1 + 2");
+    host.editor().set_computing_script(b, "a * 2");
+
+    let mut b_stream        = executor::spawn(host.notebook().receive_output_state::<i64>(b).expect("b stream"));
+    assert!(b_stream.wait_stream() == Some(Ok(6)));
+}
+
+#[test]
+fn script_reads_redefined_symbol() {
+    let host                = GluonScriptHost::new();
+    let a                   = FloScriptSymbol::with_name("a");
+    let b                   = FloScriptSymbol::with_name("b");
+
+    host.editor().set_computing_script(a, "1");
+    host.editor().set_computing_script(b, "a + 1");
+
+    let mut b_stream        = executor::spawn(host.notebook().receive_output_state::<i64>(b).expect("b stream"));
+    assert!(b_stream.wait_stream() == Some(Ok(2)));
+
+    host.editor().set_computing_script(a, "10");
+    assert!(b_stream.wait_stream() == Some(Ok(11)));
+}
+
+#[test]
+fn script_reads_symbol_defined_later() {
+    let host                = GluonScriptHost::new();
+    let a                   = FloScriptSymbol::with_name("a");
+    let b                   = FloScriptSymbol::with_name("b");
+
+    host.editor().set_computing_script(b, "a + 1");
+    assert!(host.notebook().receive_output_state::<i64>(b).is_err());
+
+    host.editor().set_constant(a, 1i64);
+
+    let mut b_stream        = executor::spawn(host.notebook().receive_output_state::<i64>(b).expect("b stream"));
+    assert!(b_stream.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn script_reading_symbol_as_wrong_type_is_an_error() {
+    let host                = GluonScriptHost::new();
+    let name                = FloScriptSymbol::with_name("name");
+    let length              = FloScriptSymbol::with_name("length");
+
+    host.editor().set_constant(name, "Background");
+    host.editor().set_computing_script(length, "name + 1");
+
+    match host.notebook().receive_output_state::<i64>(length) {
+        Err(FloScriptError::ScriptError(_)) => { },
+        _                                   => panic!("Script should not compile")
+    }
+}
+
+#[test]
+fn script_reads_script_with_declared_type() {
+    let host                = GluonScriptHost::new();
+    let names               = FloScriptSymbol::with_name("names");
+    let count               = FloScriptSymbol::with_name("count");
+
+    host.editor().set_typed_computing_script::<Vec<String>>(names, "[\"a\", \"b\", \"c\"]");
+    host.editor().set_computing_script(count, "let array = import! std.array in array.len names");
+
+    let mut count_stream    = executor::spawn(host.notebook().receive_output_state::<i64>(count).expect("count stream"));
+    assert!(count_stream.wait_stream() == Some(Ok(3)));
+}
+
+#[test]
+fn reading_undeclared_record_script_is_unsupported() {
+    let host                = GluonScriptHost::new();
+    let point               = FloScriptSymbol::with_name("point");
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.editor().set_computing_script(point, "{ x = 1, y = 2 }");
+    host.editor().set_computing_script(x, "point.x");
+
+    match host.notebook().receive_output_state::<i64>(x) {
+        Err(FloScriptError::UnsupportedType(_)) => { },
+        _                                       => panic!("Record type should need a declaration")
+    }
+}
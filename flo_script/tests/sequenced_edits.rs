@@ -0,0 +1,127 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn acknowledge_edits_in_order() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::SetInputType(input_x, i32::description()),
+        ScriptEdit::SetInputType(input_y, i32::description()),
+        ScriptEdit::UndefineSymbol(input_x)
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements == vec![
+        EditAcknowledgement { sequence: 0, result: Ok(()) },
+        EditAcknowledgement { sequence: 1, result: Ok(()) },
+        EditAcknowledgement { sequence: 2, result: Ok(()) }
+    ]);
+}
+
+#[test]
+fn acknowledge_failed_edits() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+    let input_y             = FloScriptSymbol::with_name("y");
+
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::UndefineSymbol(input_x),
+        ScriptEdit::SetInputType(input_x, i32::description()),
+        ScriptEdit::WithNamespace(input_x, vec![ScriptEdit::SetInputType(input_y, i32::description())])
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements == vec![
+        EditAcknowledgement { sequence: 0, result: Err(FloScriptError::UndefinedSymbol(input_x)) },
+        EditAcknowledgement { sequence: 1, result: Ok(()) },
+        EditAcknowledgement { sequence: 2, result: Err(FloScriptError::NotANamespace) }
+    ]);
+}
+
+#[test]
+fn sequence_continues_after_other_edits() {
+    let host                = GluonScriptHost::new();
+    let input_x             = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().undefine_symbol(input_x);
+
+    let edits               = stream::iter_ok(vec![ScriptEdit::Clear]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements == vec![EditAcknowledgement { sequence: 2, result: Ok(()) }]);
+}
+
+#[test]
+fn namespace_edits_stop_at_the_first_failure() {
+    let host                = GluonScriptHost::new();
+    let namespace           = FloScriptSymbol::with_name("ns");
+    let input_a             = FloScriptSymbol::with_name("a");
+    let input_b             = FloScriptSymbol::with_name("b");
+    let constant            = FloScriptSymbol::with_name("c");
+    let undefined           = FloScriptSymbol::with_name("undefined");
+
+    host.editor().edit(ScriptEdit::WithNamespace(namespace, vec![
+        ScriptEdit::SetInputType(input_a, i32::description()),
+        ScriptEdit::SetConstant(constant, ScriptValue::I32(1))
+    ]));
+
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::WithNamespace(namespace, vec![
+            ScriptEdit::SetInputType(input_b, i32::description()),
+            ScriptEdit::SetConstant(constant, ScriptValue::I32(2)),
+            ScriptEdit::UndefineSymbol(undefined),
+            ScriptEdit::UndefineSymbol(input_a)
+        ])
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements == vec![EditAcknowledgement { sequence: 1, result: Err(FloScriptError::UndefinedSymbol(undefined)) }]);
+
+    // The edits before the one that failed are applied, and the ones after it are not
+    let definition          = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    let ns_definition       = match &definition.symbols[..] {
+        [NotebookSymbolDefinition::Namespace { definition, .. }]  => definition.clone(),
+        _                                                       => panic!("Expected a single namespace")
+    };
+
+    assert!(ns_definition.symbols.contains(&NotebookSymbolDefinition::Input { name: "a".to_string(), type_name: "i32".to_string() }));
+    assert!(ns_definition.symbols.contains(&NotebookSymbolDefinition::Input { name: "b".to_string(), type_name: "i32".to_string() }));
+
+    let mut constant_value  = executor::spawn(host.notebook().receive_output_at::<i32>(&FloScriptPath::new(vec![namespace], constant)).unwrap());
+    assert!(constant_value.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn namespace_edits_define_typed_scripts() {
+    let host                = GluonScriptHost::new();
+    let namespace           = FloScriptSymbol::with_name("ns");
+    let input_x             = FloScriptSymbol::with_name("x");
+    let output_y            = FloScriptSymbol::with_name("y");
+
+    let edits               = stream::iter_ok(vec![
+        ScriptEdit::WithNamespace(namespace, vec![
+            ScriptEdit::SetConstant(input_x, ScriptValue::I32(1)),
+            ScriptEdit::SetTypedComputingScript(output_y, i32::description(), "x + 1".to_string())
+        ]),
+        ScriptEdit::WithNamespace(namespace, vec![
+            ScriptEdit::SetTypedComputingScript(output_y, i32::description(), "\"text\"".to_string())
+        ])
+    ]);
+    let acknowledgements    = host.editor().send_sequenced_edits(edits);
+    let acknowledgements    = executor::spawn(acknowledgements.collect()).wait_future().unwrap();
+
+    assert!(acknowledgements[0] == EditAcknowledgement { sequence: 0, result: Ok(()) });
+    assert!(matches!(acknowledgements[1].result, Err(FloScriptError::IncorrectType { .. })));
+}
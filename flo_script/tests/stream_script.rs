@@ -32,7 +32,7 @@ fn cannot_read_input_stream_as_wrong_type() {
 
     host.editor().set_input_type::<i32>(input_x);
 
-    assert!(host.notebook().receive_output::<u32>(input_x).err().unwrap() == FloScriptError::IncorrectType);
+    assert!(host.notebook().receive_output::<u32>(input_x).err().unwrap() == FloScriptError::incorrect_type(&i32::description(), &u32::description()));
 }
 
 #[test]
@@ -0,0 +1,115 @@
+use flo_script::*;
+
+#[test]
+fn named_symbol_key_is_its_name() {
+    let symbol = FloScriptSymbol::with_name("x");
+
+    assert!(symbol.key() == FloScriptSymbolKey::Named("x".to_string()));
+    assert!(FloScriptSymbol::with_key(&symbol.key()) == symbol);
+}
+
+#[test]
+fn anonymous_symbol_key_is_stable() {
+    let symbol  = FloScriptSymbol::new();
+    let key     = symbol.key();
+
+    assert!(symbol.key() == key);
+    assert!(FloScriptSymbol::with_key(&key) == symbol);
+}
+
+#[test]
+fn anonymous_symbols_have_different_keys() {
+    assert!(FloScriptSymbol::new().key() != FloScriptSymbol::new().key());
+    assert!(FloScriptSymbolKey::new_anonymous() != FloScriptSymbolKey::new_anonymous());
+}
+
+#[test]
+fn restore_anonymous_symbol_in_new_table() {
+    // Simulate saving a key in one session and loading it in another
+    let old_table   = FloScriptSymbolTable::new();
+    let old_symbol  = FloScriptSymbol::new();
+    let key         = old_table.key_for(old_symbol);
+    drop(old_table);
+
+    let new_table   = FloScriptSymbolTable::new();
+    let new_symbol  = new_table.symbol_for_key(&key);
+
+    assert!(new_symbol != old_symbol);
+    assert!(new_table.symbol_for_key(&key) == new_symbol);
+    assert!(new_table.key_for(new_symbol) == key);
+}
+
+#[test]
+fn restore_named_symbol_in_new_table() {
+    let old_table   = FloScriptSymbolTable::new();
+    let key         = old_table.key_for(old_table.symbol("x"));
+
+    let new_table   = FloScriptSymbolTable::new();
+    let new_symbol  = new_table.symbol_for_key(&key);
+
+    assert!(new_symbol == new_table.symbol("x"));
+    assert!(new_symbol.name() == Some("x".to_string()));
+}
+
+#[test]
+fn released_anonymous_symbol_loses_key() {
+    let table       = FloScriptSymbolTable::new();
+    let key         = FloScriptSymbolKey::new_anonymous();
+    let symbol      = table.symbol_for_key(&key);
+
+    table.release(symbol);
+
+    assert!(table.symbol_for_key(&key) != symbol);
+}
+
+#[test]
+fn anonymous_symbol_keeps_key_assigned_by_table() {
+    // Keys of symbols created in this session aren't stored, so there's nothing to free when they're released
+    let table       = FloScriptSymbolTable::new();
+    let symbol      = FloScriptSymbol::new();
+    let key         = table.key_for(symbol);
+
+    table.release(symbol);
+
+    assert!(table.key_for(symbol) == key);
+    assert!(table.symbol_for_key(&key) == symbol);
+}
+
+#[test]
+fn tables_can_find_keys_for_each_others_symbols_at_once() {
+    let first       = FloScriptSymbolTable::new();
+    let second      = FloScriptSymbolTable::new();
+    let first_x     = first.symbol("x");
+    let second_y    = second.symbol("y");
+
+    // Each table names the other table's symbol while the other thread is using that table
+    let threads     = vec![(first.clone(), second_y, "y"), (second.clone(), first_x, "x")].into_iter()
+        .map(|(table, symbol, name)| std::thread::spawn(move || {
+            for _ in 0..10000 {
+                assert!(table.key_for(symbol) == FloScriptSymbolKey::Named(name.to_string()));
+            }
+        }))
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn keys_can_be_serialized() {
+    let named       = FloScriptSymbolKey::Named("x".to_string());
+    let anonymous   = FloScriptSymbolKey::new_anonymous();
+
+    let named_json  = serde_json::to_string(&named).unwrap();
+    let anon_json   = serde_json::to_string(&anonymous).unwrap();
+
+    assert!(serde_json::from_str::<FloScriptSymbolKey>(&named_json).unwrap() == named);
+    assert!(serde_json::from_str::<FloScriptSymbolKey>(&anon_json).unwrap() == anonymous);
+}
+
+#[test]
+fn display_keys() {
+    assert!(FloScriptSymbolKey::Named("x".to_string()).to_string() == "x");
+    assert!(FloScriptSymbolKey::Anonymous(1).to_string() == "<00000000000000000000000000000001>");
+}
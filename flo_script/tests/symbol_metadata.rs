@@ -0,0 +1,115 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn read_metadata_from_notebook() {
+    let host        = GluonScriptHost::new();
+    let opacity     = FloScriptSymbol::with_name("opacity");
+    let metadata    = SymbolMetadata::new()
+        .with_label("Opacity")
+        .with_units("%")
+        .with_display_hint(DisplayHint::Slider { min: 0.0, max: 100.0, step: Some(1.0) })
+        .with_tag("appearance");
+
+    host.editor().set_input_type::<f64>(opacity);
+    host.editor().set_metadata(opacity, metadata.clone());
+
+    assert!(host.notebook().metadata(opacity) == Some(metadata));
+    assert!(host.notebook().metadata(FloScriptSymbol::with_name("not_defined")).is_none());
+}
+
+#[test]
+fn metadata_is_kept_when_symbol_is_redefined() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let metadata    = SymbolMetadata::new().with_description("The x coordinate");
+
+    host.editor().set_input_type::<f64>(x);
+    host.editor().set_metadata(x, metadata.clone());
+    host.editor().set_input_type::<i32>(x);
+
+    assert!(host.notebook().metadata(x) == Some(metadata));
+}
+
+#[test]
+fn metadata_is_removed_with_symbol() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<f64>(x);
+    host.editor().set_metadata(x, SymbolMetadata::new().with_label("X"));
+    host.editor().undefine_symbol(x);
+    host.editor().set_input_type::<f64>(x);
+
+    assert!(host.notebook().metadata(x).is_none());
+}
+
+#[test]
+fn metadata_moves_with_symbol() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+    let metadata    = SymbolMetadata::new().with_label("X");
+
+    host.editor().set_input_type::<f64>(x);
+    host.editor().set_metadata(x, metadata.clone());
+    host.editor().rename_symbol(x, y);
+
+    assert!(host.notebook().metadata(x).is_none());
+    assert!(host.notebook().metadata(y) == Some(metadata));
+}
+
+#[test]
+fn cannot_set_metadata_for_undefined_symbol() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    let edits               = stream::iter_ok(vec![ScriptEdit::SetMetadata(x, SymbolMetadata::new())]);
+    let acknowledgements    = executor::spawn(host.editor().send_sequenced_edits(edits).collect()).wait_future().unwrap();
+
+    assert!(acknowledgements[0].result == Err(FloScriptError::UndefinedSymbol(x)));
+}
+
+#[test]
+fn updates_start_with_current_definitions() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let metadata    = SymbolMetadata::new().with_display_hint(DisplayHint::ColourPicker);
+
+    host.editor().set_input_type::<u32>(x);
+    host.editor().set_metadata(x, metadata.clone());
+
+    let mut updates = executor::spawn(host.notebook().updates());
+
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::DefinedInputSymbol(x, u32::description()))));
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::SetMetadata(x, metadata))));
+}
+
+#[test]
+fn updates_report_metadata_changes() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let metadata    = SymbolMetadata::new().with_label("X");
+
+    let mut updates = executor::spawn(host.notebook().updates());
+
+    host.editor().set_input_type::<bool>(x);
+    host.editor().set_metadata(x, metadata.clone());
+    host.editor().undefine_symbol(x);
+
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::DefinedInputSymbol(x, bool::description()))));
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::SetMetadata(x, metadata))));
+    assert!(updates.wait_stream() == Some(Ok(NotebookUpdate::UndefinedSymbol(x))));
+}
+
+#[test]
+fn metadata_can_be_serialized() {
+    let metadata    = SymbolMetadata::new().with_label("Size").with_display_hint(DisplayHint::Custom("size_picker".to_string()));
+    let json        = serde_json::to_string(&metadata).unwrap();
+
+    assert!(serde_json::from_str::<SymbolMetadata>(&json).unwrap() == metadata);
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use self::common::*;
+
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::*;
+use futures::stream;
+use futures::executor;
+
+fn set_name_policy(host: &GluonScriptHost, policy: SymbolNamePolicy) -> FloScriptResult<()> {
+    let acknowledgements = host.editor().send_sequenced_gluon_edits(stream::iter_ok(vec![GluonScriptEdit::SetNamePolicy(policy)]));
+
+    executor::spawn(acknowledgements.collect()).wait_future().unwrap()[0].result.clone()
+}
+
+#[test]
+fn unusable_names_are_rejected_by_default() {
+    let host    = GluonScriptHost::new();
+    let name    = FloScriptSymbol::with_name("my value");
+    let results = apply_edits(&host, vec![ScriptEdit::SetInputType(name, i32::description())]);
+
+    assert!(results == vec![Err(FloScriptError::InvalidSymbolName("my value".to_string()))]);
+    assert!(host.notebook().receive_output::<i32>(name).is_err());
+}
+
+#[test]
+fn allow_policy_accepts_any_name() {
+    let host    = GluonScriptHost::new();
+    let name    = FloScriptSymbol::with_name("my value");
+    set_name_policy(&host, SymbolNamePolicy::Allow).unwrap();
+
+    let results = apply_edits(&host, vec![ScriptEdit::SetInputType(name, i32::description())]);
+
+    assert!(results == vec![Ok(())]);
+    assert!(host.notebook().receive_output::<i32>(name).is_ok());
+}
+
+#[test]
+fn reject_names_that_cannot_be_used_in_scripts() {
+    let host    = GluonScriptHost::new();
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("my value"), i32::description()),
+        ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("let"), "1".to_string()),
+        ScriptEdit::SetConstant(FloScriptSymbol::with_name("Width"), ScriptValue::I32(1)),
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("my namespace"), vec![]),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("valid_name"), i32::description())
+    ]);
+
+    assert!(results == vec![
+        Err(FloScriptError::InvalidSymbolName("my value".to_string())),
+        Err(FloScriptError::InvalidSymbolName("let".to_string())),
+        Err(FloScriptError::InvalidSymbolName("Width".to_string())),
+        Err(FloScriptError::InvalidSymbolName("my namespace".to_string())),
+        Ok(())
+    ]);
+    assert!(host.notebook().receive_output::<i32>(FloScriptSymbol::with_name("my value")).is_err());
+    assert!(host.notebook().namespace(FloScriptSymbol::with_name("my namespace")).is_none());
+}
+
+#[test]
+fn reject_invalid_name_when_moving() {
+    let host    = GluonScriptHost::new();
+    let x       = FloScriptSymbol::with_name("x");
+    set_name_policy(&host, SymbolNamePolicy::Reject).unwrap();
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(x, i32::description()),
+        ScriptEdit::MoveSymbol(vec![x], vec![FloScriptSymbol::with_name("new x")])
+    ]);
+
+    assert!(results[1] == Err(FloScriptError::InvalidSymbolName("new x".to_string())));
+    assert!(host.notebook().receive_output::<i32>(x).is_ok());
+}
+
+#[test]
+fn anonymous_symbols_are_allowed() {
+    let host    = GluonScriptHost::new();
+    set_name_policy(&host, SymbolNamePolicy::Reject).unwrap();
+
+    let results = apply_edits(&host, vec![ScriptEdit::SetInputType(FloScriptSymbol::new(), i32::description())]);
+
+    assert!(results == vec![Ok(())]);
+}
+
+#[test]
+fn mangle_policy_allows_any_name() {
+    let host    = GluonScriptHost::new();
+    let name    = FloScriptSymbol::with_name("my value");
+
+    set_name_policy(&host, SymbolNamePolicy::Mangle).unwrap();
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(name, i32::description()),
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("My Layer"), vec![ScriptEdit::SetInputType(FloScriptSymbol::with_name("in"), i32::description())])
+    ]);
+
+    assert!(results == vec![Ok(()), Ok(())]);
+    assert!(host.notebook().receive_output::<i32>(name).is_ok());
+}
+
+#[test]
+fn mangled_names_must_be_distinct() {
+    let host    = GluonScriptHost::new();
+    let literal = FloScriptSymbol::with_name("my_x20_value");
+    let mangled = FloScriptSymbol::with_name("my value");
+
+    set_name_policy(&host, SymbolNamePolicy::Mangle).unwrap();
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetConstant(literal, ScriptValue::I32(1)),
+        ScriptEdit::SetConstant(mangled, ScriptValue::I32(2)),
+        ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("result"), "my_x20_value + 1".to_string())
+    ]);
+
+    assert!(results == vec![Ok(()), Err(FloScriptError::DuplicateScriptName("my_x20_value".to_string())), Ok(())]);
+
+    let mut result = executor::spawn(host.notebook().receive_output::<i32>(FloScriptSymbol::with_name("result")).unwrap());
+    assert!(result.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn cannot_mangle_names_that_would_be_the_same() {
+    let host    = GluonScriptHost::new();
+    set_name_policy(&host, SymbolNamePolicy::Allow).unwrap();
+
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("Width"), i32::description()),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("_Width"), i32::description())
+    ]);
+
+    assert!(results == vec![Ok(()), Ok(())]);
+    assert!(set_name_policy(&host, SymbolNamePolicy::Mangle) == Err(FloScriptError::DuplicateScriptName("_Width".to_string())));
+
+    // The namespace keeps its previous policy, so names aren't mangled
+    let results = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("my value"), i32::description()),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("my_x20_value"), i32::description())
+    ]);
+    assert!(results == vec![Ok(()), Ok(())]);
+}
+
+#[test]
+fn validation_reports_invalid_names() {
+    let host        = GluonScriptHost::new();
+    let symbol      = FloScriptSymbol::with_name("my script");
+    set_name_policy(&host, SymbolNamePolicy::Reject).unwrap();
+
+    let validation  = host.editor().validate(vec![ScriptEdit::SetComputingScript(symbol, "1".to_string())]);
+
+    assert!(validation.diagnostics == vec![(vec![symbol], FloScriptError::InvalidSymbolName("my script".to_string()))]);
+}
+
+#[test]
+fn name_policy_applies_to_existing_namespaces() {
+    let host        = GluonScriptHost::new();
+    let namespace   = FloScriptSymbol::with_name("ns");
+    let child       = FloScriptSymbol::with_name("child");
+
+    apply_edits(&host, vec![ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::WithNamespace(child, vec![])])]);
+    set_name_policy(&host, SymbolNamePolicy::Mangle).unwrap();
+
+    let results     = apply_edits(&host, vec![
+        ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::WithNamespace(child, vec![
+            ScriptEdit::SetConstant(FloScriptSymbol::with_name("my value"), ScriptValue::I32(1)),
+            ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("result"), "my_x20_value + 1".to_string())
+        ])])
+    ]);
+
+    assert!(results == vec![Ok(())]);
+
+    let child_namespace = host.notebook().nested_namespace(&[namespace, child]).unwrap();
+    let mut result      = executor::spawn(child_namespace.receive_output::<i32>(FloScriptSymbol::with_name("result")).unwrap());
+    assert!(result.wait_stream() == Some(Ok(2)));
+}
+
+#[test]
+fn name_policy_is_not_changed_if_a_child_namespace_cannot_use_it() {
+    let host        = GluonScriptHost::new();
+    let namespace   = FloScriptSymbol::with_name("ns");
+
+    set_name_policy(&host, SymbolNamePolicy::Allow).unwrap();
+    apply_edits(&host, vec![ScriptEdit::WithNamespace(namespace, vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("Width"), i32::description()),
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("_Width"), i32::description())
+    ])]);
+
+    assert!(set_name_policy(&host, SymbolNamePolicy::Mangle) == Err(FloScriptError::DuplicateScriptName("_Width".to_string())));
+
+    // Neither namespace changes its policy, so they both still allow any name
+    let results     = apply_edits(&host, vec![
+        ScriptEdit::SetInputType(FloScriptSymbol::with_name("my value"), i32::description()),
+        ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetInputType(FloScriptSymbol::with_name("my value"), i32::description())])
+    ]);
+    assert!(results == vec![Ok(()), Ok(())]);
+}
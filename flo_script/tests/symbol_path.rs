@@ -0,0 +1,122 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn parse_dotted_path() {
+    let path = FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "layers.background.opacity").unwrap();
+
+    assert!(path.namespaces() == [FloScriptSymbol::with_name("layers"), FloScriptSymbol::with_name("background")]);
+    assert!(path.symbol() == FloScriptSymbol::with_name("opacity"));
+}
+
+#[test]
+fn parse_single_symbol() {
+    let path = FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "x").unwrap();
+
+    assert!(path.namespaces().is_empty());
+    assert!(path == FloScriptPath::from(FloScriptSymbol::with_name("x")));
+}
+
+#[test]
+fn cannot_parse_empty_parts() {
+    assert!(FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "layers..opacity") == Err(FloScriptError::InvalidPath("layers..opacity".to_string())));
+    assert!(FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "") == Err(FloScriptError::InvalidPath("".to_string())));
+}
+
+#[test]
+fn display_path() {
+    let path = FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "layers.background.opacity").unwrap();
+
+    assert!(path.to_string() == "layers.background.opacity");
+}
+
+#[test]
+fn path_edit_is_wrapped_in_namespaces() {
+    let path = FloScriptPath::parse(&FloScriptSymbolTable::default_table(), "layers.background.opacity").unwrap();
+    let edit = path.edit(|symbol| ScriptEdit::SetComputingScript(symbol, "1.0".to_string()));
+
+    assert!(edit == ScriptEdit::WithNamespace(FloScriptSymbol::with_name("layers"), vec![
+        ScriptEdit::WithNamespace(FloScriptSymbol::with_name("background"), vec![
+            ScriptEdit::SetComputingScript(FloScriptSymbol::with_name("opacity"), "1.0".to_string())
+        ])
+    ]));
+}
+
+#[test]
+fn editing_path_creates_namespaces() {
+    let host    = GluonScriptHost::new();
+    let path    = FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap();
+
+    host.editor().set_input_type_at::<f64>(&path);
+
+    let layers  = host.notebook().namespace(host.symbols().symbol("layers"));
+    assert!(layers.is_some());
+    assert!(layers.unwrap().namespace(host.symbols().symbol("background")).is_some());
+    assert!(host.notebook().namespace_for_path(&path).is_some());
+}
+
+#[test]
+fn read_and_write_through_path() {
+    let host                = GluonScriptHost::new();
+    let path                = FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap();
+
+    host.editor().set_input_type_at::<f64>(&path);
+    host.notebook().attach_input_at(&path, stream::iter_ok::<_, ()>(vec![0.5])).expect("attaching input");
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_at::<f64>(&path).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(0.5)));
+}
+
+#[test]
+fn constant_at_path() {
+    let host                = GluonScriptHost::new();
+    let path                = FloScriptPath::parse(&host.symbols(), "settings.name").unwrap();
+
+    host.editor().set_constant_at(&path, "Background");
+
+    let settings            = host.notebook().namespace(host.symbols().symbol("settings")).unwrap();
+    let mut output_stream   = executor::spawn(settings.receive_output::<String>(host.symbols().symbol("name")).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok("Background".to_string())));
+}
+
+#[test]
+fn missing_namespace_is_undefined() {
+    let host    = GluonScriptHost::new();
+    let path    = FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap();
+
+    assert!(host.notebook().namespace_for_path(&path).is_none());
+    assert!(host.notebook().receive_output_at::<f64>(&path).err() == Some(FloScriptError::UndefinedSymbol(path.symbol())));
+}
+
+#[test]
+fn script_reads_symbol_at_path() {
+    let host                = GluonScriptHost::new();
+    let path                = FloScriptPath::parse(&host.symbols(), "layers.background.opacity").unwrap();
+    let doubled             = host.symbols().symbol("doubled");
+
+    host.editor().set_input_type_at::<f64>(&path);
+    host.editor().set_computing_script(doubled, "layers.background.opacity * 2.0");
+    host.notebook().attach_input_at(&path, stream::iter_ok::<_, ()>(vec![0.25])).expect("attaching input");
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<f64>(doubled).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(0.5)));
+}
+
+#[test]
+fn script_follows_redefined_symbol_at_path() {
+    let host                = GluonScriptHost::new();
+    let path                = FloScriptPath::parse(&host.symbols(), "settings.size").unwrap();
+    let area                = host.symbols().symbol("area");
+
+    host.editor().set_computing_script_at(&path, "2");
+    host.editor().set_computing_script(area, "settings.size * settings.size");
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output_state::<i64>(area).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(4)));
+
+    host.editor().set_computing_script_at(&path, "3");
+    assert!(output_stream.wait_stream() == Some(Ok(9)));
+}
@@ -0,0 +1,134 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn status_of_each_kind_of_symbol() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+    let z           = FloScriptSymbol::with_name("z");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(y, "x + 1");
+    host.editor().set_constant(z, 42);
+
+    assert!(host.notebook().status(x) == Some(SymbolStatus::WaitingForInput));
+    assert!(host.notebook().status(y) == Some(SymbolStatus::NotStarted));
+    assert!(host.notebook().status(z) == Some(SymbolStatus::UpToDate));
+}
+
+#[test]
+fn undefined_symbol_has_no_status() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    assert!(host.notebook().status(x).is_none());
+    assert!(host.notebook().watch_status(x).err() == Some(FloScriptError::UndefinedSymbol(x)));
+}
+
+#[test]
+fn input_is_finished_once_stream_ends() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+    assert!(host.notebook().status(x) == Some(SymbolStatus::UpToDate));
+
+    let mut output  = executor::spawn(host.notebook().receive_output::<i32>(x).unwrap());
+    assert!(output.wait_stream() == Some(Ok(1)));
+    assert!(output.wait_stream().is_none());
+
+    assert!(host.notebook().status(x) == Some(SymbolStatus::Finished));
+}
+
+#[test]
+fn attaching_wrong_type_leaves_status_alone() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+
+    assert!(host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1.0f64])) == Err(FloScriptError::incorrect_type(&i32::description(), &f64::description())));
+    assert!(host.notebook().status(x) == Some(SymbolStatus::WaitingForInput));
+}
+
+#[test]
+fn watch_status_changes() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+
+    let mut status  = executor::spawn(host.notebook().watch_status(x).unwrap());
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::WaitingForInput)));
+
+    host.notebook().attach_input(x, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::UpToDate)));
+}
+
+#[test]
+fn status_stream_only_returns_latest_status() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(x);
+
+    let mut status  = executor::spawn(host.notebook().watch_status(x).unwrap());
+
+    host.editor().set_computing_script(x, "1");
+    host.editor().set_constant(x, 1);
+
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::UpToDate)));
+}
+
+#[test]
+fn status_stream_finishes_when_symbol_is_undefined() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+
+    host.editor().set_constant(x, 1);
+
+    let mut status  = executor::spawn(host.notebook().watch_status(x).unwrap());
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::UpToDate)));
+
+    host.editor().undefine_symbol(x);
+    assert!(status.wait_stream().is_none());
+}
+
+#[test]
+fn status_follows_moved_symbol() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(x);
+
+    let mut status  = executor::spawn(host.notebook().watch_status(x).unwrap());
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::WaitingForInput)));
+
+    host.editor().rename_symbol(x, y);
+    host.notebook().attach_input(y, stream::iter_ok::<_, ()>(vec![1])).unwrap();
+
+    assert!(host.notebook().status(x).is_none());
+    assert!(status.wait_stream() == Some(Ok(SymbolStatus::UpToDate)));
+}
+
+#[test]
+fn script_in_cycle_has_failed() {
+    let host        = GluonScriptHost::new();
+    let a           = FloScriptSymbol::with_name("a");
+    let b           = FloScriptSymbol::with_name("b");
+
+    host.editor().set_computing_script(a, "b + 1");
+    host.editor().set_computing_script(b, "a + 1");
+
+    let cycle       = vec![FloScriptPath::from(b), FloScriptPath::from(a)];
+
+    assert!(host.notebook().status(a) == Some(SymbolStatus::NotStarted));
+    assert!(host.notebook().status(b) == Some(SymbolStatus::Failed(FloScriptError::DependencyCycle(cycle))));
+}
+
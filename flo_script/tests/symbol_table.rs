@@ -0,0 +1,233 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::executor;
+
+#[test]
+fn same_name_gives_same_symbol() {
+    let table = FloScriptSymbolTable::new();
+
+    assert!(table.symbol("x") == table.symbol("x"));
+    assert!(table.symbol("x") != table.symbol("y"));
+}
+
+#[test]
+fn symbols_from_different_tables_are_distinct() {
+    let table_1 = FloScriptSymbolTable::new();
+    let table_2 = FloScriptSymbolTable::new();
+
+    assert!(table_1.symbol("x") != table_2.symbol("x"));
+    assert!(table_1.symbol("x") != FloScriptSymbol::with_name("x"));
+}
+
+#[test]
+fn table_symbols_have_names() {
+    let table   = FloScriptSymbolTable::new();
+    let symbol  = table.symbol("x");
+
+    assert!(symbol.name() == Some("x".to_string()));
+    assert!(table.name(symbol) == Some("x".to_string()));
+    assert!(table.find("x") == Some(symbol));
+}
+
+#[test]
+fn released_symbol_is_freed() {
+    let table   = FloScriptSymbolTable::new();
+    let symbol  = table.symbol("x");
+
+    assert!(table.len() == 1);
+
+    table.release(symbol);
+
+    assert!(table.is_empty());
+    assert!(table.find("x").is_none());
+    assert!(symbol.name().is_none());
+}
+
+#[test]
+fn symbol_is_kept_until_every_usage_is_released() {
+    let table   = FloScriptSymbolTable::new();
+    let symbol  = table.symbol("x");
+
+    table.retain(symbol);
+    table.release(symbol);
+    assert!(table.find("x") == Some(symbol));
+
+    table.release(symbol);
+    assert!(table.find("x").is_none());
+}
+
+#[test]
+fn names_are_freed_with_table() {
+    let table   = FloScriptSymbolTable::new();
+    let symbol  = table.symbol("x");
+
+    assert!(symbol.name().is_some());
+
+    drop(table);
+    assert!(symbol.name().is_none());
+}
+
+#[test]
+fn names_are_kept_by_their_own_table() {
+    let table_1 = FloScriptSymbolTable::new();
+    let table_2 = FloScriptSymbolTable::new();
+    let x       = table_1.symbol("x");
+    let y       = table_2.symbol("y");
+
+    assert!(table_2.name(x).is_none());
+    assert!(table_1.name(y).is_none());
+
+    drop(table_2);
+    assert!(x.name() == Some("x".to_string()));
+    assert!(y.name().is_none());
+}
+
+#[test]
+fn released_symbol_keeps_its_name_while_it_is_defined() {
+    let table   = FloScriptSymbolTable::new();
+    let host    = GluonScriptHost::with_symbol_table(table.clone());
+    let x       = table.symbol("x");
+    let y       = table.symbol("y");
+
+    host.editor().set_input_type::<i32>(x);
+    host.editor().set_computing_script(y, "x + 1");
+
+    table.release(x);
+    table.release(y);
+
+    // The notebook still refers to both symbols by name
+    assert!(x.name() == Some("x".to_string()));
+    assert!(table.find("y") == Some(y));
+
+    let definition = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+    assert!(definition.symbols == vec![
+        NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() },
+        NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "x + 1".to_string() }
+    ]);
+
+    let mut y_stream = executor::spawn(host.notebook().receive_output::<i32>(y).expect("y stream"));
+    host.notebook().send_input_tick(vec![InputValue::new(x, 41)]).unwrap();
+    assert!(y_stream.wait_stream() == Some(Ok(42)));
+
+    // The names are freed once the symbols are no longer defined
+    host.editor().undefine_symbol(y);
+    host.editor().undefine_symbol(x);
+
+    assert!(x.name().is_none());
+    assert!(table.is_empty());
+}
+
+#[test]
+fn validating_edits_does_not_release_names() {
+    let table   = FloScriptSymbolTable::new();
+    let host    = GluonScriptHost::with_symbol_table(table.clone());
+    let ns      = table.symbol("ns");
+    let x       = table.symbol("x");
+
+    host.editor().edit(ScriptEdit::WithNamespace(ns, vec![ScriptEdit::SetInputType(x, i32::description())]));
+    table.release(ns);
+    table.release(x);
+
+    // Validation works on copies of the namespaces, which must not release the names of the symbols in the original when they're dropped
+    for _ in 0..3 {
+        host.editor().validate(vec![ScriptEdit::WithNamespace(ns, vec![ScriptEdit::UndefineSymbol(x)])]);
+        host.editor().edit(ScriptEdit::WithNamespace(ns, vec![ScriptEdit::SetInputType(x, i32::description())]));
+    }
+
+    assert!(ns.name() == Some("ns".to_string()));
+    assert!(x.name() == Some("x".to_string()));
+}
+
+#[test]
+fn loading_a_definition_does_not_keep_its_names() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::Namespace { name: "ns".to_string(), definition: NotebookDefinition {
+                run_io:     false,
+                symbols:    vec![NotebookSymbolDefinition::Input { name: "x".to_string(), type_name: "i32".to_string() }]
+            } },
+            NotebookSymbolDefinition::ComputingScript { name: "y".to_string(), script: "1".to_string() }
+        ]
+    };
+    let types       = ScriptTypeRegistry::with_standard_types();
+
+    let host        = GluonScriptHost::new();
+    let gluon_host  = GluonScriptHost::new();
+
+    // Loading a definition more than once shouldn't add any more usages to the names
+    for _ in 0..2 {
+        host.editor().load_definition(&definition, &types).unwrap();
+        gluon_host.editor().load_gluon_definition(&definition, &types).unwrap();
+    }
+
+    // The names are only kept for as long as the notebook defines them
+    for host in [host, gluon_host].iter() {
+        assert!(host.symbols().len() == 3);
+
+        host.editor().load_definition(&NotebookDefinition::new(), &types).unwrap();
+        assert!(host.symbols().is_empty());
+    }
+}
+
+#[test]
+fn loaded_definitions_use_the_host_symbol_table() {
+    let definition  = NotebookDefinition {
+        run_io:     false,
+        symbols:    vec![
+            NotebookSymbolDefinition::Namespace { name: "loaded_table_ns".to_string(), definition: NotebookDefinition {
+                run_io:     false,
+                symbols:    vec![NotebookSymbolDefinition::Input { name: "loaded_table_x".to_string(), type_name: "i32".to_string() }]
+            } }
+        ]
+    };
+
+    let host        = GluonScriptHost::new();
+    let gluon_host  = GluonScriptHost::new();
+    host.editor().load_definition(&definition, &ScriptTypeRegistry::with_standard_types()).unwrap();
+    gluon_host.editor().load_gluon_definition(&definition, &ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    // The names are only added to the table of each host
+    assert!(FloScriptSymbolTable::default_table().find("loaded_table_ns").is_none());
+    assert!(FloScriptSymbolTable::default_table().find("loaded_table_x").is_none());
+
+    for host in [host, gluon_host].iter() {
+        let namespace = host.notebook().namespace(host.symbols().symbol("loaded_table_ns")).expect("namespace");
+        assert!(namespace.symbols()[0].symbol() == host.symbols().symbol("loaded_table_x"));
+    }
+}
+
+#[test]
+fn default_table_symbols_are_not_freed() {
+    let symbol  = FloScriptSymbol::with_name("symbol_table_default_test");
+    let table   = FloScriptSymbolTable::default_table();
+
+    assert!(table.find("symbol_table_default_test") == Some(symbol));
+
+    table.release(symbol);
+    table.release(symbol);
+
+    assert!(FloScriptSymbol::with_name("symbol_table_default_test") == symbol);
+    assert!(symbol.name() == Some("symbol_table_default_test".to_string()));
+}
+
+#[test]
+fn hosts_can_share_a_symbol_table() {
+    let table   = FloScriptSymbolTable::new();
+    let host_1  = GluonScriptHost::with_symbol_table(table.clone());
+    let host_2  = GluonScriptHost::with_symbol_table(table.clone());
+
+    assert!(host_1.symbols().symbol("x") == host_2.symbols().symbol("x"));
+    assert!(GluonScriptHost::new().symbols().symbol("x") != table.symbol("x"));
+}
+
+#[test]
+fn parse_path_in_table() {
+    let table   = FloScriptSymbolTable::new();
+    let path    = FloScriptPath::parse(&table, "layers.opacity").unwrap();
+
+    assert!(path.namespaces() == [table.symbol("layers")]);
+    assert!(path.symbol() == table.symbol("opacity"));
+    assert!(path.to_string() == "layers.opacity");
+}
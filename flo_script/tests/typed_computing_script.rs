@@ -0,0 +1,64 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::executor;
+
+#[test]
+fn read_script_with_declared_type() {
+    let host        = GluonScriptHost::new();
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_typed_computing_script::<f64>(y, "1.0 + 2.0");
+
+    let mut output  = executor::spawn(host.notebook().receive_output::<f64>(y).unwrap());
+    assert!(output.wait_stream() == Some(Ok(3.0)));
+}
+
+#[test]
+fn wrongly_typed_script_is_rejected_when_defined() {
+    let host        = GluonScriptHost::new();
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_typed_computing_script::<f64>(y, "\"not a number\"");
+
+    assert!(host.notebook().status(y) == Some(SymbolStatus::Failed(FloScriptError::incorrect_type(&f64::description(), &String::description()))));
+}
+
+#[test]
+fn wrongly_typed_record_script_is_rejected_when_defined() {
+    let host        = GluonScriptHost::new();
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_typed_computing_script::<f64>(y, "{ x = 1.0 }");
+
+    match host.notebook().status(y) {
+        Some(SymbolStatus::Failed(FloScriptError::IncorrectType { expected, .. }))  => assert!(expected == f64::description().to_string()),
+        other                                                                       => panic!("Unexpected status {:?}", other)
+    }
+}
+
+#[test]
+fn reading_script_as_another_type_fails_immediately() {
+    let host        = GluonScriptHost::new();
+    let y           = FloScriptSymbol::with_name("y");
+
+    host.editor().set_typed_computing_script::<f64>(y, "1.0");
+
+    assert!(host.notebook().receive_output::<String>(y).err() == Some(FloScriptError::incorrect_type(&f64::description(), &String::description())));
+    assert!(host.notebook().receive_output::<i64>(y).err() == Some(FloScriptError::incorrect_type(&f64::description(), &i64::description())));
+}
+
+#[test]
+fn reading_untyped_script_as_another_type_describes_both_types() {
+    let host        = GluonScriptHost::new();
+    let x           = FloScriptSymbol::with_name("x");
+    let y           = FloScriptSymbol::with_name("y");
+    let z           = FloScriptSymbol::with_name("z");
+
+    host.editor().set_input_type::<f64>(x);
+    host.editor().set_computing_script(y, "1.5");
+    host.editor().set_computing_script(z, "x + 1.0");
+
+    assert!(host.notebook().receive_output::<String>(y).err() == Some(FloScriptError::incorrect_type(&f64::description(), &String::description())));
+    assert!(host.notebook().receive_output::<String>(z).err() == Some(FloScriptError::incorrect_type(&f64::description(), &String::description())));
+}
@@ -0,0 +1,133 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn validating_does_not_change_notebook() {
+    let host        = GluonScriptHost::new();
+    let input_x     = FloScriptSymbol::with_name("x");
+    let input_y     = FloScriptSymbol::with_name("y");
+    let namespace   = FloScriptSymbol::with_name("ns");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.editor().edit(ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetInputType(input_x, i32::description())]));
+    let definition  = host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap();
+
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::UndefineSymbol(input_x),
+        ScriptEdit::SetInputType(input_y, i32::description()),
+        ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::Clear])
+    ]);
+
+    assert!(validation.is_valid());
+    assert!(host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == definition);
+}
+
+#[test]
+fn report_edits_to_symbols_that_are_not_namespaces() {
+    let host        = GluonScriptHost::new();
+    let input_x     = FloScriptSymbol::with_name("x");
+    let input_y     = FloScriptSymbol::with_name("y");
+
+    host.editor().set_input_type::<i32>(input_x);
+
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::WithNamespace(input_x, vec![ScriptEdit::SetInputType(input_y, i32::description())])
+    ]);
+
+    assert!(!validation.is_valid());
+    assert!(validation.diagnostics == vec![(vec![input_x], FloScriptError::NotANamespace)]);
+}
+
+#[test]
+fn validation_sees_earlier_edits_in_the_batch() {
+    let host        = GluonScriptHost::new();
+    let input_x     = FloScriptSymbol::with_name("x");
+    let input_y     = FloScriptSymbol::with_name("y");
+
+    // 'x' is only an input symbol in the copy of the notebook used for validation
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::SetInputType(input_x, i32::description()),
+        ScriptEdit::WithNamespace(input_x, vec![ScriptEdit::SetInputType(input_y, i32::description())])
+    ]);
+
+    assert!(validation.diagnostics == vec![(vec![input_x], FloScriptError::NotANamespace)]);
+    assert!(host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == NotebookDefinition::new());
+}
+
+#[test]
+fn validating_does_not_change_constants() {
+    let host        = GluonScriptHost::new();
+    let constant    = FloScriptSymbol::with_name("c");
+
+    host.editor().set_constant(constant, 1);
+
+    let validation  = host.editor().validate(vec![ScriptEdit::SetConstant(constant, ScriptValue::I32(2))]);
+    assert!(validation.is_valid());
+
+    let mut reader  = executor::spawn(host.notebook().receive_output_state::<i32>(constant).unwrap());
+    assert!(reader.wait_stream() == Some(Ok(1)));
+}
+
+#[test]
+fn validating_does_not_change_attached_inputs() {
+    let host        = GluonScriptHost::new();
+    let input_x     = FloScriptSymbol::with_name("x");
+
+    host.editor().set_input_type::<i32>(input_x);
+    host.notebook().attach_input(input_x, stream::iter_ok(vec![1])).unwrap();
+
+    let validation  = host.editor().validate(vec![ScriptEdit::SetInputType(input_x, f64::description())]);
+    assert!(validation.is_valid());
+
+    let mut reader  = executor::spawn(host.notebook().receive_output_state::<i32>(input_x).unwrap());
+    assert!(reader.wait_stream() == Some(Ok(1)));
+}
+
+#[test]
+fn report_output_types_of_scripts() {
+    let host        = GluonScriptHost::new();
+    let input_x     = FloScriptSymbol::with_name("x");
+    let output_y    = FloScriptSymbol::with_name("y");
+    let output_z    = FloScriptSymbol::with_name("z");
+
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::SetInputType(input_x, f64::description()),
+        ScriptEdit::SetComputingScript(output_y, "x + 1.0".to_string()),
+        ScriptEdit::SetComputingScript(output_z, "\"text\"".to_string())
+    ]);
+
+    assert!(validation.is_valid());
+    assert!(validation.output_types == vec![(vec![output_y], "Float".to_string()), (vec![output_z], "String".to_string())]);
+}
+
+#[test]
+fn report_scripts_that_do_not_compile() {
+    let host        = GluonScriptHost::new();
+    let output_y    = FloScriptSymbol::with_name("y");
+    let namespace   = FloScriptSymbol::with_name("ns");
+
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::WithNamespace(namespace, vec![ScriptEdit::SetComputingScript(output_y, "1 + \"text\"".to_string())])
+    ]);
+
+    assert!(!validation.is_valid());
+    assert!(validation.output_types.is_empty());
+    assert!(validation.diagnostics.len() == 1);
+    assert!(matches!(&validation.diagnostics[0], (path, FloScriptError::ScriptError(_)) if path == &vec![namespace, output_y]));
+}
+
+#[test]
+fn report_scripts_with_the_wrong_declared_type() {
+    let host        = GluonScriptHost::new();
+    let output_y    = FloScriptSymbol::with_name("y");
+
+    let validation  = host.editor().validate(vec![
+        ScriptEdit::SetTypedComputingScript(output_y, i32::description(), "\"text\"".to_string())
+    ]);
+
+    assert!(validation.diagnostics == vec![(vec![output_y], FloScriptError::incorrect_type(&i32::description(), &String::description()))]);
+    assert!(host.notebook().definition(&ScriptTypeRegistry::with_standard_types()).unwrap() == NotebookDefinition::new());
+}
@@ -0,0 +1,75 @@
+use flo_script::*;
+use flo_script::gluon_host::*;
+
+use futures::stream;
+use futures::executor;
+
+#[test]
+fn read_constant_in_namespace_with_shared_vms() {
+    let host                = GluonScriptHost::new();
+    let namespace           = FloScriptSymbol::with_name("namespace");
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.set_vm_sharing(VmSharing::Shared);
+    host.editor().set_constant_at(&FloScriptPath::new(vec![namespace], x), 42);
+
+    let notebook            = host.notebook().namespace(namespace).expect("namespace");
+    let mut output_stream   = executor::spawn(notebook.receive_output::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn read_constant_with_pooled_vms() {
+    let host                = GluonScriptHost::new();
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.set_vm_sharing(VmSharing::Pooled(4));
+    host.editor().set_constant(x, 42);
+
+    let mut output_stream   = executor::spawn(host.notebook().receive_output::<i32>(x).expect("output stream"));
+    assert!(output_stream.wait_stream() == Some(Ok(42)));
+}
+
+#[test]
+fn evaluate_scripts_in_namespaces_with_shared_vm() {
+    let host                = GluonScriptHost::new();
+    let first               = FloScriptSymbol::with_name("first");
+    let second              = FloScriptSymbol::with_name("second");
+    let x                   = FloScriptSymbol::with_name("x");
+
+    host.set_vm_sharing(VmSharing::Shared);
+    host.editor().set_computing_script_at(&FloScriptPath::new(vec![first], x), "1 + 2");
+    host.editor().set_computing_script_at(&FloScriptPath::new(vec![second], x), "\"Hello\"");
+
+    let first_notebook      = host.notebook().namespace(first).expect("first namespace");
+    let second_notebook     = host.notebook().namespace(second).expect("second namespace");
+    let mut first_stream    = executor::spawn(first_notebook.receive_output::<i64>(x).expect("first stream"));
+    let mut second_stream   = executor::spawn(second_notebook.receive_output::<String>(x).expect("second stream"));
+
+    assert!(first_stream.wait_stream() == Some(Ok(3)));
+    assert!(second_stream.wait_stream() == Some(Ok("Hello".to_string())));
+}
+
+#[test]
+fn evaluate_script_reading_input_with_pooled_vms() {
+    let host                = GluonScriptHost::new();
+    let namespaces          = (0..3).map(|index| FloScriptSymbol::with_name(&format!("ns{}", index))).collect::<Vec<_>>();
+    let x                   = FloScriptSymbol::with_name("x");
+    let y                   = FloScriptSymbol::with_name("y");
+
+    // More namespaces than there are VMs in the pool, so some of them share a VM
+    host.set_vm_sharing(VmSharing::Pooled(2));
+
+    for namespace in namespaces.iter() {
+        host.editor().set_input_type_at::<i32>(&FloScriptPath::new(vec![*namespace], x));
+        host.editor().set_computing_script_at(&FloScriptPath::new(vec![*namespace], y), "x * 2");
+    }
+
+    for (index, namespace) in namespaces.iter().enumerate() {
+        let notebook        = host.notebook().namespace(*namespace).expect("namespace");
+        notebook.attach_input(x, stream::iter_ok::<_, ()>(vec![index as i32])).unwrap();
+
+        let mut output      = executor::spawn(notebook.receive_output::<i32>(y).expect("output stream"));
+        assert!(output.wait_stream() == Some(Ok(index as i32 * 2)));
+    }
+}
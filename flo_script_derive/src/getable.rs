@@ -0,0 +1,103 @@
+use super::gluon_definition::*;
+
+use proc_macro2::{TokenStream};
+use quote::quote;
+use syn::{DataStruct, DataEnum, Fields, Ident};
+
+///
+/// Generates code that retrieves the data from a Gluon value (which is expected to be in the variable `variants`)
+///
+fn unpack_data() -> TokenStream {
+    quote! {
+        let data = match variants.as_ref() {
+            _gluon_api::ValueRef::Data(data)    => data,
+            value                               => panic!("Unexpected value: '{:?}'. Do the type definitions match?", value)
+        };
+    }
+}
+
+///
+/// Generates code that reads the fields of a struct or enum variant by their index in the Gluon data
+///
+fn fields_by_index(fields: &Fields) -> TokenStream {
+    let values = gluon_fields(fields).into_iter().enumerate().map(|(index, (name, field_type))| {
+        let value = quote! {
+            match data.get_variant(#index) {
+                Some(value) => <#field_type as _gluon_api::Getable<'__vm, '__value>>::from_value(vm, value),
+                None        => panic!("Cannot find the field with index '{}'. Do the type definitions match?", #index)
+            }
+        };
+
+        match fields {
+            Fields::Named(_)    => quote! { #name: #value },
+            _                   => value
+        }
+    });
+
+    match fields {
+        Fields::Named(_)    => quote! { { #(#values),* } },
+        Fields::Unnamed(_)  => quote! { ( #(#values),* ) },
+        Fields::Unit        => quote! { }
+    }
+}
+
+///
+/// Generates the body of `Getable::from_value()` for a struct
+///
+pub fn struct_from_value(ident: &Ident, data: &DataStruct) -> TokenStream {
+    if is_newtype(data) {
+        let field_type = &data.fields.iter().next().unwrap().ty;
+        return quote! { #ident(<#field_type as _gluon_api::Getable<'__vm, '__value>>::from_value(vm, variants)) };
+    }
+
+    if let Fields::Named(_) = data.fields {
+        // Records are read by field name, as the fields can be in a different order in Gluon
+        let unpack_data = unpack_data();
+        let fields      = gluon_fields(&data.fields).into_iter().map(|(name, field_type)| {
+            let field_name = name.to_string();
+
+            quote! {
+                #name: match data.lookup_field(vm, #field_name) {
+                    Some(value) => <#field_type as _gluon_api::Getable<'__vm, '__value>>::from_value(vm, value),
+                    None        => panic!("Cannot find the field '{}'. Do the type definitions match?", #field_name)
+                }
+            }
+        });
+
+        return quote! {
+            #unpack_data
+            #ident { #(#fields),* }
+        };
+    }
+
+    let unpack_data = unpack_data();
+    let fields      = fields_by_index(&data.fields);
+
+    quote! {
+        #unpack_data
+        #ident #fields
+    }
+}
+
+///
+/// Generates the body of `Getable::from_value()` for an enum
+///
+pub fn enum_from_value(ident: &Ident, data: &DataEnum) -> TokenStream {
+    let unpack_data = unpack_data();
+    let variants    = data.variants.iter().enumerate().map(|(tag, variant)| {
+        let variant_ident   = &variant.ident;
+        let fields          = fields_by_index(&variant.fields);
+
+        quote! { #tag => #ident::#variant_ident #fields }
+    });
+
+    // The tag is the index of the variant in the enum
+    quote! {
+        #unpack_data
+
+        match data.tag() as usize {
+            #(#variants,)*
+            tag => panic!("Unexpected tag: '{}'. Do the type definitions match?", tag)
+        }
+    }
+}
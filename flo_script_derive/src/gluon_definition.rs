@@ -0,0 +1,78 @@
+use proc_macro2::{TokenStream, Span};
+use quote::quote;
+use syn::{DataStruct, DataEnum, Fields, Ident, Type};
+
+///
+/// Retrieves the names used for a set of fields in Gluon, along with their types
+///
+/// Tuple fields are named after their index, in the same way as Gluon tuples (`_0`, `_1`, etc).
+///
+pub fn gluon_fields(fields: &Fields) -> Vec<(Ident, &Type)> {
+    fields.iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let name = field.ident.clone().unwrap_or_else(|| Ident::new(&format!("_{}", index), Span::call_site()));
+            (name, &field.ty)
+        })
+        .collect()
+}
+
+///
+/// True if a struct has a single unnamed field (in which case it's represented in Gluon using the type of that field)
+///
+pub fn is_newtype(data: &DataStruct) -> bool {
+    match &data.fields {
+        Fields::Unnamed(fields) => fields.unnamed.len() == 1,
+        _                       => false
+    }
+}
+
+///
+/// Generates the Gluon record type for a struct
+///
+pub fn struct_definition(data: &DataStruct) -> TokenStream {
+    if is_newtype(data) {
+        let field_type = &data.fields.iter().next().unwrap().ty;
+        return quote! { <#field_type as _gluon_api::VmType>::make_type(vm) };
+    }
+
+    if let Fields::Unit = data.fields {
+        return quote! { _gluon_base::types::Type::unit() };
+    }
+
+    let fields = gluon_fields(&data.fields).into_iter().map(|(name, field_type)| {
+        let name = name.to_string();
+
+        quote! {
+            _gluon_base::types::Field {
+                name:   _gluon_base::symbol::Symbol::from(#name),
+                typ:    <#field_type as _gluon_api::VmType>::make_type(vm)
+            }
+        }
+    });
+
+    quote! {
+        _gluon_base::types::Type::record(vec![], vec![#(#fields),*])
+    }
+}
+
+///
+/// Generates the Gluon variant type for an enum
+///
+pub fn enum_definition(data: &DataEnum) -> TokenStream {
+    let variants = data.variants.iter().map(|variant| {
+        let name        = variant.ident.to_string();
+        let arguments   = variant.fields.iter().map(|field| {
+            let field_type = &field.ty;
+            quote! { <#field_type as _gluon_api::VmType>::make_type(vm) }
+        });
+
+        quote! {
+            _gluon_base::types::Field::ctor(_gluon_base::symbol::Symbol::from(#name), vec![#(#arguments),*])
+        }
+    });
+
+    quote! {
+        _gluon_base::types::Type::variant(vec![#(#variants),*])
+    }
+}
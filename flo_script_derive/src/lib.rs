@@ -0,0 +1,121 @@
+//!
+//! # flo_script_derive
+//!
+//! Provides `#[derive(ScriptType)]`, which makes a Rust struct or enum usable as the type of an input or output symbol
+//! in a flo_script notebook. The derive generates the Gluon `VmType`, `Getable` and `Pushable` implementations for the
+//! type (which in turn implement `ScriptType`), with structs represented as Gluon records and enums as Gluon variants.
+//!
+//! The corresponding Gluon type is declared by name in a VM the first time the type is used there. Types that scripts refer
+//! to by name before they're used as a symbol type can be declared up front with `GluonScriptHost::declare_type()`.
+//!
+//! ```ignore
+//! #[derive(Clone, ScriptType)]
+//! struct Point {
+//!     x: f64,
+//!     y: f64
+//! }
+//! ```
+//!
+
+extern crate proc_macro;
+
+mod gluon_definition;
+mod getable;
+mod pushable;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Data};
+
+///
+/// Derives the traits needed for a struct or enum to be used as the type of an input or output symbol
+///
+#[proc_macro_derive(ScriptType)]
+pub fn derive_script_type(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match script_type(&input) {
+        Ok(tokens)  => tokens.into(),
+        Err(error)  => error.to_compile_error().into()
+    }
+}
+
+///
+/// Generates the implementation of the script type traits for a type
+///
+fn script_type(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    // Script types must be 'static and have a single Gluon type, so generic types are not supported
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "ScriptType can't be derived for generic types"));
+    }
+
+    let ident       = &input.ident;
+    let gluon_name  = ident.to_string();
+
+    let (definition, from_value, push) = match &input.data {
+        Data::Struct(data)  => (gluon_definition::struct_definition(data), getable::struct_from_value(ident, data), pushable::struct_push(ident, data)),
+        Data::Enum(data)    => (gluon_definition::enum_definition(data), getable::enum_from_value(ident, data), pushable::enum_push(ident, data)),
+        Data::Union(_)      => return Err(syn::Error::new_spanned(input, "ScriptType can't be derived for unions"))
+    };
+
+    Ok(quote! {
+        const _: () = {
+            use ::flo_script::gluon::base as _gluon_base;
+            use ::flo_script::gluon::vm::api as _gluon_api;
+            use ::flo_script::gluon::vm::thread as _gluon_thread;
+            use ::flo_script::gluon::vm::types as _gluon_types;
+            use ::flo_script::gluon::vm::Variants as _GluonVariants;
+            use ::flo_script::gluon::vm::Result as _GluonResult;
+            use ::flo_script::gluon_host as _flo_gluon_host;
+
+            #[automatically_derived]
+            impl _flo_gluon_host::DeclaredScriptType for #ident {
+                fn gluon_name() -> &'static str {
+                    #gluon_name
+                }
+
+                fn gluon_definition(vm: &_gluon_thread::Thread) -> _gluon_base::types::ArcType {
+                    #definition
+                }
+            }
+
+            #[automatically_derived]
+            impl _gluon_api::VmType for #ident {
+                type Type = #ident;
+
+                fn make_type(vm: &_gluon_thread::Thread) -> _gluon_base::types::ArcType {
+                    // If another type already has this name, this type can still be used by its definition (declare_type() reports the clash)
+                    _flo_gluon_host::declare_script_type::<#ident>(vm)
+                        .unwrap_or_else(|_| <#ident as _flo_gluon_host::DeclaredScriptType>::gluon_definition(vm))
+                }
+            }
+
+            #[automatically_derived]
+            #[allow(unused_variables)]
+            impl<'__vm, '__value> _gluon_api::Getable<'__vm, '__value> for #ident {
+                type Proxy = _GluonVariants<'__value>;
+
+                fn to_proxy(vm: &'__vm _gluon_thread::Thread, value: _GluonVariants<'__value>) -> _GluonResult<Self::Proxy> {
+                    Ok(value)
+                }
+
+                fn from_proxy(vm: &'__vm _gluon_thread::Thread, proxy: &'__value mut Self::Proxy) -> Self {
+                    Self::from_value(vm, *proxy)
+                }
+
+                fn from_value(vm: &'__vm _gluon_thread::Thread, variants: _GluonVariants<'__value>) -> Self {
+                    #from_value
+                }
+            }
+
+            #[automatically_derived]
+            #[allow(unused_variables)]
+            impl<'__vm> _gluon_api::Pushable<'__vm> for #ident {
+                fn push(self, ctx: &mut _gluon_thread::ActiveThread<'__vm>) -> _GluonResult<()> {
+                    #push
+                    Ok(())
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,80 @@
+use super::gluon_definition::*;
+
+use proc_macro2::{TokenStream};
+use quote::quote;
+use syn::{DataStruct, DataEnum, Fields, Ident};
+
+///
+/// Generates a pattern that binds the fields of a struct or enum variant to their Gluon names
+///
+fn fields_pattern(fields: &Fields) -> TokenStream {
+    let names = gluon_fields(fields).into_iter().map(|(name, _)| name);
+
+    match fields {
+        Fields::Named(_)    => quote! { { #(#names),* } },
+        Fields::Unnamed(_)  => quote! { ( #(#names),* ) },
+        Fields::Unit        => quote! { }
+    }
+}
+
+///
+/// Generates code that pushes the fields bound by `fields_pattern()` onto the stack
+///
+fn push_fields(fields: &Fields) -> TokenStream {
+    let pushes = gluon_fields(fields).into_iter().map(|(name, field_type)| {
+        quote! { <#field_type as _gluon_api::Pushable<'__vm>>::push(#name, ctx)?; }
+    });
+
+    quote! { #(#pushes)* }
+}
+
+///
+/// Generates the body of `Pushable::push()` for a struct (which is pushed as a record)
+///
+pub fn struct_push(ident: &Ident, data: &DataStruct) -> TokenStream {
+    if is_newtype(data) {
+        let field_type = &data.fields.iter().next().unwrap().ty;
+        return quote! { <#field_type as _gluon_api::Pushable<'__vm>>::push(self.0, ctx)?; };
+    }
+
+    let pattern     = fields_pattern(&data.fields);
+    let push_fields = push_fields(&data.fields);
+    let num_fields  = data.fields.len();
+    let field_names = gluon_fields(&data.fields).into_iter().map(|(name, _)| name.to_string());
+
+    quote! {
+        let #ident #pattern = self;
+        #push_fields
+
+        let vm          = ctx.thread();
+        let field_names = [#(vm.global_env().intern(#field_names)?),*];
+        ctx.context().push_new_record(vm, #num_fields, &field_names)?;
+    }
+}
+
+///
+/// Generates the body of `Pushable::push()` for an enum (which is pushed as data tagged with the index of the variant)
+///
+pub fn enum_push(ident: &Ident, data: &DataEnum) -> TokenStream {
+    let variants = data.variants.iter().enumerate().map(|(tag, variant)| {
+        let variant_ident   = &variant.ident;
+        let pattern         = fields_pattern(&variant.fields);
+        let push_fields     = push_fields(&variant.fields);
+        let num_fields      = variant.fields.len();
+
+        quote! {
+            #ident::#variant_ident #pattern => {
+                #push_fields
+
+                let vm = ctx.thread();
+                ctx.context().push_new_data(vm, #tag as _gluon_types::VmTag, #num_fields)?;
+            }
+        }
+    });
+
+    quote! {
+        match self {
+            #(#variants),*
+        }
+    }
+}